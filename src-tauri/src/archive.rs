@@ -0,0 +1,365 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager, State};
+use tokio::task;
+
+use crate::backend::app_server::now_millis;
+use crate::backend::events::AppServerEvent;
+use crate::backend::protocol::{parse_result, ThreadResumeParams};
+use crate::state::AppState;
+use crate::storage::write_json_file;
+use crate::thread_export::{render_thread, searchable_text, thread_title, write_rendered};
+use crate::utils::file_safe_name;
+
+/// Items kept per thread in the in-memory journal.
+const MAX_JOURNAL_ITEMS: usize = 2000;
+/// Threads kept in the journal; the least recently active is dropped first.
+const MAX_JOURNAL_THREADS: usize = 32;
+
+/// A thread kept locally after `archive_thread`. `thread` has the same
+/// `turns[].items[]` shape as a `thread/resume` result, so it can be
+/// searched and exported without the backend.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct ArchivedThread {
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    #[serde(rename = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) preview: String,
+    #[serde(rename = "archivedAt")]
+    pub(crate) archived_at: u64,
+    pub(crate) thread: Value,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct ArchivedThreadSummary {
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    #[serde(rename = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) preview: String,
+    #[serde(rename = "archivedAt")]
+    pub(crate) archived_at: u64,
+}
+
+#[derive(Default)]
+struct JournalThread {
+    /// `(turn id, items)` in the order turns were first seen.
+    turns: Vec<(String, Vec<Value>)>,
+    items: usize,
+    /// Journal clock value of the last recorded item.
+    last_active: u64,
+}
+
+/// Completed items seen on the event stream this session, for the most
+/// recently active threads. Used when the backend can no longer resume a
+/// thread being archived.
+#[derive(Default)]
+pub(crate) struct ThreadJournal {
+    threads: HashMap<String, JournalThread>,
+    clock: u64,
+}
+
+impl ThreadJournal {
+    fn record(&mut self, message: &Value) {
+        if message.get("method").and_then(Value::as_str) != Some("item/completed") {
+            return;
+        }
+        let Some(params) = message.get("params") else {
+            return;
+        };
+        let read = |camel: &str, snake: &str| {
+            params
+                .get(camel)
+                .or_else(|| params.get(snake))
+                .and_then(Value::as_str)
+                .unwrap_or("")
+                .to_string()
+        };
+        let thread_id = read("threadId", "thread_id");
+        let Some(item) = params.get("item").filter(|_| !thread_id.is_empty()) else {
+            return;
+        };
+        let turn_id = read("turnId", "turn_id");
+        if !self.threads.contains_key(&thread_id) && self.threads.len() >= MAX_JOURNAL_THREADS {
+            self.evict_least_recent();
+        }
+        self.clock += 1;
+        let thread = self.threads.entry(thread_id).or_default();
+        thread.last_active = self.clock;
+        if thread.items >= MAX_JOURNAL_ITEMS {
+            return;
+        }
+        thread.items += 1;
+        match thread.turns.iter_mut().find(|(id, _)| *id == turn_id) {
+            Some((_, items)) => items.push(item.clone()),
+            None => thread.turns.push((turn_id, vec![item.clone()])),
+        }
+    }
+
+    /// Builds a `thread/resume`-shaped thread from the journal.
+    fn thread(&self, thread_id: &str) -> Option<Value> {
+        let journal = self.threads.get(thread_id)?;
+        let turns: Vec<Value> = journal
+            .turns
+            .iter()
+            .map(|(id, items)| json!({ "id": id, "items": items }))
+            .collect();
+        let preview = journal
+            .turns
+            .iter()
+            .flat_map(|(_, items)| items)
+            .find(|item| item.get("type").and_then(Value::as_str) == Some("userMessage"))
+            .and_then(|item| item.get("content"))
+            .and_then(Value::as_array)
+            .and_then(|content| {
+                content
+                    .iter()
+                    .find_map(|input| input.get("text").and_then(Value::as_str))
+            })
+            .unwrap_or("");
+        Some(json!({ "id": thread_id, "preview": preview, "turns": turns }))
+    }
+
+    fn forget(&mut self, thread_id: &str) {
+        self.threads.remove(thread_id);
+    }
+
+    fn evict_least_recent(&mut self) {
+        let oldest = self
+            .threads
+            .iter()
+            .min_by_key(|(_, thread)| thread.last_active)
+            .map(|(id, _)| id.clone());
+        if let Some(oldest) = oldest {
+            self.threads.remove(&oldest);
+        }
+    }
+}
+
+pub(crate) fn observe_event(app: &AppHandle, event: &AppServerEvent) {
+    if event.message.get("method").and_then(Value::as_str) != Some("item/completed") {
+        return;
+    }
+    app.state::<AppState>()
+        .thread_journal
+        .lock()
+        .unwrap()
+        .record(&event.message);
+}
+
+fn archive_file(dir: &Path, thread_id: &str) -> PathBuf {
+    dir.join(format!("{}.json", file_safe_name(thread_id)))
+}
+
+fn read_archive(path: &Path) -> Option<ArchivedThread> {
+    let data = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+/// Archived threads, newest first. A non-empty `query` keeps threads whose
+/// preview or transcript contains every whitespace-separated term.
+fn list_archive(dir: &Path, query: &str) -> Vec<ArchivedThreadSummary> {
+    let terms: Vec<String> = query
+        .to_lowercase()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    let mut threads: Vec<ArchivedThreadSummary> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| read_archive(&entry.path()))
+        .filter(|archived| {
+            if terms.is_empty() {
+                return true;
+            }
+            let haystack = format!(
+                "{}\n{}",
+                archived.preview,
+                searchable_text(&archived.thread)
+            )
+            .to_lowercase();
+            terms.iter().all(|term| haystack.contains(term.as_str()))
+        })
+        .map(|archived| ArchivedThreadSummary {
+            workspace_id: archived.workspace_id,
+            thread_id: archived.thread_id,
+            preview: archived.preview,
+            archived_at: archived.archived_at,
+        })
+        .collect();
+    threads.sort_by_key(|thread| Reverse(thread.archived_at));
+    threads
+}
+
+/// Saves a local copy of a thread before the backend archives it. Prefers
+/// the full `thread/resume` result and falls back to the event journal.
+/// Does nothing when neither has anything for the thread; errors only when
+/// the copy could not be written.
+pub(crate) async fn retain_thread(
+    state: &AppState,
+    workspace_id: &str,
+    thread_id: &str,
+) -> Result<(), String> {
+    let session = state.sessions.lock().await.get(workspace_id).cloned();
+    let mut thread = None;
+    if let Some(session) = session {
+        let params = ThreadResumeParams {
+            thread_id: thread_id.to_string(),
+        };
+        if let Ok(response) = session.request(&params).await {
+            thread = parse_result::<ThreadResumeParams>(&response)
+                .ok()
+                .map(|result| result.thread);
+        }
+    }
+    let journal_thread = state.thread_journal.lock().unwrap().thread(thread_id);
+    let Some(thread) = thread.or(journal_thread) else {
+        return Ok(());
+    };
+    let archived = ArchivedThread {
+        workspace_id: workspace_id.to_string(),
+        thread_id: thread_id.to_string(),
+        preview: thread_title(&thread, thread_id),
+        archived_at: now_millis(),
+        thread,
+    };
+    let value = serde_json::to_value(&archived).map_err(|e| e.to_string())?;
+    write_json_file(&archive_file(&state.archive_dir, thread_id), &value)?;
+    state.thread_journal.lock().unwrap().forget(thread_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn list_archived_threads(
+    query: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ArchivedThreadSummary>, String> {
+    let dir = state.archive_dir.clone();
+    let query = query.unwrap_or_default();
+    task::spawn_blocking(move || list_archive(&dir, &query))
+        .await
+        .map_err(|_| "archive listing failed".to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn export_archived_thread(
+    thread_id: String,
+    format: String,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let archived =
+        read_archive(&archive_file(&state.archive_dir, &thread_id)).ok_or("thread not archived")?;
+    write_rendered(render_thread(&archived.thread, &thread_id, &format)?, path)
+}
+
+#[tauri::command]
+pub(crate) async fn delete_archived_thread(
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let path = archive_file(&state.archive_dir, &thread_id);
+    if !path.exists() {
+        return Err("thread not archived".to_string());
+    }
+    std::fs::remove_file(path).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{archive_file, list_archive, ArchivedThread, ThreadJournal, MAX_JOURNAL_THREADS};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn completed(thread_id: &str, turn_id: &str, item: serde_json::Value) -> serde_json::Value {
+        json!({
+            "method": "item/completed",
+            "params": { "threadId": thread_id, "turnId": turn_id, "item": item }
+        })
+    }
+
+    #[test]
+    fn journal_rebuilds_thread_in_turn_order() {
+        let mut journal = ThreadJournal::default();
+        journal.record(&completed(
+            "t1",
+            "turn-1",
+            json!({ "type": "userMessage", "content": [{ "type": "text", "text": "Bump serde" }] }),
+        ));
+        journal.record(&completed(
+            "t1",
+            "turn-1",
+            json!({ "type": "agentMessage", "text": "Bumped." }),
+        ));
+        journal.record(&completed(
+            "t1",
+            "turn-2",
+            json!({ "type": "agentMessage", "text": "Tests pass." }),
+        ));
+        journal.record(&json!({ "method": "turn/completed", "params": { "threadId": "t1" } }));
+
+        let thread = journal.thread("t1").expect("thread");
+        assert_eq!(thread["preview"], "Bump serde");
+        assert_eq!(thread["turns"][0]["items"].as_array().unwrap().len(), 2);
+        assert_eq!(thread["turns"][1]["id"], "turn-2");
+        journal.forget("t1");
+        assert!(journal.thread("t1").is_none());
+    }
+
+    #[test]
+    fn journal_drops_least_recently_active_thread() {
+        let mut journal = ThreadJournal::default();
+        let item = || json!({ "type": "agentMessage", "text": "ok" });
+        for index in 0..MAX_JOURNAL_THREADS {
+            journal.record(&completed(&format!("t{index}"), "turn-1", item()));
+        }
+        journal.record(&completed("t0", "turn-2", item()));
+        journal.record(&completed("new", "turn-1", item()));
+
+        assert_eq!(journal.threads.len(), MAX_JOURNAL_THREADS);
+        assert!(journal.thread("t0").is_some());
+        assert!(journal.thread("t1").is_none());
+        assert!(journal.thread("new").is_some());
+    }
+
+    #[test]
+    fn list_archive_filters_by_transcript_terms() {
+        let dir = std::env::temp_dir().join(format!("codex-monitor-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        for (thread_id, archived_at, text) in [
+            ("t/1", 1, "Upgrade tokio to 1.40"),
+            ("t2", 2, "Fix flaky login test"),
+        ] {
+            let archived = ArchivedThread {
+                workspace_id: "ws".to_string(),
+                thread_id: thread_id.to_string(),
+                preview: text.to_string(),
+                archived_at,
+                thread: json!({ "turns": [{ "items": [
+                    { "type": "agentMessage", "text": format!("done: {text}") }
+                ] }] }),
+            };
+            let data = serde_json::to_string(&archived).unwrap();
+            std::fs::write(archive_file(&dir, thread_id), data).expect("write archive");
+        }
+        assert!(archive_file(&dir, "t/1").ends_with("t_1.json"));
+
+        let all = list_archive(&dir, "");
+        assert_eq!(
+            all.iter().map(|t| t.thread_id.as_str()).collect::<Vec<_>>(),
+            vec!["t2", "t/1"]
+        );
+        let hits = list_archive(&dir, "DONE tokio");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].thread_id, "t/1");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,126 @@
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// Largest single text file inlined into a message.
+pub(crate) const MAX_FILE_BYTES: u64 = 256 * 1024;
+/// Cap on all inlined text per message, so a drag of a whole folder can't
+/// blow through the model's context.
+pub(crate) const MAX_TOTAL_BYTES: u64 = 1024 * 1024;
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp"];
+
+pub(crate) fn is_image_path(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Input item for an image given as a data/http URL or a local path.
+pub(crate) fn image_input(path: &str) -> Value {
+    if path.starts_with("data:") || path.starts_with("http://") || path.starts_with("https://") {
+        json!({ "type": "image", "url": path })
+    } else {
+        json!({ "type": "localImage", "path": path })
+    }
+}
+
+fn text_file_input(path: &str, contents: &str) -> Value {
+    let body = contents.strip_suffix('\n').unwrap_or(contents);
+    json!({
+        "type": "text",
+        "text": format!("<attached_file path=\"{path}\">\n{body}\n</attached_file>")
+    })
+}
+
+/// Turns composer attachments into `turn/start` input items. Images are
+/// passed by path; anything else must be a UTF-8 text file within the size
+/// limits and is inlined.
+pub(crate) fn attachment_inputs(paths: &[String]) -> Result<Vec<Value>, String> {
+    let mut inputs = Vec::new();
+    let mut total_bytes = 0u64;
+    for path in paths {
+        let path = path.trim();
+        if path.is_empty() {
+            continue;
+        }
+        if path.starts_with("data:") || is_image_path(path) {
+            inputs.push(image_input(path));
+            continue;
+        }
+        let size = std::fs::metadata(path)
+            .map_err(|err| format!("Failed to read attachment {path}: {err}"))?
+            .len();
+        if size > MAX_FILE_BYTES {
+            return Err(format!(
+                "Attachment {path} is too large ({size} bytes, limit {MAX_FILE_BYTES})"
+            ));
+        }
+        total_bytes += size;
+        if total_bytes > MAX_TOTAL_BYTES {
+            return Err(format!(
+                "Attachments exceed {MAX_TOTAL_BYTES} bytes of inlined text"
+            ));
+        }
+        let bytes = std::fs::read(path)
+            .map_err(|err| format!("Failed to read attachment {path}: {err}"))?;
+        if bytes.contains(&0) {
+            return Err(format!("Attachment {path} is not a text file"));
+        }
+        let contents = String::from_utf8(bytes)
+            .map_err(|_| format!("Attachment {path} is not a text file"))?;
+        inputs.push(text_file_input(path, &contents));
+    }
+    Ok(inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{attachment_inputs, MAX_FILE_BYTES};
+    use uuid::Uuid;
+
+    #[test]
+    fn attachment_inputs_inlines_text_and_passes_images() {
+        let dir = std::env::temp_dir().join(format!("codex-monitor-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let notes = dir.join("notes.md");
+        std::fs::write(&notes, "# Notes\nship it\n").expect("write notes");
+        let notes = notes.to_string_lossy().to_string();
+
+        let inputs = attachment_inputs(&[
+            notes.clone(),
+            "  ".to_string(),
+            "/tmp/Screenshot.PNG".to_string(),
+        ])
+        .expect("inputs");
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(
+            inputs[0]["text"].as_str().unwrap(),
+            format!("<attached_file path=\"{notes}\">\n# Notes\nship it\n</attached_file>")
+        );
+        assert_eq!(inputs[1]["type"], "localImage");
+        assert_eq!(inputs[1]["path"], "/tmp/Screenshot.PNG");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn attachment_inputs_rejects_binary_and_oversized_files() {
+        let dir = std::env::temp_dir().join(format!("codex-monitor-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let binary = dir.join("blob.bin");
+        std::fs::write(&binary, [0u8, 159, 146, 150]).expect("write blob");
+        let large = dir.join("large.txt");
+        std::fs::write(&large, "a".repeat(MAX_FILE_BYTES as usize + 1)).expect("write large");
+
+        let error = attachment_inputs(&[binary.to_string_lossy().to_string()]).unwrap_err();
+        assert!(error.contains("not a text file"));
+        let error = attachment_inputs(&[large.to_string_lossy().to_string()]).unwrap_err();
+        assert!(error.contains("too large"));
+        assert!(
+            attachment_inputs(&[dir.join("missing.txt").to_string_lossy().to_string()]).is_err()
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,30 +1,564 @@
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::env;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::{Child, ChildStdin, Command};
+use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command};
 use tokio::sync::{oneshot, Mutex};
-use tokio::time::timeout;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout};
 
 use crate::backend::events::{AppServerEvent, EventSink};
-use crate::types::WorkspaceEntry;
+use crate::backend::protocol::{parse_result, ClientRequest, TurnInterruptParams};
+use crate::backend::remote::{build_ssh_command, PathMap};
+use crate::backend::request_queue::{RequestPriority, RequestQueue, RequestQueueMetrics};
+use crate::backend::server_requests::{ServerRequest, ServerRequestRegistry};
+use crate::types::{AppSettings, WorkspaceEntry};
+
+const INITIALIZE_TIMEOUT: Duration = Duration::from_secs(15);
+/// How often startup checks whether the child already exited.
+const STARTUP_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Stderr lines from a failed start kept in the error and event.
+const STARTUP_STDERR_LINES: usize = 20;
+pub(crate) const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 120;
+const LOG_BUFFER_LINES: usize = 2000;
+/// Log lines returned when the caller doesn't ask for a count.
+pub(crate) const DEFAULT_BACKEND_LOG_LINES: usize = 200;
+const LOG_LINE_MAX_CHARS: usize = 4000;
+const EVENT_BUFFER_LEN: usize = 1000;
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+const UNRESPONSIVE_AFTER: Duration = Duration::from_secs(30);
+const RESTART_BACKOFF_INITIAL: Duration = Duration::from_secs(1);
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(30);
+const MAX_RESTART_ATTEMPTS: u32 = 6;
+const MAX_CODEX_ARGS: usize = 32;
+const VERBOSITY_LEVELS: [&str; 3] = ["low", "medium", "high"];
+/// Item types the UI renders natively; others fall back to a generic row.
+const SUPPORTED_ITEM_TYPES: &[&str] = &[
+    "userMessage",
+    "agentMessage",
+    "reasoning",
+    "commandExecution",
+    "fileChange",
+    "mcpToolCall",
+    "collabToolCall",
+    "collabAgentToolCall",
+    "webSearch",
+    "imageView",
+    "enteredReviewMode",
+    "exitedReviewMode",
+];
+/// Streaming notifications the UI renders incrementally.
+const STREAMED_DELTAS: &[&str] = &[
+    "item/agentMessage/delta",
+    "item/reasoning/summaryTextDelta",
+    "item/reasoning/textDelta",
+    "item/commandExecution/outputDelta",
+    "item/fileChange/outputDelta",
+];
+/// Server requests CodexMonitor answers.
+const APPROVAL_METHODS: &[&str] = &[
+    "item/commandExecution/requestApproval",
+    "item/fileChange/requestApproval",
+    "execCommandApproval",
+    "applyPatchApproval",
+];
+/// JSON-RPC error code for requests cut off by an app-server restart.
+pub(crate) const BACKEND_RESTARTED_CODE: i64 = -32001;
+/// JSON-RPC error code for a `turn/start` on a thread already running one.
+pub(crate) const TURN_IN_PROGRESS_CODE: i64 = -32002;
+/// JSON-RPC error code for requests withdrawn with `$/cancelRequest`.
+pub(crate) const REQUEST_CANCELLED_CODE: i64 = -32800;
+const CANCEL_REQUEST_METHOD: &str = "$/cancelRequest";
+/// How often an interrupted read is re-issued before giving up.
+const MAX_REISSUE_ATTEMPTS: u32 = 2;
+/// Longest wait for a restarted app-server before re-issuing a read. Kept
+/// well below the request timeout; a slow restart just returns the "backend
+/// restarted" error instead of stalling the caller.
+const REISSUE_WAIT_MAX: Duration = Duration::from_secs(10);
+/// Read-only methods that are safe to send again after a restart.
+const IDEMPOTENT_METHODS: &[&str] = &[
+    "thread/list",
+    "thread/resume",
+    "model/list",
+    "collaborationMode/list",
+    "account/rateLimits/read",
+    "skills/list",
+];
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct BackendLogEntry {
+    pub(crate) timestamp: u64,
+    pub(crate) stream: String,
+    pub(crate) line: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SessionStatus {
+    Starting,
+    Connected,
+    Unresponsive,
+    Dead,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct SessionStats {
+    pub(crate) pid: Option<u32>,
+    #[serde(rename = "cpuPercent")]
+    pub(crate) cpu_percent: Option<f64>,
+    #[serde(rename = "rssBytes")]
+    pub(crate) rss_bytes: Option<u64>,
+    #[serde(rename = "uptimeSecs")]
+    pub(crate) uptime_secs: u64,
+    #[serde(rename = "restartCount")]
+    pub(crate) restart_count: u32,
+    pub(crate) status: SessionStatus,
+}
+
+/// JSON-RPC "method not found".
+const METHOD_NOT_FOUND_CODE: i64 = -32601;
+/// Optional methods that are only sent when the server advertises them (if
+/// it advertises anything at all).
+const FEATURE_METHODS: &[&str] = &[
+    "collaborationMode/list",
+    "skills/list",
+    "account/rateLimits/read",
+    "review/start",
+];
+
+/// What the connected app-server can do, from its `initialize` response plus
+/// any "method not found" errors seen since.
+#[derive(Debug, Serialize, Clone, Default)]
+pub(crate) struct BackendCapabilities {
+    #[serde(rename = "userAgent")]
+    pub(crate) user_agent: Option<String>,
+    /// Methods listed under `capabilities.methods`, if the server sends it.
+    pub(crate) methods: Option<Vec<String>>,
+    pub(crate) unsupported: Vec<String>,
+    /// Raw `initialize` result.
+    pub(crate) initialize: Value,
+}
+
+impl BackendCapabilities {
+    fn from_initialize(result: &Value) -> Self {
+        let methods = result
+            .get("capabilities")
+            .and_then(|capabilities| capabilities.get("methods"))
+            .and_then(Value::as_array)
+            .map(|methods| {
+                methods
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            });
+        Self {
+            user_agent: result
+                .get("userAgent")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            methods,
+            unsupported: Vec::new(),
+            initialize: result.clone(),
+        }
+    }
+
+    pub(crate) fn supports(&self, method: &str) -> bool {
+        if self.unsupported.iter().any(|entry| entry == method) {
+            return false;
+        }
+        match &self.methods {
+            Some(methods) if FEATURE_METHODS.contains(&method) => {
+                methods.iter().any(|entry| entry == method)
+            }
+            _ => true,
+        }
+    }
+
+    /// Whether `initialize` listed `method`. Unlike `supports`, a server that
+    /// sends no method list advertises nothing.
+    pub(crate) fn advertises(&self, method: &str) -> bool {
+        self.methods
+            .as_ref()
+            .is_some_and(|methods| methods.iter().any(|entry| entry == method))
+    }
+
+    fn note_response(&mut self, method: &str, response: &Value) {
+        let code = response
+            .get("error")
+            .and_then(|error| error.get("code"))
+            .and_then(Value::as_i64);
+        if code == Some(METHOD_NOT_FOUND_CODE) && self.supports(method) {
+            self.unsupported.push(method.to_string());
+        }
+    }
+}
+
+/// An in-flight request, kept with its method so a restart can answer it.
+pub(crate) struct PendingRequest {
+    pub(crate) method: String,
+    pub(crate) tx: oneshot::Sender<Value>,
+}
 
 pub(crate) struct WorkspaceSession {
     pub(crate) entry: WorkspaceEntry,
     pub(crate) child: Mutex<Child>,
     pub(crate) stdin: Mutex<ChildStdin>,
-    pub(crate) pending: Mutex<HashMap<u64, oneshot::Sender<Value>>>,
+    pub(crate) pending: Mutex<HashMap<u64, PendingRequest>>,
     pub(crate) next_id: AtomicU64,
+    pub(crate) closed: AtomicBool,
+    pub(crate) restart_count: AtomicU32,
+    /// Per-request response deadline in seconds; `0` waits indefinitely.
+    pub(crate) request_timeout_secs: AtomicU64,
+    pub(crate) request_queue: RequestQueue,
+    pub(crate) logs: Mutex<VecDeque<BackendLogEntry>>,
+    pub(crate) events: Mutex<VecDeque<AppServerEvent>>,
+    pub(crate) next_event_seq: AtomicU64,
+    pub(crate) status: Mutex<SessionStatus>,
+    /// Milliseconds since the epoch of the last line read from stdout.
+    pub(crate) last_output_at: AtomicU64,
+    /// Milliseconds since the epoch when the current child was spawned.
+    pub(crate) started_at: AtomicU64,
+    pub(crate) capabilities: Mutex<BackendCapabilities>,
+    /// Turn id -> thread id for turns that have started but not finished.
+    pub(crate) active_turns: Mutex<HashMap<String, String>>,
+    /// Server-initiated requests (approvals) still waiting for an answer.
+    pub(crate) server_requests: Mutex<ServerRequestRegistry>,
+    /// Local <-> remote path rewriting for SSH workspaces.
+    pub(crate) path_map: Option<PathMap>,
+}
+
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Keeps `active` in step with `turn/started`, `turn/completed`, and
+/// terminal `error` notifications.
+fn track_turn(active: &mut HashMap<String, String>, message: &Value) {
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let turn = params.get("turn").unwrap_or(&Value::Null);
+    let turn_id = turn.get("id").and_then(Value::as_str);
+    let thread_id = turn
+        .get("threadId")
+        .or_else(|| turn.get("thread_id"))
+        .or_else(|| params.get("threadId"))
+        .or_else(|| params.get("thread_id"))
+        .and_then(Value::as_str);
+    match message.get("method").and_then(Value::as_str) {
+        Some("turn/started") => {
+            if let (Some(turn_id), Some(thread_id)) = (turn_id, thread_id) {
+                active.insert(turn_id.to_string(), thread_id.to_string());
+            }
+        }
+        Some("turn/completed") => {
+            if let Some(turn_id) = turn_id {
+                active.remove(turn_id);
+            }
+        }
+        Some("error") => {
+            let will_retry = params
+                .get("willRetry")
+                .or_else(|| params.get("will_retry"))
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            if let (false, Some(thread_id)) = (will_retry, thread_id) {
+                active.retain(|_, active_thread| active_thread != thread_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Error response handed to callers whose request was in flight when the
+/// app-server went away.
+fn backend_restarted_response(id: u64, method: &str) -> Value {
+    json!({
+        "id": id,
+        "error": {
+            "code": BACKEND_RESTARTED_CODE,
+            "message": format!("Codex app-server restarted before `{method}` completed."),
+            "data": { "reason": "backendRestarted", "method": method },
+        }
+    })
+}
+
+/// Error response handed to callers whose request was cancelled.
+fn request_cancelled_response(id: u64, method: &str) -> Value {
+    json!({
+        "id": id,
+        "error": {
+            "code": REQUEST_CANCELLED_CODE,
+            "message": format!("`{method}` was cancelled."),
+            "data": { "reason": "requestCancelled", "method": method },
+        }
+    })
+}
+
+/// Error response for a turn that was not started because `turn_id` is
+/// still running on the thread.
+fn turn_in_progress_response(thread_id: &str, turn_id: &str) -> Value {
+    json!({
+        "error": {
+            "code": TURN_IN_PROGRESS_CODE,
+            "message": format!("A turn is already running on this thread ({turn_id})."),
+            "data": { "reason": "turnInProgress", "threadId": thread_id, "turnId": turn_id },
+        }
+    })
+}
+
+pub(crate) fn is_backend_restarted(response: &Value) -> bool {
+    response
+        .get("error")
+        .and_then(|error| error.get("code"))
+        .and_then(Value::as_i64)
+        == Some(BACKEND_RESTARTED_CODE)
 }
 
 impl WorkspaceSession {
-    async fn write_message(&self, value: Value) -> Result<(), String> {
+    /// Kills the app-server and stops the monitor from respawning it.
+    pub(crate) async fn terminate(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        *self.status.lock().await = SessionStatus::Dead;
+        let mut child = self.child.lock().await;
+        let _ = child.kill().await;
+    }
+
+    /// Asks the app-server process group to exit, waits up to `grace`, then
+    /// kills whatever is left. Used when the app quits.
+    pub(crate) async fn shutdown(&self, grace: Duration) {
+        self.closed.store(true, Ordering::SeqCst);
+        *self.status.lock().await = SessionStatus::Dead;
+        let mut child = self.child.lock().await;
+        #[cfg(unix)]
+        let pid = child.id();
+        #[cfg(unix)]
+        if let Some(pid) = pid {
+            signal_process_group(pid, libc::SIGTERM);
+        }
+        if timeout(grace, child.wait()).await.is_err() {
+            // The child is not reaped yet, so its pid still names our group.
+            #[cfg(unix)]
+            if let Some(pid) = pid {
+                signal_process_group(pid, libc::SIGKILL);
+            }
+            let _ = child.kill().await;
+        }
+    }
+
+    /// Sends `turn/interrupt` for every running turn. Returns the turns that
+    /// were interrupted and any that failed.
+    pub(crate) async fn interrupt_all(&self) -> Value {
+        let turns: Vec<(String, String)> = self
+            .active_turns
+            .lock()
+            .await
+            .iter()
+            .map(|(turn_id, thread_id)| (turn_id.clone(), thread_id.clone()))
+            .collect();
+        let mut interrupted = Vec::new();
+        let mut errors = Vec::new();
+        for (turn_id, thread_id) in turns {
+            let result = self
+                .request(&TurnInterruptParams {
+                    thread_id: thread_id.clone(),
+                    turn_id: turn_id.clone(),
+                })
+                .await;
+            let error = result
+                .and_then(|response| parse_result::<TurnInterruptParams>(&response))
+                .err();
+            match error {
+                None => interrupted.push(json!({ "threadId": thread_id, "turnId": turn_id })),
+                Some(error) => errors.push(json!({
+                    "threadId": thread_id,
+                    "turnId": turn_id,
+                    "error": error,
+                })),
+            }
+        }
+        json!({ "interrupted": interrupted, "errors": errors })
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    async fn record_log(&self, stream: &str, line: &str) {
+        let line = match line.char_indices().nth(LOG_LINE_MAX_CHARS) {
+            Some((index, _)) => format!("{}…", &line[..index]),
+            None => line.to_string(),
+        };
+        let timestamp = now_millis();
+        let mut logs = self.logs.lock().await;
+        if logs.len() >= LOG_BUFFER_LINES {
+            logs.pop_front();
+        }
+        logs.push_back(BackendLogEntry {
+            timestamp,
+            stream: stream.to_string(),
+            line,
+        });
+    }
+
+    /// Reports the current app-server child's PID, CPU, memory, and uptime.
+    pub(crate) async fn stats(&self) -> SessionStats {
+        let pid = self.child.lock().await.id();
+        let (cpu_percent, rss_bytes) = match pid {
+            Some(pid) => probe_process(pid).await.unzip(),
+            None => (None, None),
+        };
+        let started_at = self.started_at.load(Ordering::SeqCst);
+        SessionStats {
+            pid,
+            cpu_percent,
+            rss_bytes,
+            uptime_secs: now_millis().saturating_sub(started_at) / 1000,
+            restart_count: self.restart_count.load(Ordering::SeqCst),
+            status: *self.status.lock().await,
+        }
+    }
+
+    /// Returns up to `lines` of the most recent backend output, oldest first.
+    pub(crate) async fn recent_logs(&self, lines: usize) -> Vec<BackendLogEntry> {
+        let logs = self.logs.lock().await;
+        let skip = logs.len().saturating_sub(lines);
+        logs.iter().skip(skip).cloned().collect()
+    }
+
+    /// Stamps `message` with the next sequence number, keeps a copy for
+    /// replay, and forwards it to the sink. The buffer lock is held across
+    /// the emit so sequence numbers reach the frontend in order.
+    async fn emit_event<E: EventSink>(&self, event_sink: &E, message: Value) {
+        track_turn(&mut *self.active_turns.lock().await, &message);
+        let mut events = self.events.lock().await;
+        let event = AppServerEvent {
+            workspace_id: self.entry.id.clone(),
+            seq: self.next_event_seq.fetch_add(1, Ordering::SeqCst),
+            message,
+        };
+        if events.len() >= EVENT_BUFFER_LEN {
+            events.pop_front();
+        }
+        events.push_back(event.clone());
+        event_sink.emit_app_server_event(event);
+    }
+
+    /// Records a connectivity transition and emits `workspace/statusChanged`
+    /// when it differs from the previous state.
+    async fn set_status<E: EventSink>(&self, event_sink: &E, status: SessionStatus) {
+        {
+            let mut current = self.status.lock().await;
+            if *current == status {
+                return;
+            }
+            *current = status;
+        }
+        let message = json!({
+            "method": "workspace/statusChanged",
+            "params": { "workspaceId": self.entry.id, "status": status }
+        });
+        self.emit_event(event_sink, message).await;
+    }
+
+    /// Returns buffered events with a sequence number greater than `since_seq`.
+    pub(crate) async fn events_since(&self, since_seq: u64) -> Vec<AppServerEvent> {
+        let events = self.events.lock().await;
+        events
+            .iter()
+            .filter(|event| event.seq > since_seq)
+            .cloned()
+            .collect()
+    }
+
+    /// Answers every in-flight request with a "backend restarted" error so
+    /// callers stop waiting on a process that is gone.
+    async fn cancel_pending(&self) {
+        self.active_turns.lock().await.clear();
+        // The new process will not accept answers to the old one's requests.
+        self.server_requests.lock().await.clear();
+        for (id, pending) in self.pending.lock().await.drain() {
+            let _ = pending
+                .tx
+                .send(backend_restarted_response(id, &pending.method));
+        }
+    }
+
+    /// A "turn in progress" error response when `thread_id` has a running
+    /// turn. Callers return it instead of sending `turn/start`, unless they
+    /// mean to steer the running turn.
+    pub(crate) async fn turn_in_progress(&self, thread_id: &str) -> Option<Value> {
+        let active = self.active_turns.lock().await;
+        active
+            .iter()
+            .find(|(_, active_thread)| active_thread.as_str() == thread_id)
+            .map(|(turn_id, _)| turn_in_progress_response(thread_id, turn_id))
+    }
+
+    /// Withdraws in-flight request `id`: the caller gets a "request
+    /// cancelled" error instead of waiting for the result, and an app-server
+    /// that advertises `$/cancelRequest` is told to stop working on it.
+    pub(crate) async fn cancel_request(&self, id: u64) -> Result<(), String> {
+        let pending = self
+            .pending
+            .lock()
+            .await
+            .remove(&id)
+            .ok_or_else(|| format!("No in-flight request with id {id}."))?;
+        self.notify_cancelled(id).await;
+        let _ = pending
+            .tx
+            .send(request_cancelled_response(id, &pending.method));
+        Ok(())
+    }
+
+    /// Sends `$/cancelRequest` for `id`, but only to an app-server whose
+    /// `initialize` result lists it.
+    async fn notify_cancelled(&self, id: u64) {
+        if !self
+            .capabilities
+            .lock()
+            .await
+            .advertises(CANCEL_REQUEST_METHOD)
+        {
+            return;
+        }
+        let _ = self
+            .send_notification(CANCEL_REQUEST_METHOD, Some(json!({ "id": id })))
+            .await;
+    }
+
+    /// Waits for the monitor to bring the app-server back. Returns `false`
+    /// if the session died or `limit` passed first.
+    async fn wait_until_connected(&self, limit: Duration) -> bool {
+        let deadline = Instant::now() + limit;
+        loop {
+            if self.is_closed() {
+                return false;
+            }
+            if *self.status.lock().await == SessionStatus::Connected {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            sleep(Duration::from_millis(250)).await;
+        }
+    }
+
+    async fn write_message(&self, mut value: Value) -> Result<(), String> {
+        if let Some(path_map) = &self.path_map {
+            path_map.to_remote(&mut value);
+        }
         let mut stdin = self.stdin.lock().await;
         let mut line = serde_json::to_string(&value).map_err(|e| e.to_string())?;
         line.push('\n');
@@ -34,13 +568,130 @@ impl WorkspaceSession {
             .map_err(|e| e.to_string())
     }
 
+    /// Applies the app settings that tune a running session.
+    pub(crate) fn apply_settings(&self, settings: &AppSettings) {
+        self.request_timeout_secs
+            .store(settings.request_timeout_secs, Ordering::SeqCst);
+        self.request_queue
+            .set_limit(settings.max_concurrent_requests as usize);
+    }
+
+    pub(crate) fn request_queue_metrics(&self) -> RequestQueueMetrics {
+        self.request_queue.metrics()
+    }
+
+    fn request_timeout(&self) -> Option<Duration> {
+        match self.request_timeout_secs.load(Ordering::SeqCst) {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
     pub(crate) async fn send_request(&self, method: &str, params: Value) -> Result<Value, String> {
+        self.send_request_with_timeout(method, params, self.request_timeout())
+            .await
+    }
+
+    /// Sends typed params. The raw response is returned, as with
+    /// `send_request`; use `protocol::parse_result` to read it.
+    pub(crate) async fn request<R: ClientRequest>(&self, params: &R) -> Result<Value, String> {
+        let params = serde_json::to_value(params).map_err(|err| err.to_string())?;
+        self.send_request(R::METHOD, params).await
+    }
+
+    /// Sends a request, re-issuing idempotent reads that were cut off by an
+    /// app-server restart. Other methods get the "backend restarted" error
+    /// response, since the server may already have acted on them.
+    pub(crate) async fn send_request_with_timeout(
+        &self,
+        method: &str,
+        params: Value,
+        request_timeout: Option<Duration>,
+    ) -> Result<Value, String> {
+        if !self.capabilities.lock().await.supports(method) {
+            return Err(format!(
+                "`{method}` is not supported by this Codex backend."
+            ));
+        }
+        let mut attempts = 0;
+        loop {
+            let response = self
+                .send_request_once(method, params.clone(), request_timeout)
+                .await?;
+            self.capabilities
+                .lock()
+                .await
+                .note_response(method, &response);
+            if !is_backend_restarted(&response)
+                || !IDEMPOTENT_METHODS.contains(&method)
+                || attempts >= MAX_REISSUE_ATTEMPTS
+            {
+                return Ok(response);
+            }
+            attempts += 1;
+            if !self
+                .wait_until_connected(
+                    request_timeout.map_or(REISSUE_WAIT_MAX, |limit| limit.min(REISSUE_WAIT_MAX)),
+                )
+                .await
+            {
+                return Ok(response);
+            }
+        }
+    }
+
+    async fn send_request_once(
+        &self,
+        method: &str,
+        params: Value,
+        request_timeout: Option<Duration>,
+    ) -> Result<Value, String> {
+        let priority = if method == "turn/interrupt" || method == "initialize" {
+            RequestPriority::Urgent
+        } else {
+            RequestPriority::Normal
+        };
+        let _slot = match request_timeout {
+            Some(duration) => timeout(duration, self.request_queue.acquire(priority))
+                .await
+                .map_err(|_| {
+                    format!(
+                        "`{method}` waited more than {}s for a free request slot.",
+                        duration.as_secs()
+                    )
+                })?,
+            None => self.request_queue.acquire(priority).await,
+        };
         let id = self.next_id.fetch_add(1, Ordering::SeqCst);
         let (tx, rx) = oneshot::channel();
-        self.pending.lock().await.insert(id, tx);
-        self.write_message(json!({ "id": id, "method": method, "params": params }))
-            .await?;
-        rx.await.map_err(|_| "request canceled".to_string())
+        self.pending.lock().await.insert(
+            id,
+            PendingRequest {
+                method: method.to_string(),
+                tx,
+            },
+        );
+        if let Err(err) = self
+            .write_message(json!({ "id": id, "method": method, "params": params }))
+            .await
+        {
+            self.pending.lock().await.remove(&id);
+            return Err(err);
+        }
+        let response = match request_timeout {
+            Some(duration) => match timeout(duration, rx).await {
+                Ok(response) => response,
+                Err(_) => {
+                    let _ = self.cancel_request(id).await;
+                    return Err(format!(
+                        "Codex app-server did not respond to `{method}` within {}s.",
+                        duration.as_secs()
+                    ));
+                }
+            },
+            None => rx.await,
+        };
+        response.map_err(|_| "request canceled".to_string())
     }
 
     pub(crate) async fn send_notification(
@@ -60,15 +711,60 @@ impl WorkspaceSession {
         self.write_message(json!({ "id": id, "result": result }))
             .await
     }
+
+    /// Answers a pending server request after checking `result` against
+    /// its type. Each request can be answered once.
+    pub(crate) async fn respond_to_server_request(
+        &self,
+        id: u64,
+        result: Value,
+    ) -> Result<(), String> {
+        let result = self.server_requests.lock().await.resolve(id, result)?;
+        // An answered request must not prompt again when the UI replays
+        // buffered events after a reload.
+        self.events.lock().await.retain(|event| {
+            event.message.get("method").is_none()
+                || event.message.get("id").and_then(Value::as_u64) != Some(id)
+        });
+        self.send_response(id, result).await
+    }
+
+    pub(crate) async fn pending_server_requests(&self) -> Vec<ServerRequest> {
+        self.server_requests.lock().await.list()
+    }
 }
 
 pub(crate) fn build_codex_path_env(codex_bin: Option<&str>) -> Option<String> {
-    let mut paths: Vec<String> = env::var("PATH")
-        .unwrap_or_default()
-        .split(':')
-        .filter(|value| !value.is_empty())
-        .map(|value| value.to_string())
-        .collect();
+    let mut paths: Vec<PathBuf> = env::var_os("PATH")
+        .map(|value| {
+            env::split_paths(&value)
+                .filter(|path| !path.as_os_str().is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut extras = platform_path_extras();
+    if let Some(bin_path) = codex_bin.filter(|value| !value.trim().is_empty()) {
+        let parent = Path::new(bin_path).parent();
+        if let Some(parent) = parent {
+            extras.push(parent.to_path_buf());
+        }
+    }
+    for extra in extras {
+        if !paths.contains(&extra) {
+            paths.push(extra);
+        }
+    }
+    if paths.is_empty() {
+        None
+    } else {
+        env::join_paths(paths)
+            .ok()
+            .map(|value| value.to_string_lossy().to_string())
+    }
+}
+
+#[cfg(not(windows))]
+fn platform_path_extras() -> Vec<PathBuf> {
     let mut extras = vec![
         "/opt/homebrew/bin",
         "/usr/local/bin",
@@ -78,39 +774,50 @@ pub(crate) fn build_codex_path_env(codex_bin: Option<&str>) -> Option<String> {
         "/sbin",
     ]
     .into_iter()
-    .map(|value| value.to_string())
-    .collect::<Vec<String>>();
+    .map(PathBuf::from)
+    .collect::<Vec<PathBuf>>();
     if let Ok(home) = env::var("HOME") {
-        extras.push(format!("{home}/.local/bin"));
-        extras.push(format!("{home}/.local/share/mise/shims"));
-        extras.push(format!("{home}/.cargo/bin"));
-        extras.push(format!("{home}/.bun/bin"));
-        let nvm_root = Path::new(&home).join(".nvm/versions/node");
+        let home = Path::new(&home);
+        extras.push(home.join(".local/bin"));
+        extras.push(home.join(".local/share/mise/shims"));
+        extras.push(home.join(".cargo/bin"));
+        extras.push(home.join(".bun/bin"));
+        let nvm_root = home.join(".nvm/versions/node");
         if let Ok(entries) = std::fs::read_dir(nvm_root) {
             for entry in entries.flatten() {
                 let bin_path = entry.path().join("bin");
                 if bin_path.is_dir() {
-                    extras.push(bin_path.to_string_lossy().to_string());
+                    extras.push(bin_path);
                 }
             }
         }
     }
-    if let Some(bin_path) = codex_bin.filter(|value| !value.trim().is_empty()) {
-        let parent = Path::new(bin_path).parent();
-        if let Some(parent) = parent {
-            extras.push(parent.to_string_lossy().to_string());
-        }
+    extras
+}
+
+#[cfg(windows)]
+fn platform_path_extras() -> Vec<PathBuf> {
+    let mut extras = Vec::new();
+    if let Some(app_data) = env::var_os("APPDATA") {
+        extras.push(PathBuf::from(app_data).join("npm"));
     }
-    for extra in extras {
-        if !paths.contains(&extra) {
-            paths.push(extra);
-        }
+    if let Some(scoop) = env::var_os("SCOOP") {
+        extras.push(PathBuf::from(scoop).join("shims"));
     }
-    if paths.is_empty() {
-        None
-    } else {
-        Some(paths.join(":"))
+    if let Some(profile) = env::var_os("USERPROFILE") {
+        let profile = PathBuf::from(profile);
+        extras.push(profile.join("scoop").join("shims"));
+        extras.push(profile.join(".cargo").join("bin"));
+        extras.push(profile.join(".bun").join("bin"));
     }
+    let program_data = env::var_os("ProgramData")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"));
+    extras.push(program_data.join("chocolatey").join("bin"));
+    if let Some(program_files) = env::var_os("ProgramFiles") {
+        extras.push(PathBuf::from(program_files).join("nodejs"));
+    }
+    extras
 }
 
 pub(crate) fn build_codex_command_with_bin(codex_bin: Option<String>) -> Command {
@@ -118,13 +825,62 @@ pub(crate) fn build_codex_command_with_bin(codex_bin: Option<String>) -> Command
         .clone()
         .filter(|value| !value.trim().is_empty())
         .unwrap_or_else(|| "codex".into());
-    let mut command = Command::new(bin);
-    if let Some(path_env) = build_codex_path_env(codex_bin.as_deref()) {
+    let path_env = build_codex_path_env(codex_bin.as_deref());
+    let mut command = new_codex_command(&bin, path_env.as_deref());
+    if let Some(path_env) = path_env {
         command.env("PATH", path_env);
     }
     command
 }
 
+#[cfg(not(windows))]
+pub(crate) fn new_codex_command(bin: &str, _path_env: Option<&str>) -> Command {
+    Command::new(bin)
+}
+
+/// npm installs `codex` as a `.cmd` shim, which `CreateProcess` cannot start
+/// directly, so batch files are launched through `cmd /C`.
+#[cfg(windows)]
+pub(crate) fn new_codex_command(bin: &str, path_env: Option<&str>) -> Command {
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let resolved = resolve_windows_bin(bin, path_env).unwrap_or_else(|| PathBuf::from(bin));
+    let is_batch = resolved
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("cmd") || ext.eq_ignore_ascii_case("bat"))
+        .unwrap_or(false);
+    let mut command = if is_batch {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(&resolved);
+        command
+    } else {
+        Command::new(&resolved)
+    };
+    command.creation_flags(CREATE_NO_WINDOW);
+    command
+}
+
+#[cfg(windows)]
+fn resolve_windows_bin(bin: &str, path_env: Option<&str>) -> Option<PathBuf> {
+    let with_extension = |base: &Path| -> Option<PathBuf> {
+        if base.extension().is_some() && base.is_file() {
+            return Some(base.to_path_buf());
+        }
+        ["exe", "cmd", "bat"]
+            .iter()
+            .map(|ext| base.with_extension(ext))
+            .find(|candidate| candidate.is_file())
+    };
+    let candidate = Path::new(bin);
+    if candidate.is_absolute() || candidate.components().count() > 1 {
+        return with_extension(candidate);
+    }
+    let search = path_env
+        .map(std::ffi::OsString::from)
+        .or_else(|| env::var_os("PATH"))?;
+    env::split_paths(&search).find_map(|dir| with_extension(&dir.join(bin)))
+}
+
 pub(crate) async fn check_codex_installation(
     codex_bin: Option<String>,
 ) -> Result<Option<String>, String> {
@@ -173,123 +929,276 @@ pub(crate) async fn check_codex_installation(
     Ok(if version.is_empty() { None } else { Some(version) })
 }
 
-pub(crate) async fn spawn_workspace_session<E: EventSink>(
-    entry: WorkspaceEntry,
-    default_codex_bin: Option<String>,
-    client_version: String,
-    event_sink: E,
-    codex_home: Option<PathBuf>,
-) -> Result<Arc<WorkspaceSession>, String> {
-    let codex_bin = entry
-        .codex_bin
-        .clone()
-        .filter(|value| !value.trim().is_empty())
-        .or(default_codex_bin);
-    let _ = check_codex_installation(codex_bin.clone()).await?;
+/// Cleans up user-supplied `codex app-server` flags: trims each one, drops
+/// blanks, and rejects control characters or an unreasonable count.
+pub(crate) fn validate_codex_args(args: &[String]) -> Result<Vec<String>, String> {
+    let args: Vec<String> = args
+        .iter()
+        .map(|arg| arg.trim().to_string())
+        .filter(|arg| !arg.is_empty())
+        .collect();
+    if args.len() > MAX_CODEX_ARGS {
+        return Err(format!(
+            "Too many app-server arguments ({}, limit {MAX_CODEX_ARGS}).",
+            args.len()
+        ));
+    }
+    if let Some(arg) = args.iter().find(|arg| arg.chars().any(char::is_control)) {
+        return Err(format!(
+            "App-server argument {arg:?} contains control characters."
+        ));
+    }
+    if args.first().is_some_and(|arg| !arg.starts_with('-')) {
+        return Err("App-server arguments must start with a flag.".to_string());
+    }
+    Ok(args)
+}
+
+/// Trims the verbosity preference; blank clears it.
+pub(crate) fn validate_verbosity(verbosity: Option<&str>) -> Result<Option<String>, String> {
+    match verbosity.map(str::trim).filter(|value| !value.is_empty()) {
+        None => Ok(None),
+        Some(value) if VERBOSITY_LEVELS.contains(&value) => Ok(Some(value.to_string())),
+        Some(value) => Err(format!(
+            "Unknown verbosity \"{value}\" (expected low, medium or high)."
+        )),
+    }
+}
 
+/// `codex app-server` plus any extra flags from settings.
+pub(crate) fn build_app_server_command(
+    codex_bin: Option<String>,
+    codex_args: &[String],
+) -> Command {
     let mut command = build_codex_command_with_bin(codex_bin);
-    command.current_dir(&entry.path);
     command.arg("app-server");
-    if let Some(codex_home) = codex_home {
-        command.env("CODEX_HOME", codex_home);
-    }
+    command.args(codex_args);
+    command
+}
+
+fn spawn_app_server(
+    entry: &WorkspaceEntry,
+    codex_bin: Option<String>,
+    codex_args: &[String],
+    codex_home: Option<&PathBuf>,
+) -> Result<Child, String> {
+    let mut command = match &entry.settings.remote {
+        // CODEX_HOME and the working directory are the remote host's own.
+        Some(remote) => build_ssh_command(remote, codex_bin.as_deref(), codex_args),
+        None => {
+            let mut command = build_app_server_command(codex_bin, codex_args);
+            command.current_dir(&entry.path);
+            if let Some(codex_home) = codex_home {
+                command.env("CODEX_HOME", codex_home);
+            }
+            command
+        }
+    };
     command.stdin(std::process::Stdio::piped());
     command.stdout(std::process::Stdio::piped());
     command.stderr(std::process::Stdio::piped());
+    // Own process group so shutdown can reach helpers the CLI spawns.
+    #[cfg(unix)]
+    command.process_group(0);
+    command.spawn().map_err(|e| e.to_string())
+}
+
+#[cfg(unix)]
+fn signal_process_group(pid: u32, signal: libc::c_int) {
+    // SAFETY: killpg takes no pointers; a group that is already gone only
+    // makes it return ESRCH.
+    unsafe {
+        libc::killpg(pid as libc::pid_t, signal);
+    }
+}
+
+/// Reads CPU percent and resident memory for `pid` from `ps`.
+#[cfg(unix)]
+async fn probe_process(pid: u32) -> Option<(f64, u64)> {
+    let output = Command::new("ps")
+        .args(["-o", "%cpu=,rss=", "-p", &pid.to_string()])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_ps_usage(&String::from_utf8_lossy(&output.stdout))
+}
 
-    let mut child = command.spawn().map_err(|e| e.to_string())?;
+// No dependency-free probe on Windows yet; PID and uptime still report.
+#[cfg(not(unix))]
+async fn probe_process(_pid: u32) -> Option<(f64, u64)> {
+    None
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn parse_ps_usage(output: &str) -> Option<(f64, u64)> {
+    let mut fields = output.split_whitespace();
+    let cpu = fields.next()?.replace(',', ".").parse::<f64>().ok()?;
+    let rss_kib = fields.next()?.parse::<u64>().ok()?;
+    Some((cpu, rss_kib * 1024))
+}
+
+fn take_child_pipes(child: &mut Child) -> Result<(ChildStdin, ChildStdout, ChildStderr), String> {
     let stdin = child.stdin.take().ok_or("missing stdin")?;
     let stdout = child.stdout.take().ok_or("missing stdout")?;
     let stderr = child.stderr.take().ok_or("missing stderr")?;
+    Ok((stdin, stdout, stderr))
+}
 
-    let session = Arc::new(WorkspaceSession {
-        entry: entry.clone(),
-        child: Mutex::new(child),
-        stdin: Mutex::new(stdin),
-        pending: Mutex::new(HashMap::new()),
-        next_id: AtomicU64::new(1),
-    });
-
-    let session_clone = Arc::clone(&session);
-    let workspace_id = entry.id.clone();
+/// Starts the stdout/stderr readers for the current child. The returned
+/// handle completes once stdout closes, which is how process exit is detected.
+fn spawn_output_readers<E: EventSink>(
+    session: &Arc<WorkspaceSession>,
+    stdout: ChildStdout,
+    stderr: ChildStderr,
+    event_sink: E,
+) -> JoinHandle<()> {
+    let session_clone = Arc::clone(session);
     let event_sink_clone = event_sink.clone();
-    tokio::spawn(async move {
+    let stdout_task = tokio::spawn(async move {
         let mut lines = BufReader::new(stdout).lines();
         while let Ok(Some(line)) = lines.next_line().await {
             if line.trim().is_empty() {
                 continue;
             }
-            let value: Value = match serde_json::from_str(&line) {
+            session_clone.last_output_at.store(now_millis(), Ordering::SeqCst);
+            session_clone.record_log("stdout", &line).await;
+            let mut value: Value = match serde_json::from_str(&line) {
                 Ok(value) => value,
                 Err(err) => {
-                    let payload = AppServerEvent {
-                        workspace_id: workspace_id.clone(),
-                        message: json!({
-                            "method": "codex/parseError",
-                            "params": { "error": err.to_string(), "raw": line },
-                        }),
-                    };
-                    event_sink_clone.emit_app_server_event(payload);
+                    session_clone
+                        .emit_event(
+                            &event_sink_clone,
+                            json!({
+                                "method": "codex/parseError",
+                                "params": { "error": err.to_string(), "raw": line },
+                            }),
+                        )
+                        .await;
                     continue;
                 }
             };
+            if let Some(path_map) = &session_clone.path_map {
+                path_map.to_local(&mut value);
+            }
 
             let maybe_id = value.get("id").and_then(|id| id.as_u64());
             let has_method = value.get("method").is_some();
             let has_result_or_error = value.get("result").is_some() || value.get("error").is_some();
             if let Some(id) = maybe_id {
                 if has_result_or_error {
-                    if let Some(tx) = session_clone.pending.lock().await.remove(&id) {
-                        let _ = tx.send(value);
+                    if let Some(pending) = session_clone.pending.lock().await.remove(&id) {
+                        let _ = pending.tx.send(value);
                     }
                 } else if has_method {
-                    let payload = AppServerEvent {
-                        workspace_id: workspace_id.clone(),
-                        message: value,
-                    };
-                    event_sink_clone.emit_app_server_event(payload);
-                } else if let Some(tx) = session_clone.pending.lock().await.remove(&id) {
-                    let _ = tx.send(value);
+                    if let Some(request) = ServerRequest::parse(&value, now_millis()) {
+                        session_clone.server_requests.lock().await.insert(request);
+                    }
+                    session_clone.emit_event(&event_sink_clone, value).await;
+                } else if let Some(pending) = session_clone.pending.lock().await.remove(&id) {
+                    let _ = pending.tx.send(value);
                 }
             } else if has_method {
-                let payload = AppServerEvent {
-                    workspace_id: workspace_id.clone(),
-                    message: value,
-                };
-                event_sink_clone.emit_app_server_event(payload);
+                if value.get("method").and_then(Value::as_str) == Some("$/cancelRequest") {
+                    // The server withdrew one of its requests; drop it so a
+                    // late answer from the UI is rejected.
+                    if let Some(id) = value.pointer("/params/id").and_then(Value::as_u64) {
+                        session_clone.server_requests.lock().await.cancel(id);
+                    }
+                }
+                session_clone.emit_event(&event_sink_clone, value).await;
             }
         }
     });
 
-    let workspace_id = entry.id.clone();
-    let event_sink_clone = event_sink.clone();
+    let session_clone = Arc::clone(session);
     tokio::spawn(async move {
         let mut lines = BufReader::new(stderr).lines();
         while let Ok(Some(line)) = lines.next_line().await {
             if line.trim().is_empty() {
                 continue;
             }
-            let payload = AppServerEvent {
-                workspace_id: workspace_id.clone(),
-                message: json!({
-                    "method": "codex/stderr",
-                    "params": { "message": line },
-                }),
-            };
-            event_sink_clone.emit_app_server_event(payload);
+            session_clone.record_log("stderr", &line).await;
+            session_clone
+                .emit_event(
+                    &event_sink,
+                    json!({
+                        "method": "codex/stderr",
+                        "params": { "message": line },
+                    }),
+                )
+                .await;
         }
     });
 
-    let init_params = json!({
-        "clientInfo": {
-            "name": "codex_monitor",
-            "title": "CodexMonitor",
-            "version": client_version
+    stdout_task
+}
+
+/// What CodexMonitor sends in `initialize`. It is kept for the life of the
+/// session so a restarted app-server is offered the same capabilities and
+/// preferences.
+#[derive(Debug, Clone)]
+pub(crate) struct ClientInfo {
+    pub(crate) version: String,
+    /// BCP 47 tag such as `en-US`.
+    pub(crate) locale: Option<String>,
+    pub(crate) verbosity: Option<String>,
+}
+
+impl ClientInfo {
+    pub(crate) fn new(version: String, settings: &AppSettings) -> Self {
+        Self {
+            version,
+            locale: system_locale(),
+            verbosity: settings.verbosity.clone(),
         }
-    });
+    }
+
+    pub(crate) fn initialize_params(&self) -> Value {
+        json!({
+            "clientInfo": {
+                "name": "codex_monitor",
+                "title": "CodexMonitor",
+                "version": self.version
+            },
+            "capabilities": {
+                "approvals": {
+                    "methods": APPROVAL_METHODS,
+                    "decisions": ["accept", "acceptForSession", "decline", "cancel"]
+                },
+                "streaming": { "deltas": STREAMED_DELTAS },
+                "itemTypes": SUPPORTED_ITEM_TYPES
+            },
+            "preferences": {
+                "locale": self.locale,
+                "verbosity": self.verbosity
+            }
+        })
+    }
+}
+
+/// `en_US.UTF-8` -> `en-US`. `C` and `POSIX` carry no preference.
+fn locale_tag(raw: &str) -> Option<String> {
+    let base = raw.split(['.', '@']).next().unwrap_or("").trim();
+    if base.is_empty() || base == "C" || base == "POSIX" {
+        return None;
+    }
+    Some(base.replace('_', "-"))
+}
+
+fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find_map(|value| locale_tag(&value))
+}
+
+async fn initialize_session(session: &WorkspaceSession, client: &ClientInfo) -> Result<(), String> {
     let init_result = timeout(
-        Duration::from_secs(15),
-        session.send_request("initialize", init_params),
+        INITIALIZE_TIMEOUT,
+        session.send_request_with_timeout("initialize", client.initialize_params(), None),
     )
     .await;
     let init_response = match init_result {
@@ -303,17 +1212,621 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
             );
         }
     };
-    init_response?;
-    session.send_notification("initialized", None).await?;
-
-    let payload = AppServerEvent {
-        workspace_id: entry.id.clone(),
-        message: json!({
-            "method": "codex/connected",
-            "params": { "workspaceId": entry.id.clone() }
-        }),
+    let response = init_response?;
+    *session.capabilities.lock().await =
+        BackendCapabilities::from_initialize(response.get("result").unwrap_or(&Value::Null));
+    session.send_notification("initialized", None).await
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum StartupFailureCause {
+    MissingNode,
+    BadArguments,
+    BadConfig,
+    BadAuth,
+    Unknown,
+}
+
+impl StartupFailureCause {
+    fn hint(self) -> Option<&'static str> {
+        match self {
+            Self::MissingNode => {
+                Some("Codex needs Node.js on PATH. Install Node or point Settings at a standalone Codex binary.")
+            }
+            Self::BadArguments => Some("Check the extra app-server arguments in Settings."),
+            Self::BadConfig => Some("Check `config.toml` in your Codex home."),
+            Self::BadAuth => Some("Run `codex login` in Terminal."),
+            Self::Unknown => None,
+        }
+    }
+}
+
+/// Guesses why the app-server failed to start from what it printed.
+pub(crate) fn classify_startup_failure(stderr: &[String]) -> StartupFailureCause {
+    let text = stderr.join("\n").to_lowercase();
+    let any = |needles: &[&str]| needles.iter().any(|needle| text.contains(needle));
+    if any(&[
+        "env: node: no such file",
+        "node: not found",
+        "node: command not found",
+        "node: no such file or directory",
+    ]) {
+        StartupFailureCause::MissingNode
+    } else if any(&[
+        "unexpected argument",
+        "unrecognized option",
+        "invalid value for",
+    ]) {
+        StartupFailureCause::BadArguments
+    } else if any(&[
+        "config.toml",
+        "error loading config",
+        "invalid configuration",
+    ]) {
+        StartupFailureCause::BadConfig
+    } else if any(&[
+        "401",
+        "unauthorized",
+        "not logged in",
+        "login required",
+        "invalid api key",
+        "incorrect api key",
+    ]) {
+        StartupFailureCause::BadAuth
+    } else {
+        StartupFailureCause::Unknown
+    }
+}
+
+/// Folds the exit code and stderr tail of a failed start into `error` and
+/// reports it as `backend/startupFailed`.
+async fn report_startup_failure<E: EventSink>(
+    session: &WorkspaceSession,
+    event_sink: &E,
+    error: String,
+) -> String {
+    // Give the stderr reader a moment to drain what the process printed.
+    sleep(Duration::from_millis(100)).await;
+    let exit_code = match session.child.lock().await.try_wait() {
+        Ok(Some(status)) => status.code(),
+        _ => None,
+    };
+    let started_at = session.started_at.load(Ordering::SeqCst);
+    let mut stderr: Vec<String> = session
+        .logs
+        .lock()
+        .await
+        .iter()
+        .rev()
+        .filter(|entry| entry.stream == "stderr" && entry.timestamp >= started_at)
+        .take(STARTUP_STDERR_LINES)
+        .map(|entry| entry.line.clone())
+        .collect();
+    stderr.reverse();
+    let cause = classify_startup_failure(&stderr);
+    session
+        .emit_event(
+            event_sink,
+            json!({
+                "method": "backend/startupFailed",
+                "params": {
+                    "workspaceId": session.entry.id,
+                    "cause": cause,
+                    "error": error,
+                    "exitCode": exit_code,
+                    "stderr": stderr,
+                }
+            }),
+        )
+        .await;
+
+    let mut message = error;
+    if let Some(code) = exit_code {
+        message.push_str(&format!(" (exit code {code})"));
+    }
+    if !stderr.is_empty() {
+        message.push_str("\n\n");
+        message.push_str(&stderr.join("\n"));
+    }
+    if let Some(hint) = cause.hint() {
+        message.push_str("\n\n");
+        message.push_str(hint);
+    }
+    message
+}
+
+/// Runs `initialize`, failing as soon as the child exits instead of waiting
+/// out the timeout, and reports any failure with the child's stderr.
+async fn start_session<E: EventSink>(
+    session: &WorkspaceSession,
+    reader: &JoinHandle<()>,
+    client: &ClientInfo,
+    event_sink: &E,
+) -> Result<(), String> {
+    let mut init = Box::pin(initialize_session(session, client));
+    let result = loop {
+        match timeout(STARTUP_POLL_INTERVAL, &mut init).await {
+            Ok(result) => break result,
+            Err(_) if reader.is_finished() => {
+                break Err("Codex app-server exited during initialize.".to_string())
+            }
+            Err(_) => {}
+        }
+    };
+    match result {
+        Ok(()) => Ok(()),
+        Err(err) => Err(report_startup_failure(session, event_sink, err).await),
+    }
+}
+
+async fn emit_connected<E: EventSink>(session: &WorkspaceSession, event_sink: &E) {
+    let message = json!({
+        "method": "codex/connected",
+        "params": { "workspaceId": session.entry.id }
+    });
+    session.emit_event(event_sink, message).await;
+}
+
+async fn emit_disconnected<E: EventSink>(
+    session: &WorkspaceSession,
+    event_sink: &E,
+    exit_code: Option<i32>,
+    will_retry: bool,
+    error: Option<String>,
+) {
+    let message = json!({
+        "method": "codex/disconnected",
+        "params": {
+            "workspaceId": session.entry.id,
+            "exitCode": exit_code,
+            "willRetry": will_retry,
+            "error": error,
+        }
+    });
+    session.emit_event(event_sink, message).await;
+}
+
+/// Spawns a fresh app-server, swaps it into the session, and replays
+/// `initialize`. Returns the stdout reader handle of the new process.
+async fn respawn_session<E: EventSink>(
+    session: &Arc<WorkspaceSession>,
+    codex_bin: Option<String>,
+    codex_args: &[String],
+    codex_home: Option<&PathBuf>,
+    client: &ClientInfo,
+    event_sink: &E,
+) -> Result<JoinHandle<()>, String> {
+    let mut child = spawn_app_server(&session.entry, codex_bin, codex_args, codex_home)?;
+    let (stdin, stdout, stderr) = take_child_pipes(&mut child)?;
+    *session.child.lock().await = child;
+    *session.stdin.lock().await = stdin;
+    session.started_at.store(now_millis(), Ordering::SeqCst);
+    if session.is_closed() {
+        let _ = session.child.lock().await.kill().await;
+        return Err("workspace session closed".to_string());
+    }
+    let reader = spawn_output_readers(session, stdout, stderr, event_sink.clone());
+    start_session(session, &reader, client, event_sink).await?;
+    Ok(reader)
+}
+
+async fn monitor_session<E: EventSink>(
+    session: Arc<WorkspaceSession>,
+    mut reader: JoinHandle<()>,
+    codex_bin: Option<String>,
+    codex_args: Vec<String>,
+    codex_home: Option<PathBuf>,
+    client: ClientInfo,
+    event_sink: E,
+) {
+    loop {
+        let _ = (&mut reader).await;
+        if session.is_closed() {
+            return;
+        }
+        let exit_code = {
+            let mut child = session.child.lock().await;
+            match timeout(Duration::from_secs(2), child.wait()).await {
+                Ok(Ok(status)) => status.code(),
+                _ => {
+                    let _ = child.kill().await;
+                    None
+                }
+            }
+        };
+        session.cancel_pending().await;
+        if session.is_closed() {
+            return;
+        }
+        emit_disconnected(&session, &event_sink, exit_code, true, None).await;
+        session.set_status(&event_sink, SessionStatus::Starting).await;
+
+        let mut delay = RESTART_BACKOFF_INITIAL;
+        let mut last_error = None;
+        let mut restarted = None;
+        for _ in 0..MAX_RESTART_ATTEMPTS {
+            sleep(delay).await;
+            if session.is_closed() {
+                return;
+            }
+            match respawn_session(
+                &session,
+                codex_bin.clone(),
+                &codex_args,
+                codex_home.as_ref(),
+                &client,
+                &event_sink,
+            )
+            .await
+            {
+                Ok(handle) => {
+                    restarted = Some(handle);
+                    break;
+                }
+                Err(err) => {
+                    session.cancel_pending().await;
+                    last_error = Some(err);
+                }
+            }
+            delay = (delay * 2).min(RESTART_BACKOFF_MAX);
+        }
+
+        match restarted {
+            Some(handle) => {
+                reader = handle;
+                session.restart_count.fetch_add(1, Ordering::SeqCst);
+                session.set_status(&event_sink, SessionStatus::Connected).await;
+                emit_connected(&session, &event_sink).await;
+            }
+            None => {
+                session.closed.store(true, Ordering::SeqCst);
+                session.set_status(&event_sink, SessionStatus::Dead).await;
+                emit_disconnected(&session, &event_sink, exit_code, false, last_error).await;
+                return;
+            }
+        }
+    }
+}
+
+/// Periodically checks that the app-server process is alive and that
+/// outstanding requests are still producing output. Restarts are left to
+/// `monitor_session`, which owns the starting/dead transitions around them.
+async fn heartbeat_session<E: EventSink>(session: Arc<WorkspaceSession>, event_sink: E) {
+    loop {
+        sleep(HEARTBEAT_INTERVAL).await;
+        if session.is_closed() {
+            return;
+        }
+        let current = *session.status.lock().await;
+        if matches!(current, SessionStatus::Starting | SessionStatus::Dead) {
+            continue;
+        }
+        let exited = !matches!(session.child.lock().await.try_wait(), Ok(None));
+        if exited {
+            session.set_status(&event_sink, SessionStatus::Dead).await;
+            continue;
+        }
+        let waiting = !session.pending.lock().await.is_empty();
+        let idle = now_millis().saturating_sub(session.last_output_at.load(Ordering::SeqCst));
+        let next = if waiting && idle >= UNRESPONSIVE_AFTER.as_millis() as u64 {
+            SessionStatus::Unresponsive
+        } else {
+            SessionStatus::Connected
+        };
+        session.set_status(&event_sink, next).await;
+    }
+}
+
+pub(crate) async fn spawn_workspace_session<E: EventSink>(
+    entry: WorkspaceEntry,
+    default_codex_bin: Option<String>,
+    codex_args: Vec<String>,
+    client: ClientInfo,
+    event_sink: E,
+    codex_home: Option<PathBuf>,
+) -> Result<Arc<WorkspaceSession>, String> {
+    let remote = entry.settings.remote.as_ref();
+    let entry_bin = entry
+        .codex_bin
+        .clone()
+        .filter(|value| !value.trim().is_empty());
+    // The local default binary means nothing on a remote host.
+    let codex_bin = match remote {
+        Some(_) => entry_bin,
+        None => entry_bin.or(default_codex_bin),
     };
-    event_sink.emit_app_server_event(payload);
+    if remote.is_none() {
+        let _ = check_codex_installation(codex_bin.clone()).await?;
+    }
+    let path_map = remote.and_then(|remote| PathMap::new(&entry.path, &remote.path));
+
+    let mut child = spawn_app_server(&entry, codex_bin.clone(), &codex_args, codex_home.as_ref())?;
+    let (stdin, stdout, stderr) = take_child_pipes(&mut child)?;
+
+    let session = Arc::new(WorkspaceSession {
+        entry: entry.clone(),
+        child: Mutex::new(child),
+        stdin: Mutex::new(stdin),
+        pending: Mutex::new(HashMap::new()),
+        next_id: AtomicU64::new(1),
+        closed: AtomicBool::new(false),
+        restart_count: AtomicU32::new(0),
+        request_timeout_secs: AtomicU64::new(DEFAULT_REQUEST_TIMEOUT_SECS),
+        request_queue: RequestQueue::new(0),
+        logs: Mutex::new(VecDeque::new()),
+        events: Mutex::new(VecDeque::new()),
+        next_event_seq: AtomicU64::new(1),
+        status: Mutex::new(SessionStatus::Starting),
+        last_output_at: AtomicU64::new(now_millis()),
+        started_at: AtomicU64::new(now_millis()),
+        capabilities: Mutex::new(BackendCapabilities::default()),
+        active_turns: Mutex::new(HashMap::new()),
+        server_requests: Mutex::new(ServerRequestRegistry::default()),
+        path_map,
+    });
+
+    let reader = spawn_output_readers(&session, stdout, stderr, event_sink.clone());
+    if let Err(err) = start_session(&session, &reader, &client, &event_sink).await {
+        session.terminate().await;
+        return Err(err);
+    }
+    session.set_status(&event_sink, SessionStatus::Connected).await;
+    emit_connected(&session, &event_sink).await;
+
+    tokio::spawn(heartbeat_session(Arc::clone(&session), event_sink.clone()));
+    tokio::spawn(monitor_session(
+        Arc::clone(&session),
+        reader,
+        codex_bin,
+        codex_args,
+        codex_home,
+        client,
+        event_sink,
+    ));
 
     Ok(session)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        backend_restarted_response, classify_startup_failure, is_backend_restarted, locale_tag,
+        parse_ps_usage, request_cancelled_response, track_turn, turn_in_progress_response,
+        validate_codex_args, validate_verbosity, BackendCapabilities, ClientInfo,
+        StartupFailureCause, REQUEST_CANCELLED_CODE, TURN_IN_PROGRESS_CODE,
+    };
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn validate_codex_args_trims_and_rejects_bad_input() {
+        let args = vec![
+            " --config ".to_string(),
+            "".to_string(),
+            "model=\"o3\"".to_string(),
+        ];
+        assert_eq!(
+            validate_codex_args(&args).unwrap(),
+            vec!["--config".to_string(), "model=\"o3\"".to_string()]
+        );
+        assert!(validate_codex_args(&["serve".to_string()]).is_err());
+        assert!(validate_codex_args(&["--x\n--y".to_string()]).is_err());
+        assert!(validate_codex_args(&vec!["-v".to_string(); 33]).is_err());
+    }
+
+    #[test]
+    fn client_info_advertises_capabilities_and_preferences() {
+        let client = ClientInfo {
+            version: "1.2.3".to_string(),
+            locale: locale_tag("de_DE.UTF-8@euro"),
+            verbosity: validate_verbosity(Some(" low ")).unwrap(),
+        };
+        let params = client.initialize_params();
+        assert_eq!(params["clientInfo"]["version"], "1.2.3");
+        assert_eq!(
+            params["preferences"],
+            json!({ "locale": "de-DE", "verbosity": "low" })
+        );
+        let approvals = params["capabilities"]["approvals"]["methods"]
+            .as_array()
+            .unwrap();
+        assert!(approvals.contains(&json!("item/commandExecution/requestApproval")));
+        assert!(params["capabilities"]["itemTypes"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("fileChange")));
+
+        assert_eq!(locale_tag("C.UTF-8"), None);
+        assert_eq!(validate_verbosity(Some("")).unwrap(), None);
+        assert!(validate_verbosity(Some("verbose")).is_err());
+    }
+
+    #[test]
+    fn classify_startup_failure_reads_stderr() {
+        let classify = |lines: &[&str]| {
+            let lines: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+            classify_startup_failure(&lines)
+        };
+        assert_eq!(
+            classify(&["env: node: No such file or directory"]),
+            StartupFailureCause::MissingNode
+        );
+        assert_eq!(
+            classify(&["error: unexpected argument '--bogus' found"]),
+            StartupFailureCause::BadArguments
+        );
+        assert_eq!(
+            classify(&["Error loading config.toml: invalid type"]),
+            StartupFailureCause::BadConfig
+        );
+        assert_eq!(
+            classify(&["request failed: 401 Unauthorized"]),
+            StartupFailureCause::BadAuth
+        );
+        assert_eq!(classify(&[]), StartupFailureCause::Unknown);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn startup_failure_reports_exit_code_and_stderr() {
+        use super::{spawn_workspace_session, ClientInfo, INITIALIZE_TIMEOUT};
+        use crate::backend::events::{AppServerEvent, EventSink, TerminalOutput};
+        use crate::types::{AppSettings, WorkspaceEntry};
+        use std::os::unix::fs::PermissionsExt;
+        use std::sync::{Arc, Mutex};
+        use std::time::Instant;
+
+        #[derive(Clone, Default)]
+        struct Sink(Arc<Mutex<Vec<serde_json::Value>>>);
+        impl EventSink for Sink {
+            fn emit_app_server_event(&self, event: AppServerEvent) {
+                self.0.lock().unwrap().push(event.message);
+            }
+            fn emit_terminal_output(&self, _event: TerminalOutput) {}
+        }
+
+        let dir = std::env::temp_dir().join(format!("codex-monitor-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let script = dir.join("codex");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\n\
+             if [ \"$1\" = \"--version\" ]; then echo codex-cli 0.0.0; exit 0; fi\n\
+             echo 'env: node: No such file or directory' >&2\n\
+             exit 127\n",
+        )
+        .expect("write script");
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755))
+            .expect("chmod script");
+        let entry: WorkspaceEntry = serde_json::from_value(json!({
+            "id": "ws", "name": "ws", "path": dir, "codex_bin": null
+        }))
+        .expect("workspace deserialize");
+
+        let sink = Sink::default();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+        let started = Instant::now();
+        let result = runtime.block_on(spawn_workspace_session(
+            entry,
+            Some(script.to_string_lossy().to_string()),
+            Vec::new(),
+            ClientInfo::new("test".to_string(), &AppSettings::default()),
+            sink.clone(),
+            None,
+        ));
+        let Err(error) = result else {
+            panic!("startup should fail");
+        };
+        assert!(started.elapsed() < INITIALIZE_TIMEOUT);
+        assert!(error.contains("exit code 127"), "{error}");
+        assert!(error.contains("env: node"), "{error}");
+
+        let events = sink.0.lock().unwrap();
+        let failed = events
+            .iter()
+            .find(|message| message["method"] == "backend/startupFailed")
+            .expect("startupFailed event");
+        assert_eq!(failed["params"]["cause"], "missingNode");
+        assert_eq!(failed["params"]["exitCode"], 127);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn parse_ps_usage_reads_cpu_and_rss() {
+        assert_eq!(parse_ps_usage("  3.5 20480\n"), Some((3.5, 20480 * 1024)));
+        assert_eq!(parse_ps_usage("0,7 512"), Some((0.7, 512 * 1024)));
+        assert_eq!(parse_ps_usage(""), None);
+    }
+
+    #[test]
+    fn turn_in_progress_response_names_the_running_turn() {
+        let response = turn_in_progress_response("thread-1", "turn-7");
+        assert_eq!(response["error"]["code"], TURN_IN_PROGRESS_CODE);
+        assert_eq!(response["error"]["data"]["reason"], "turnInProgress");
+        assert_eq!(response["error"]["data"]["threadId"], "thread-1");
+        assert_eq!(response["error"]["data"]["turnId"], "turn-7");
+    }
+
+    #[test]
+    fn request_cancelled_response_is_structured() {
+        let response = request_cancelled_response(9, "thread/list");
+        assert!(!is_backend_restarted(&response));
+        assert_eq!(response["id"], 9);
+        assert_eq!(response["error"]["code"], REQUEST_CANCELLED_CODE);
+        assert_eq!(response["error"]["data"]["reason"], "requestCancelled");
+    }
+
+    #[test]
+    fn backend_restarted_response_is_structured() {
+        let response = backend_restarted_response(7, "turn/start");
+        assert!(is_backend_restarted(&response));
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["error"]["data"]["method"], "turn/start");
+        assert_eq!(response["error"]["data"]["reason"], "backendRestarted");
+        assert!(!is_backend_restarted(
+            &json!({ "id": 1, "error": { "code": -32600, "message": "bad" } })
+        ));
+        assert!(!is_backend_restarted(&json!({ "id": 1, "result": {} })));
+    }
+
+    #[test]
+    fn capabilities_gate_feature_methods() {
+        let mut legacy = BackendCapabilities::from_initialize(&json!({ "userAgent": "codex/0.1" }));
+        assert_eq!(legacy.user_agent.as_deref(), Some("codex/0.1"));
+        assert!(legacy.supports("skills/list"));
+        legacy.note_response(
+            "skills/list",
+            &json!({ "id": 3, "error": { "code": -32601, "message": "method not found" } }),
+        );
+        legacy.note_response(
+            "skills/list",
+            &json!({ "id": 4, "error": { "code": -32601 } }),
+        );
+        assert!(!legacy.supports("skills/list"));
+        assert_eq!(legacy.unsupported, vec!["skills/list".to_string()]);
+
+        let advertised = BackendCapabilities::from_initialize(&json!({
+            "capabilities": { "methods": ["thread/start", "review/start"] }
+        }));
+        assert!(advertised.supports("review/start"));
+        assert!(!advertised.supports("collaborationMode/list"));
+        assert!(advertised.supports("thread/list"));
+        assert!(advertised.advertises("review/start"));
+        assert!(!advertised.advertises("$/cancelRequest"));
+        assert!(!legacy.advertises("$/cancelRequest"));
+    }
+
+    #[test]
+    fn track_turn_follows_turn_lifecycle() {
+        let mut active = HashMap::new();
+        for (turn, thread) in [("turn-1", "thread-1"), ("turn-2", "thread-2")] {
+            track_turn(
+                &mut active,
+                &json!({
+                    "method": "turn/started",
+                    "params": { "turn": { "id": turn, "threadId": thread } }
+                }),
+            );
+        }
+        assert_eq!(active.len(), 2);
+
+        track_turn(
+            &mut active,
+            &json!({ "method": "turn/completed", "params": { "turn": { "id": "turn-1" } } }),
+        );
+        track_turn(
+            &mut active,
+            &json!({ "method": "error", "params": { "threadId": "thread-2", "willRetry": true } }),
+        );
+        assert_eq!(active.get("turn-2").map(String::as_str), Some("thread-2"));
+        track_turn(
+            &mut active,
+            &json!({ "method": "error", "params": { "threadId": "thread-2", "willRetry": false } }),
+        );
+        assert!(active.is_empty());
+    }
+}
@@ -4,6 +4,8 @@ use serde_json::Value;
 #[derive(Serialize, Clone)]
 pub(crate) struct AppServerEvent {
     pub(crate) workspace_id: String,
+    /// Monotonic per-session counter used by `replay_events`.
+    pub(crate) seq: u64,
     pub(crate) message: Value,
 }
 
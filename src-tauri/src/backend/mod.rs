@@ -1,2 +1,6 @@
 pub(crate) mod app_server;
 pub(crate) mod events;
+pub(crate) mod protocol;
+pub(crate) mod remote;
+pub(crate) mod request_queue;
+pub(crate) mod server_requests;
@@ -0,0 +1,321 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A request CodexMonitor sends to the app-server: its params, method
+/// name, and the part of the result we read. Results handed to the UI
+/// untouched stay `Value`.
+pub(crate) trait ClientRequest: Serialize {
+    const METHOD: &'static str;
+    type Result: DeserializeOwned;
+}
+
+/// Reads the typed result out of a raw response. Error responses become
+/// their message; a result missing fields we need names the method.
+pub(crate) fn parse_result<R: ClientRequest>(response: &Value) -> Result<R::Result, String> {
+    if let Some(error) = response.get("error") {
+        return Err(error
+            .get("message")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{} failed", R::METHOD)));
+    }
+    let result = response.get("result").unwrap_or(response);
+    R::Result::deserialize(result)
+        .map_err(|err| format!("{} returned an unexpected result: {err}", R::METHOD))
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ApprovalPolicy {
+    OnRequest,
+    Never,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum SandboxPolicy {
+    DangerFullAccess,
+    ReadOnly,
+    #[serde(rename_all = "camelCase")]
+    WorkspaceWrite {
+        writable_roots: Vec<String>,
+        network_access: bool,
+    },
+}
+
+/// The approval and sandbox policy for a composer access mode
+/// (`current`, `read-only`, or `full-access`).
+pub(crate) fn access_policies(
+    access_mode: Option<&str>,
+    workspace_path: &str,
+) -> (ApprovalPolicy, SandboxPolicy) {
+    match access_mode.unwrap_or("current") {
+        "full-access" => (ApprovalPolicy::Never, SandboxPolicy::DangerFullAccess),
+        "read-only" => (ApprovalPolicy::OnRequest, SandboxPolicy::ReadOnly),
+        _ => (
+            ApprovalPolicy::OnRequest,
+            SandboxPolicy::WorkspaceWrite {
+                writable_roots: vec![workspace_path.to_string()],
+                network_access: true,
+            },
+        ),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct ThreadRef {
+    pub(crate) id: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct ThreadStartParams {
+    pub(crate) cwd: String,
+    #[serde(rename = "approvalPolicy")]
+    pub(crate) approval_policy: ApprovalPolicy,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct ThreadStartResult {
+    pub(crate) thread: ThreadRef,
+    /// The model the thread will use, when the app-server reports it.
+    #[serde(default)]
+    pub(crate) model: Option<String>,
+}
+
+impl ClientRequest for ThreadStartParams {
+    const METHOD: &'static str = "thread/start";
+    type Result = ThreadStartResult;
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct ThreadResumeParams {
+    #[serde(rename = "threadId")]
+    pub(crate) thread_id: String,
+}
+
+/// The thread with its turns, kept raw for rendering and export.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct ThreadResumeResult {
+    pub(crate) thread: Value,
+    #[serde(default)]
+    pub(crate) model: Option<String>,
+}
+
+impl ClientRequest for ThreadResumeParams {
+    const METHOD: &'static str = "thread/resume";
+    type Result = ThreadResumeResult;
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct ThreadListParams {
+    pub(crate) cursor: Option<String>,
+    pub(crate) limit: Option<u32>,
+}
+
+/// One page of threads; the entries are passed to the UI as they are.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct ThreadListResult {
+    pub(crate) data: Vec<Value>,
+    #[serde(default, rename = "nextCursor")]
+    pub(crate) next_cursor: Option<String>,
+}
+
+impl ClientRequest for ThreadListParams {
+    const METHOD: &'static str = "thread/list";
+    type Result = ThreadListResult;
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct ThreadArchiveParams {
+    #[serde(rename = "threadId")]
+    pub(crate) thread_id: String,
+}
+
+impl ClientRequest for ThreadArchiveParams {
+    const METHOD: &'static str = "thread/archive";
+    type Result = Value;
+}
+
+/// `input` items come from `attachments` and `mcp`, which build them as
+/// JSON already.
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct TurnStartParams {
+    #[serde(rename = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) input: Vec<Value>,
+    pub(crate) cwd: String,
+    #[serde(rename = "approvalPolicy")]
+    pub(crate) approval_policy: ApprovalPolicy,
+    #[serde(rename = "sandboxPolicy")]
+    pub(crate) sandbox_policy: SandboxPolicy,
+    pub(crate) model: Option<String>,
+    pub(crate) effort: Option<String>,
+    #[serde(rename = "collaborationMode")]
+    pub(crate) collaboration_mode: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct TurnRef {
+    pub(crate) id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct TurnStartResult {
+    pub(crate) turn: TurnRef,
+}
+
+impl ClientRequest for TurnStartParams {
+    const METHOD: &'static str = "turn/start";
+    type Result = TurnStartResult;
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct TurnInterruptParams {
+    #[serde(rename = "threadId")]
+    pub(crate) thread_id: String,
+    #[serde(rename = "turnId")]
+    pub(crate) turn_id: String,
+}
+
+impl ClientRequest for TurnInterruptParams {
+    const METHOD: &'static str = "turn/interrupt";
+    type Result = Value;
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct ReviewStartParams {
+    #[serde(rename = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) target: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) delivery: Option<String>,
+}
+
+impl ClientRequest for ReviewStartParams {
+    const METHOD: &'static str = "review/start";
+    type Result = Value;
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub(crate) struct ModelListParams {}
+
+impl ClientRequest for ModelListParams {
+    const METHOD: &'static str = "model/list";
+    type Result = Value;
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub(crate) struct CollaborationModeListParams {}
+
+impl ClientRequest for CollaborationModeListParams {
+    const METHOD: &'static str = "collaborationMode/list";
+    type Result = Value;
+}
+
+/// Takes no params; serializes as `null`.
+#[derive(Debug, Serialize, Clone, Default)]
+pub(crate) struct RateLimitsReadParams;
+
+impl ClientRequest for RateLimitsReadParams {
+    const METHOD: &'static str = "account/rateLimits/read";
+    type Result = Value;
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct SkillsListParams {
+    pub(crate) cwd: String,
+}
+
+impl ClientRequest for SkillsListParams {
+    const METHOD: &'static str = "skills/list";
+    type Result = Value;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        access_policies, parse_result, RateLimitsReadParams, ThreadListParams, ThreadStartParams,
+        TurnStartParams,
+    };
+    use serde_json::{json, Value};
+
+    #[test]
+    fn turn_start_params_match_the_wire_shape() {
+        let (approval_policy, sandbox_policy) = access_policies(None, "/repo");
+        let params = TurnStartParams {
+            thread_id: "t1".to_string(),
+            input: vec![json!({ "type": "text", "text": "hi" })],
+            cwd: "/repo".to_string(),
+            approval_policy,
+            sandbox_policy,
+            model: None,
+            effort: Some("high".to_string()),
+            collaboration_mode: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({
+                "threadId": "t1",
+                "input": [{ "type": "text", "text": "hi" }],
+                "cwd": "/repo",
+                "approvalPolicy": "on-request",
+                "sandboxPolicy": {
+                    "type": "workspaceWrite",
+                    "writableRoots": ["/repo"],
+                    "networkAccess": true
+                },
+                "model": null,
+                "effort": "high",
+                "collaborationMode": null
+            })
+        );
+        let (approval_policy, sandbox_policy) = access_policies(Some("full-access"), "/repo");
+        assert_eq!(serde_json::to_value(approval_policy).unwrap(), "never");
+        assert_eq!(
+            serde_json::to_value(sandbox_policy).unwrap(),
+            json!({ "type": "dangerFullAccess" })
+        );
+        assert_eq!(
+            serde_json::to_value(RateLimitsReadParams).unwrap(),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn parse_result_reports_errors_and_missing_fields() {
+        let ok = json!({ "id": 1, "result": { "thread": { "id": "t1", "preview": "" } } });
+        assert_eq!(
+            parse_result::<ThreadStartParams>(&ok).unwrap().thread.id,
+            "t1"
+        );
+
+        let error = json!({ "id": 1, "error": { "code": -32602, "message": "bad cwd" } });
+        assert_eq!(
+            parse_result::<ThreadStartParams>(&error).unwrap_err(),
+            "bad cwd"
+        );
+
+        let typo = json!({ "id": 1, "result": { "threads": { "id": "t1" } } });
+        let err = parse_result::<ThreadStartParams>(&typo).unwrap_err();
+        assert!(err.starts_with("thread/start returned an unexpected result"));
+        assert!(err.contains("`thread`"));
+    }
+
+    #[test]
+    fn turn_start_and_thread_list_results_are_typed() {
+        let started = json!({ "id": 2, "result": { "turn": { "id": "turn-1", "items": [] } } });
+        assert_eq!(
+            parse_result::<TurnStartParams>(&started).unwrap().turn.id,
+            "turn-1"
+        );
+
+        let page = json!({ "id": 3, "result": { "data": [{ "id": "t1" }], "nextCursor": "c2" } });
+        let page = parse_result::<ThreadListParams>(&page).unwrap();
+        assert_eq!(page.data.len(), 1);
+        assert_eq!(page.next_cursor.as_deref(), Some("c2"));
+
+        let missing = json!({ "id": 3, "result": {} });
+        assert!(parse_result::<ThreadListParams>(&missing).is_err());
+    }
+}
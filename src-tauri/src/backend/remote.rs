@@ -0,0 +1,141 @@
+use serde_json::Value;
+use tokio::process::Command;
+
+use crate::types::RemoteWorkspace;
+
+/// Quotes `value` for a POSIX shell on the remote host.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// `ssh <host> 'cd <path> && exec codex app-server <args>'`. `BatchMode`
+/// makes a missing key fail fast instead of waiting on a password prompt
+/// nobody can see.
+pub(crate) fn build_ssh_command(
+    remote: &RemoteWorkspace,
+    codex_bin: Option<&str>,
+    codex_args: &[String],
+) -> Command {
+    let mut remote_command = format!(
+        "cd {} && exec {} app-server",
+        shell_quote(&remote.path),
+        shell_quote(codex_bin.unwrap_or("codex"))
+    );
+    for arg in codex_args {
+        remote_command.push(' ');
+        remote_command.push_str(&shell_quote(arg));
+    }
+    let mut command = Command::new("ssh");
+    command.args(["-T", "-o", "BatchMode=yes", "-o", "ServerAliveInterval=15"]);
+    command.args(&remote.ssh_args);
+    command.arg(&remote.host);
+    command.arg(remote_command);
+    command
+}
+
+/// Rewrites path strings between the local workspace path and the remote
+/// one. Only whole strings equal to the root or below it are changed.
+#[derive(Debug, Clone)]
+pub(crate) struct PathMap {
+    local: String,
+    remote: String,
+}
+
+fn rewrite(value: &mut Value, from: &str, to: &str) {
+    match value {
+        Value::String(text) => {
+            if text.is_empty() {
+                return;
+            }
+            if text == from {
+                *text = to.to_string();
+            } else if let Some(rest) = text.strip_prefix(from).filter(|rest| rest.starts_with('/'))
+            {
+                *text = format!("{to}{rest}");
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| rewrite(item, from, to)),
+        Value::Object(map) => map.values_mut().for_each(|item| rewrite(item, from, to)),
+        _ => {}
+    }
+}
+
+impl PathMap {
+    /// `None` when both sides are the same directory.
+    pub(crate) fn new(local: &str, remote: &str) -> Option<Self> {
+        let local = local.trim_end_matches('/');
+        let remote = remote.trim_end_matches('/');
+        if local == remote {
+            return None;
+        }
+        Some(Self {
+            local: local.to_string(),
+            remote: remote.to_string(),
+        })
+    }
+
+    pub(crate) fn to_local(&self, value: &mut Value) {
+        rewrite(value, &self.remote, &self.local);
+    }
+
+    pub(crate) fn to_remote(&self, value: &mut Value) {
+        rewrite(value, &self.local, &self.remote);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_ssh_command, PathMap};
+    use crate::types::RemoteWorkspace;
+    use serde_json::json;
+
+    #[test]
+    fn path_map_rewrites_whole_paths_both_ways() {
+        let map = PathMap::new("/Users/me/app", "/home/me/app/").expect("map");
+        let mut event = json!({
+            "params": {
+                "cwd": "/home/me/app",
+                "changes": [{ "path": "/home/me/app/src/lib.rs" }, { "path": "/home/me/apple" }],
+                "text": "edited /home/me/app/src/lib.rs"
+            }
+        });
+        map.to_local(&mut event);
+        assert_eq!(event["params"]["cwd"], "/Users/me/app");
+        assert_eq!(
+            event["params"]["changes"][0]["path"],
+            "/Users/me/app/src/lib.rs"
+        );
+        assert_eq!(event["params"]["changes"][1]["path"], "/home/me/apple");
+        assert_eq!(event["params"]["text"], "edited /home/me/app/src/lib.rs");
+
+        let mut request = json!({ "cwd": "/Users/me/app" });
+        map.to_remote(&mut request);
+        assert_eq!(request["cwd"], "/home/me/app");
+
+        assert!(PathMap::new("/srv/app", "/srv/app/").is_none());
+    }
+
+    #[test]
+    fn ssh_command_quotes_remote_arguments() {
+        let remote = RemoteWorkspace {
+            host: "dev-box".to_string(),
+            path: "/home/me/it's here".to_string(),
+            ssh_args: vec!["-p".to_string(), "2222".to_string()],
+        };
+        let command = build_ssh_command(&remote, None, &["--profile".to_string()]);
+        let args: Vec<String> = command
+            .as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(command.as_std().get_program(), "ssh");
+        assert_eq!(
+            &args[args.len() - 4..args.len() - 1],
+            ["-p", "2222", "dev-box"]
+        );
+        assert_eq!(
+            args.last().unwrap(),
+            r"cd '/home/me/it'\''s here' && exec 'codex' app-server '--profile'"
+        );
+    }
+}
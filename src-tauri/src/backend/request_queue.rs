@@ -0,0 +1,181 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tokio::sync::oneshot;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RequestPriority {
+    Normal,
+    /// Jumps ahead of queued requests and ignores the concurrency limit.
+    Urgent,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub(crate) struct RequestQueueMetrics {
+    pub(crate) limit: usize,
+    #[serde(rename = "inFlight")]
+    pub(crate) in_flight: usize,
+    pub(crate) queued: usize,
+    #[serde(rename = "peakQueued")]
+    pub(crate) peak_queued: usize,
+    pub(crate) dispatched: u64,
+}
+
+#[derive(Default)]
+struct QueueState {
+    limit: usize,
+    in_flight: usize,
+    waiting: VecDeque<oneshot::Sender<()>>,
+    peak_queued: usize,
+    dispatched: u64,
+}
+
+/// Caps how many requests a session has outstanding at once. A limit of `0`
+/// disables queueing.
+pub(crate) struct RequestQueue {
+    state: Mutex<QueueState>,
+}
+
+/// Holds one in-flight slot; dropping it hands the slot to the next waiter.
+pub(crate) struct RequestSlot<'a> {
+    queue: &'a RequestQueue,
+}
+
+impl Drop for RequestSlot<'_> {
+    fn drop(&mut self) {
+        self.queue.release();
+    }
+}
+
+struct SlotWaiter<'a> {
+    queue: &'a RequestQueue,
+    rx: oneshot::Receiver<()>,
+    granted: bool,
+}
+
+impl Drop for SlotWaiter<'_> {
+    fn drop(&mut self) {
+        if self.granted {
+            return;
+        }
+        // A caller that gave up may already have been handed a slot.
+        self.rx.close();
+        if self.rx.try_recv().is_ok() {
+            self.queue.release();
+        }
+    }
+}
+
+impl RequestQueue {
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                limit,
+                ..QueueState::default()
+            }),
+        }
+    }
+
+    pub(crate) fn set_limit(&self, limit: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.limit = limit;
+        while limit == 0 || state.in_flight < limit {
+            let Some(tx) = state.waiting.pop_front() else {
+                break;
+            };
+            if tx.send(()).is_ok() {
+                state.in_flight += 1;
+                state.dispatched += 1;
+            }
+        }
+    }
+
+    pub(crate) fn metrics(&self) -> RequestQueueMetrics {
+        let state = self.state.lock().unwrap();
+        RequestQueueMetrics {
+            limit: state.limit,
+            in_flight: state.in_flight,
+            queued: state.waiting.iter().filter(|tx| !tx.is_closed()).count(),
+            peak_queued: state.peak_queued,
+            dispatched: state.dispatched,
+        }
+    }
+
+    pub(crate) async fn acquire(&self, priority: RequestPriority) -> RequestSlot<'_> {
+        let rx = {
+            let mut state = self.state.lock().unwrap();
+            if priority == RequestPriority::Urgent
+                || state.limit == 0
+                || state.in_flight < state.limit
+            {
+                state.in_flight += 1;
+                state.dispatched += 1;
+                return RequestSlot { queue: self };
+            }
+            let (tx, rx) = oneshot::channel();
+            state.waiting.retain(|waiting| !waiting.is_closed());
+            state.waiting.push_back(tx);
+            state.peak_queued = state.peak_queued.max(state.waiting.len());
+            rx
+        };
+        let mut waiter = SlotWaiter {
+            queue: self,
+            rx,
+            granted: false,
+        };
+        let _ = (&mut waiter.rx).await;
+        waiter.granted = true;
+        RequestSlot { queue: self }
+    }
+
+    fn release(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.limit == 0 || state.in_flight <= state.limit {
+            while let Some(tx) = state.waiting.pop_front() {
+                if tx.send(()).is_ok() {
+                    state.dispatched += 1;
+                    return;
+                }
+            }
+        }
+        state.in_flight = state.in_flight.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RequestPriority, RequestQueue};
+    use std::time::Duration;
+
+    #[test]
+    fn queue_limits_in_flight_and_lets_urgent_requests_through() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("runtime");
+        runtime.block_on(async {
+            let queue = RequestQueue::new(1);
+            let first = queue.acquire(RequestPriority::Normal).await;
+            let blocked = tokio::time::timeout(
+                Duration::from_millis(20),
+                queue.acquire(RequestPriority::Normal),
+            )
+            .await;
+            assert!(blocked.is_err());
+            assert_eq!(queue.metrics().queued, 0);
+
+            let urgent = queue.acquire(RequestPriority::Urgent).await;
+            assert_eq!(queue.metrics().in_flight, 2);
+            drop(urgent);
+            drop(first);
+            assert_eq!(queue.metrics().in_flight, 0);
+
+            let _slot = queue.acquire(RequestPriority::Normal).await;
+            let metrics = queue.metrics();
+            assert_eq!(metrics.in_flight, 1);
+            assert_eq!(metrics.peak_queued, 1);
+            assert_eq!(metrics.dispatched, 3);
+        });
+    }
+}
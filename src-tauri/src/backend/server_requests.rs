@@ -0,0 +1,274 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Approval decisions as `(v2 name, legacy name)`. The v2 protocol answers
+/// `item/*/requestApproval` with the first form, the legacy
+/// `execCommandApproval` / `applyPatchApproval` requests with the second.
+const DECISIONS: [(&str, &str); 4] = [
+    ("accept", "approved"),
+    ("acceptForSession", "approved_for_session"),
+    ("decline", "denied"),
+    ("cancel", "abort"),
+];
+
+/// What a server-initiated request is asking the user for.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub(crate) enum ServerRequestKind {
+    CommandApproval {
+        command: Option<String>,
+        cwd: Option<String>,
+        reason: Option<String>,
+    },
+    FileChangeApproval {
+        paths: Vec<String>,
+        reason: Option<String>,
+        #[serde(rename = "grantRoot")]
+        grant_root: Option<String>,
+    },
+    /// Any other request; its result is passed through unchecked.
+    Other,
+}
+
+/// A request the app-server sent to us that still needs an answer.
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct ServerRequest {
+    pub(crate) id: u64,
+    pub(crate) method: String,
+    #[serde(rename = "threadId")]
+    pub(crate) thread_id: Option<String>,
+    #[serde(flatten)]
+    pub(crate) kind: ServerRequestKind,
+    #[serde(rename = "receivedAt")]
+    pub(crate) received_at: u64,
+    /// Uses the legacy `approved` / `denied` decision names.
+    #[serde(skip)]
+    legacy: bool,
+}
+
+fn read_str(params: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| params.get(*key).and_then(Value::as_str))
+        .map(str::to_string)
+}
+
+fn read_command(params: &Value) -> Option<String> {
+    match params.get("command")? {
+        Value::String(command) => Some(command.clone()),
+        Value::Array(parts) => Some(
+            parts
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        _ => None,
+    }
+}
+
+fn read_paths(params: &Value) -> Vec<String> {
+    match params.get("changes").or_else(|| params.get("fileChanges")) {
+        Some(Value::Array(changes)) => changes
+            .iter()
+            .filter_map(|change| change.get("path").and_then(Value::as_str))
+            .map(str::to_string)
+            .collect(),
+        Some(Value::Object(changes)) => changes.keys().cloned().collect(),
+        _ => Vec::new(),
+    }
+}
+
+impl ServerRequest {
+    /// Parses a message carrying both `id` and `method`. Returns `None` for
+    /// notifications and responses.
+    pub(crate) fn parse(message: &Value, received_at: u64) -> Option<Self> {
+        let id = message.get("id").and_then(Value::as_u64)?;
+        let method = message.get("method").and_then(Value::as_str)?;
+        let params = message.get("params").unwrap_or(&Value::Null);
+        let reason = read_str(params, &["reason"]);
+        let (kind, legacy) = match method {
+            "execCommandApproval" | "item/commandExecution/requestApproval" => (
+                ServerRequestKind::CommandApproval {
+                    command: read_command(params),
+                    cwd: read_str(params, &["cwd"]),
+                    reason,
+                },
+                method == "execCommandApproval",
+            ),
+            "applyPatchApproval" | "item/fileChange/requestApproval" => (
+                ServerRequestKind::FileChangeApproval {
+                    paths: read_paths(params),
+                    reason,
+                    grant_root: read_str(params, &["grantRoot", "grant_root"]),
+                },
+                method == "applyPatchApproval",
+            ),
+            _ => (ServerRequestKind::Other, false),
+        };
+        Some(Self {
+            id,
+            method: method.to_string(),
+            thread_id: read_str(params, &["threadId", "thread_id", "conversationId"]),
+            kind,
+            received_at,
+            legacy,
+        })
+    }
+
+    /// Checks `result` against what this request accepts. Approval
+    /// decisions may use either protocol's names and are rewritten to the
+    /// one the server expects.
+    pub(crate) fn prepare_result(&self, result: Value) -> Result<Value, String> {
+        if self.kind == ServerRequestKind::Other {
+            return Ok(result);
+        }
+        let decision = result
+            .get("decision")
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("`{}` needs a `decision` in its result.", self.method))?;
+        let (current, legacy) = DECISIONS
+            .iter()
+            .find(|(current, legacy)| decision == *current || decision == *legacy)
+            .ok_or_else(|| format!("`{decision}` is not a valid approval decision."))?;
+        let mut result = result;
+        result["decision"] = json!(if self.legacy { legacy } else { current });
+        Ok(result)
+    }
+}
+
+/// Server requests awaiting an answer from the user, by JSON-RPC id.
+#[derive(Default)]
+pub(crate) struct ServerRequestRegistry {
+    requests: HashMap<u64, ServerRequest>,
+}
+
+impl ServerRequestRegistry {
+    pub(crate) fn insert(&mut self, request: ServerRequest) {
+        self.requests.insert(request.id, request);
+    }
+
+    /// Removes request `id` and returns `result` ready to send. Unknown ids
+    /// and invalid results leave the registry untouched.
+    pub(crate) fn resolve(&mut self, id: u64, result: Value) -> Result<Value, String> {
+        let request = self
+            .requests
+            .get(&id)
+            .ok_or_else(|| format!("No pending server request with id {id}."))?;
+        let result = request.prepare_result(result)?;
+        self.requests.remove(&id);
+        Ok(result)
+    }
+
+    /// Pending requests, oldest first.
+    pub(crate) fn list(&self) -> Vec<ServerRequest> {
+        let mut requests: Vec<ServerRequest> = self.requests.values().cloned().collect();
+        requests.sort_by_key(|request| (request.received_at, request.id));
+        requests
+    }
+
+    /// Drops request `id` after the server withdrew it. Returns whether it
+    /// was pending.
+    pub(crate) fn cancel(&mut self, id: u64) -> bool {
+        self.requests.remove(&id).is_some()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.requests.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ServerRequest, ServerRequestKind, ServerRequestRegistry};
+    use serde_json::json;
+
+    #[test]
+    fn parse_types_v2_and_legacy_approvals() {
+        let exec = ServerRequest::parse(
+            &json!({
+                "id": 3,
+                "method": "execCommandApproval",
+                "params": {
+                    "conversationId": "t1",
+                    "command": ["cargo", "test"],
+                    "cwd": "/repo",
+                    "reason": "run tests"
+                }
+            }),
+            10,
+        )
+        .expect("request");
+        assert_eq!(exec.thread_id.as_deref(), Some("t1"));
+        assert_eq!(
+            exec.kind,
+            ServerRequestKind::CommandApproval {
+                command: Some("cargo test".to_string()),
+                cwd: Some("/repo".to_string()),
+                reason: Some("run tests".to_string()),
+            }
+        );
+
+        let patch = ServerRequest::parse(
+            &json!({
+                "id": 4,
+                "method": "item/fileChange/requestApproval",
+                "params": { "threadId": "t1", "grantRoot": "/repo" }
+            }),
+            11,
+        )
+        .expect("request");
+        let value = serde_json::to_value(&patch).unwrap();
+        assert_eq!(value["kind"], "fileChangeApproval");
+        assert_eq!(value["grantRoot"], "/repo");
+
+        assert!(ServerRequest::parse(&json!({ "method": "turn/started" }), 0).is_none());
+        assert!(ServerRequest::parse(&json!({ "id": 5, "result": {} }), 0).is_none());
+    }
+
+    #[test]
+    fn resolve_validates_and_translates_decisions() {
+        let mut registry = ServerRequestRegistry::default();
+        for (id, method) in [
+            (1, "execCommandApproval"),
+            (2, "item/commandExecution/requestApproval"),
+            (3, "tool/custom"),
+        ] {
+            let message = json!({ "id": id, "method": method, "params": {} });
+            registry.insert(ServerRequest::parse(&message, id).unwrap());
+        }
+
+        assert!(registry.resolve(1, json!({ "decision": "maybe" })).is_err());
+        assert!(registry.resolve(2, json!({})).is_err());
+        assert_eq!(registry.list().len(), 3);
+
+        assert_eq!(
+            registry
+                .resolve(1, json!({ "decision": "accept" }))
+                .unwrap(),
+            json!({ "decision": "approved" })
+        );
+        assert_eq!(
+            registry
+                .resolve(2, json!({ "decision": "denied" }))
+                .unwrap(),
+            json!({ "decision": "decline" })
+        );
+        assert_eq!(
+            registry.resolve(3, json!({ "anything": true })).unwrap(),
+            json!({ "anything": true })
+        );
+        assert!(registry
+            .resolve(1, json!({ "decision": "accept" }))
+            .is_err());
+        assert!(registry.list().is_empty());
+
+        let message = json!({ "id": 8, "method": "execCommandApproval", "params": {} });
+        registry.insert(ServerRequest::parse(&message, 12).unwrap());
+        assert!(registry.cancel(8));
+        assert!(!registry.cancel(8));
+        assert!(registry
+            .resolve(8, json!({ "decision": "accept" }))
+            .is_err());
+    }
+}
@@ -1,7 +1,11 @@
+#[path = "../attachments.rs"]
+mod attachments;
 #[path = "../backend/mod.rs"]
 mod backend;
 #[path = "../codex_config.rs"]
 mod codex_config;
+#[path = "../file_index.rs"]
+mod file_index;
 #[path = "../storage.rs"]
 mod storage;
 #[path = "../types.rs"]
@@ -13,6 +17,7 @@ use std::env;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use ignore::WalkBuilder;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
@@ -21,18 +26,33 @@ use tokio::process::Command;
 use tokio::sync::{broadcast, mpsc, Mutex};
 use uuid::Uuid;
 
-use backend::app_server::{spawn_workspace_session, WorkspaceSession};
+use attachments::{attachment_inputs, image_input};
+use backend::app_server::{
+    spawn_workspace_session, validate_codex_args, validate_verbosity, ClientInfo, WorkspaceSession,
+    DEFAULT_BACKEND_LOG_LINES,
+};
 use backend::events::{AppServerEvent, EventSink, TerminalOutput};
+use backend::protocol::{
+    access_policies, ApprovalPolicy, CollaborationModeListParams, ModelListParams,
+    RateLimitsReadParams, ReviewStartParams, SkillsListParams, ThreadArchiveParams,
+    ThreadListParams, ThreadResumeParams, ThreadStartParams, TurnInterruptParams, TurnStartParams,
+};
+use file_index::{invalidates_index, search_files, FileIndexCache};
 use storage::{read_settings, read_workspaces, write_settings, write_workspaces};
 use types::{
-    AppSettings, WorkspaceEntry, WorkspaceInfo, WorkspaceKind, WorkspaceSettings, WorktreeInfo,
+    workspace_ids_in_group, AppSettings, RemoteWorkspace, WorkspaceEntry, WorkspaceInfo,
+    WorkspaceKind, WorkspaceSettings, WorktreeInfo,
 };
 
 const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:4732";
+const JSONRPC_VERSION: &str = "2.0";
+const SERIALIZATION_FAILED: &str =
+    "{\"jsonrpc\":\"2.0\",\"id\":0,\"error\":{\"message\":\"serialization failed\"}}";
 
 #[derive(Clone)]
 struct DaemonEventSink {
     tx: broadcast::Sender<DaemonEvent>,
+    file_index: Arc<FileIndexCache>,
 }
 
 #[derive(Clone)]
@@ -43,6 +63,13 @@ enum DaemonEvent {
 
 impl EventSink for DaemonEventSink {
     fn emit_app_server_event(&self, event: AppServerEvent) {
+        if invalidates_index(&event.message) {
+            let file_index = self.file_index.clone();
+            let workspace_id = event.workspace_id.clone();
+            tokio::spawn(async move {
+                file_index.invalidate(&workspace_id).await;
+            });
+        }
         let _ = self.tx.send(DaemonEvent::AppServer(event));
     }
 
@@ -64,24 +91,28 @@ struct DaemonState {
     storage_path: PathBuf,
     settings_path: PathBuf,
     app_settings: Mutex<AppSettings>,
+    file_index: Arc<FileIndexCache>,
     event_sink: DaemonEventSink,
 }
 
 impl DaemonState {
-    fn load(config: &DaemonConfig, event_sink: DaemonEventSink) -> Self {
+    fn load(config: &DaemonConfig, event_sink: DaemonEventSink) -> Result<Self, String> {
         let storage_path = config.data_dir.join("workspaces.json");
         let settings_path = config.data_dir.join("settings.json");
-        let workspaces = read_workspaces(&storage_path).unwrap_or_default();
-        let app_settings = read_settings(&settings_path).unwrap_or_default();
-        Self {
+        let workspaces = read_workspaces(&storage_path)
+            .map_err(|err| format!("Failed to read {}: {err}", storage_path.display()))?;
+        let app_settings = read_settings(&settings_path)
+            .map_err(|err| format!("Failed to read {}: {err}", settings_path.display()))?;
+        Ok(Self {
             data_dir: config.data_dir.clone(),
             workspaces: Mutex::new(workspaces),
             sessions: Mutex::new(HashMap::new()),
             storage_path,
             settings_path,
             app_settings: Mutex::new(app_settings),
+            file_index: event_sink.file_index.clone(),
             event_sink,
-        }
+        })
     }
 
     async fn kill_session(&self, workspace_id: &str) {
@@ -94,8 +125,20 @@ impl DaemonState {
             return;
         };
 
-        let mut child = session.child.lock().await;
-        let _ = child.kill().await;
+        session.terminate().await;
+    }
+
+    async fn shutdown_sessions(&self) {
+        let sessions: Vec<_> = self
+            .sessions
+            .lock()
+            .await
+            .drain()
+            .map(|(_, session)| session)
+            .collect();
+        for session in sessions {
+            session.shutdown(Duration::from_secs(2)).await;
+        }
     }
 
     async fn list_workspaces(&self) -> Vec<WorkspaceInfo> {
@@ -123,8 +166,14 @@ impl DaemonState {
         &self,
         path: String,
         codex_bin: Option<String>,
+        remote: Option<RemoteWorkspace>,
         client_version: String,
     ) -> Result<WorkspaceInfo, String> {
+        let mut workspace_settings = WorkspaceSettings::default();
+        if let Some(mut remote) = remote {
+            remote.normalize()?;
+            workspace_settings.remote = Some(remote);
+        }
         let name = PathBuf::from(&path)
             .file_name()
             .and_then(|s| s.to_str())
@@ -139,23 +188,23 @@ impl DaemonState {
             kind: WorkspaceKind::Main,
             parent_id: None,
             worktree: None,
-            settings: WorkspaceSettings::default(),
+            settings: workspace_settings,
         };
 
-        let default_bin = {
-            let settings = self.app_settings.lock().await;
-            settings.codex_bin.clone()
-        };
+        let settings = self.app_settings.lock().await.clone();
+        let default_bin = settings.codex_bin.clone();
 
         let codex_home = resolve_codex_home(&entry, None);
         let session = spawn_workspace_session(
             entry.clone(),
             default_bin,
-            client_version,
+            settings.codex_args.clone(),
+            ClientInfo::new(client_version, &settings),
             self.event_sink.clone(),
             codex_home,
         )
         .await?;
+        session.apply_settings(&settings);
 
         let list = {
             let mut workspaces = self.workspaces.lock().await;
@@ -241,24 +290,25 @@ impl DaemonState {
             parent_id: Some(parent_entry.id.clone()),
             worktree: Some(WorktreeInfo {
                 branch: branch.to_string(),
+                thread_id: None,
             }),
             settings: WorkspaceSettings::default(),
         };
 
-        let default_bin = {
-            let settings = self.app_settings.lock().await;
-            settings.codex_bin.clone()
-        };
+        let settings = self.app_settings.lock().await.clone();
+        let default_bin = settings.codex_bin.clone();
 
         let codex_home = resolve_codex_home(&entry, Some(&parent_entry.path));
         let session = spawn_workspace_session(
             entry.clone(),
             default_bin,
-            client_version,
+            settings.codex_args.clone(),
+            ClientInfo::new(client_version, &settings),
             self.event_sink.clone(),
             codex_home,
         )
         .await?;
+        session.apply_settings(&settings);
 
         let list = {
             let mut workspaces = self.workspaces.lock().await;
@@ -391,8 +441,11 @@ impl DaemonState {
     async fn update_workspace_settings(
         &self,
         id: String,
-        settings: WorkspaceSettings,
+        mut settings: WorkspaceSettings,
     ) -> Result<WorkspaceInfo, String> {
+        if let Some(remote) = settings.remote.as_mut() {
+            remote.normalize()?;
+        }
         let (entry_snapshot, list) = {
             let mut workspaces = self.workspaces.lock().await;
             let entry_snapshot = match workspaces.get_mut(&id) {
@@ -457,7 +510,7 @@ impl DaemonState {
     async fn connect_workspace(&self, id: String, client_version: String) -> Result<(), String> {
         {
             let sessions = self.sessions.lock().await;
-            if sessions.contains_key(&id) {
+            if sessions.get(&id).is_some_and(|session| !session.is_closed()) {
                 return Ok(());
             }
         }
@@ -470,10 +523,8 @@ impl DaemonState {
                 .ok_or("workspace not found")?
         };
 
-        let default_bin = {
-            let settings = self.app_settings.lock().await;
-            settings.codex_bin.clone()
-        };
+        let settings = self.app_settings.lock().await.clone();
+        let default_bin = settings.codex_bin.clone();
 
         let parent_path = if entry.kind.is_worktree() {
             let workspaces = self.workspaces.lock().await;
@@ -489,21 +540,31 @@ impl DaemonState {
         let session = spawn_workspace_session(
             entry,
             default_bin,
-            client_version,
+            settings.codex_args.clone(),
+            ClientInfo::new(client_version, &settings),
             self.event_sink.clone(),
             codex_home,
         )
         .await?;
+        session.apply_settings(&settings);
 
         self.sessions.lock().await.insert(id, session);
         Ok(())
     }
 
-    async fn update_app_settings(&self, settings: AppSettings) -> Result<AppSettings, String> {
+    async fn update_app_settings(&self, mut settings: AppSettings) -> Result<AppSettings, String> {
+        settings.codex_args = validate_codex_args(&settings.codex_args)?;
+        settings.verbosity = validate_verbosity(settings.verbosity.as_deref())?;
+        for group in &mut settings.workspace_groups {
+            group.normalize_color()?;
+        }
         let _ = codex_config::write_collab_enabled(settings.experimental_collab_enabled);
         let _ = codex_config::write_steer_enabled(settings.experimental_steer_enabled);
         let _ = codex_config::write_unified_exec_enabled(settings.experimental_unified_exec_enabled);
         write_settings(&self.settings_path, &settings)?;
+        for session in self.sessions.lock().await.values() {
+            session.apply_settings(&settings);
+        }
         let mut current = self.app_settings.lock().await;
         *current = settings.clone();
         Ok(settings)
@@ -517,7 +578,12 @@ impl DaemonState {
             .ok_or("workspace not connected".to_string())
     }
 
-    async fn list_workspace_files(&self, workspace_id: String) -> Result<Vec<String>, String> {
+    async fn list_workspace_files(
+        &self,
+        workspace_id: String,
+        query: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<Vec<String>, String> {
         let entry = {
             let workspaces = self.workspaces.lock().await;
             workspaces
@@ -527,24 +593,31 @@ impl DaemonState {
         };
 
         let root = PathBuf::from(entry.path);
-        Ok(list_workspace_files_inner(&root, 20000))
+        let files = self
+            .file_index
+            .get_or_build(&workspace_id, move || {
+                list_workspace_files_inner(&root, 20000)
+            })
+            .await?;
+        Ok(search_files(
+            &files,
+            query.as_deref().unwrap_or(""),
+            limit.map(|limit| limit as usize).unwrap_or(usize::MAX),
+        ))
     }
 
     async fn start_thread(&self, workspace_id: String) -> Result<Value, String> {
         let session = self.get_session(&workspace_id).await?;
-        let params = json!({
-            "cwd": session.entry.path,
-            "approvalPolicy": "on-request"
-        });
-        session.send_request("thread/start", params).await
+        let params = ThreadStartParams {
+            cwd: session.entry.path.clone(),
+            approval_policy: ApprovalPolicy::OnRequest,
+        };
+        session.request(&params).await
     }
 
     async fn resume_thread(&self, workspace_id: String, thread_id: String) -> Result<Value, String> {
         let session = self.get_session(&workspace_id).await?;
-        let params = json!({
-            "threadId": thread_id
-        });
-        session.send_request("thread/resume", params).await
+        session.request(&ThreadResumeParams { thread_id }).await
     }
 
     async fn list_threads(
@@ -554,17 +627,12 @@ impl DaemonState {
         limit: Option<u32>,
     ) -> Result<Value, String> {
         let session = self.get_session(&workspace_id).await?;
-        let params = json!({
-            "cursor": cursor,
-            "limit": limit
-        });
-        session.send_request("thread/list", params).await
+        session.request(&ThreadListParams { cursor, limit }).await
     }
 
     async fn archive_thread(&self, workspace_id: String, thread_id: String) -> Result<Value, String> {
         let session = self.get_session(&workspace_id).await?;
-        let params = json!({ "threadId": thread_id });
-        session.send_request("thread/archive", params).await
+        session.request(&ThreadArchiveParams { thread_id }).await
     }
 
     async fn send_user_message(
@@ -576,29 +644,19 @@ impl DaemonState {
         effort: Option<String>,
         access_mode: Option<String>,
         images: Option<Vec<String>>,
+        attachments: Option<Vec<String>>,
         collaboration_mode: Option<Value>,
     ) -> Result<Value, String> {
         let session = self.get_session(&workspace_id).await?;
-        let access_mode = access_mode.unwrap_or_else(|| "current".to_string());
-        let sandbox_policy = match access_mode.as_str() {
-            "full-access" => json!({
-                "type": "dangerFullAccess"
-            }),
-            "read-only" => json!({
-                "type": "readOnly"
-            }),
-            _ => json!({
-                "type": "workspaceWrite",
-                "writableRoots": [session.entry.path],
-                "networkAccess": true
-            }),
-        };
-
-        let approval_policy = if access_mode == "full-access" {
-            "never"
-        } else {
-            "on-request"
-        };
+        let steer_enabled = self.app_settings.lock().await.experimental_steer_enabled;
+        // With steering on, a message sent mid-turn is meant for the running turn.
+        if !steer_enabled {
+            if let Some(response) = session.turn_in_progress(&thread_id).await {
+                return Ok(response);
+            }
+        }
+        let (approval_policy, sandbox_policy) =
+            access_policies(access_mode.as_deref(), &session.entry.path);
 
         let trimmed_text = text.trim();
         let mut input: Vec<Value> = Vec::new();
@@ -608,34 +666,29 @@ impl DaemonState {
         if let Some(paths) = images {
             for path in paths {
                 let trimmed = path.trim();
-                if trimmed.is_empty() {
-                    continue;
-                }
-                if trimmed.starts_with("data:")
-                    || trimmed.starts_with("http://")
-                    || trimmed.starts_with("https://")
-                {
-                    input.push(json!({ "type": "image", "url": trimmed }));
-                } else {
-                    input.push(json!({ "type": "localImage", "path": trimmed }));
+                if !trimmed.is_empty() {
+                    input.push(image_input(trimmed));
                 }
             }
         }
+        if let Some(paths) = attachments {
+            input.extend(attachment_inputs(&paths)?);
+        }
         if input.is_empty() {
             return Err("empty user message".to_string());
         }
 
-        let params = json!({
-            "threadId": thread_id,
-            "input": input,
-            "cwd": session.entry.path,
-            "approvalPolicy": approval_policy,
-            "sandboxPolicy": sandbox_policy,
-            "model": model,
-            "effort": effort,
-            "collaborationMode": collaboration_mode,
-        });
-        session.send_request("turn/start", params).await
+        let params = TurnStartParams {
+            thread_id,
+            input,
+            cwd: session.entry.path.clone(),
+            approval_policy,
+            sandbox_policy,
+            model,
+            effort,
+            collaboration_mode,
+        };
+        session.request(&params).await
     }
 
     async fn turn_interrupt(
@@ -645,11 +698,44 @@ impl DaemonState {
         turn_id: String,
     ) -> Result<Value, String> {
         let session = self.get_session(&workspace_id).await?;
-        let params = json!({
-            "threadId": thread_id,
-            "turnId": turn_id
-        });
-        session.send_request("turn/interrupt", params).await
+        session
+            .request(&TurnInterruptParams { thread_id, turn_id })
+            .await
+    }
+
+    async fn interrupt_all(&self, workspace_id: String) -> Result<Value, String> {
+        let session = self.get_session(&workspace_id).await?;
+        Ok(session.interrupt_all().await)
+    }
+
+    async fn interrupt_workspace_group(&self, group_id: String) -> Result<Value, String> {
+        let workspace_ids = {
+            let workspaces = self.workspaces.lock().await;
+            workspace_ids_in_group(&workspaces, &group_id)
+        };
+        let mut results = Map::new();
+        let mut not_connected = Vec::new();
+        for workspace_id in workspace_ids {
+            let session = self.sessions.lock().await.get(&workspace_id).cloned();
+            match session {
+                Some(session) => {
+                    results.insert(workspace_id, session.interrupt_all().await);
+                }
+                None => not_connected.push(workspace_id),
+            }
+        }
+        Ok(json!({ "results": results, "notConnected": not_connected }))
+    }
+
+    async fn kill_backend(&self, workspace_id: String) -> Result<Value, String> {
+        let session = self
+            .sessions
+            .lock()
+            .await
+            .remove(&workspace_id)
+            .ok_or("workspace not connected")?;
+        session.shutdown(Duration::ZERO).await;
+        Ok(json!({ "ok": true }))
     }
 
     async fn start_review(
@@ -660,42 +746,75 @@ impl DaemonState {
         delivery: Option<String>,
     ) -> Result<Value, String> {
         let session = self.get_session(&workspace_id).await?;
-        let mut params = Map::new();
-        params.insert("threadId".to_string(), json!(thread_id));
-        params.insert("target".to_string(), target);
-        if let Some(delivery) = delivery {
-            params.insert("delivery".to_string(), json!(delivery));
-        }
-        session
-            .send_request("review/start", Value::Object(params))
-            .await
+        let params = ReviewStartParams {
+            thread_id,
+            target,
+            delivery,
+        };
+        session.request(&params).await
     }
 
     async fn model_list(&self, workspace_id: String) -> Result<Value, String> {
         let session = self.get_session(&workspace_id).await?;
-        session.send_request("model/list", json!({})).await
+        session.request(&ModelListParams::default()).await
     }
 
     async fn collaboration_mode_list(&self, workspace_id: String) -> Result<Value, String> {
         let session = self.get_session(&workspace_id).await?;
         session
-            .send_request("collaborationMode/list", json!({}))
+            .request(&CollaborationModeListParams::default())
             .await
     }
 
     async fn account_rate_limits(&self, workspace_id: String) -> Result<Value, String> {
         let session = self.get_session(&workspace_id).await?;
-        session
-            .send_request("account/rateLimits/read", Value::Null)
-            .await
+        session.request(&RateLimitsReadParams).await
     }
 
     async fn skills_list(&self, workspace_id: String) -> Result<Value, String> {
         let session = self.get_session(&workspace_id).await?;
-        let params = json!({
-            "cwd": session.entry.path
-        });
-        session.send_request("skills/list", params).await
+        let params = SkillsListParams {
+            cwd: session.entry.path.clone(),
+        };
+        session.request(&params).await
+    }
+
+    async fn get_backend_logs(
+        &self,
+        workspace_id: String,
+        lines: Option<u32>,
+    ) -> Result<Value, String> {
+        let session = self.get_session(&workspace_id).await?;
+        let logs = session
+            .recent_logs(
+                lines
+                    .map(|value| value as usize)
+                    .unwrap_or(DEFAULT_BACKEND_LOG_LINES),
+            )
+            .await;
+        serde_json::to_value(logs).map_err(|err| err.to_string())
+    }
+
+    async fn replay_events(&self, workspace_id: String, since_seq: u64) -> Result<Value, String> {
+        let session = self.get_session(&workspace_id).await?;
+        let events = session.events_since(since_seq).await;
+        serde_json::to_value(events).map_err(|err| err.to_string())
+    }
+
+    async fn get_request_queue_metrics(&self, workspace_id: String) -> Result<Value, String> {
+        let session = self.get_session(&workspace_id).await?;
+        serde_json::to_value(session.request_queue_metrics()).map_err(|err| err.to_string())
+    }
+
+    async fn get_session_stats(&self, workspace_id: String) -> Result<Value, String> {
+        let session = self.get_session(&workspace_id).await?;
+        serde_json::to_value(session.stats().await).map_err(|err| err.to_string())
+    }
+
+    async fn get_backend_capabilities(&self, workspace_id: String) -> Result<Value, String> {
+        let session = self.get_session(&workspace_id).await?;
+        let capabilities = session.capabilities.lock().await.clone();
+        serde_json::to_value(capabilities).map_err(|err| err.to_string())
     }
 
     async fn respond_to_server_request(
@@ -705,9 +824,17 @@ impl DaemonState {
         result: Value,
     ) -> Result<Value, String> {
         let session = self.get_session(&workspace_id).await?;
-        session.send_response(request_id, result).await?;
+        session
+            .respond_to_server_request(request_id, result)
+            .await?;
         Ok(json!({ "ok": true }))
     }
+
+    async fn list_server_requests(&self, workspace_id: String) -> Result<Value, String> {
+        let session = self.get_session(&workspace_id).await?;
+        serde_json::to_value(session.pending_server_requests().await)
+            .map_err(|err| err.to_string())
+    }
 }
 
 fn sort_workspaces(workspaces: &mut [WorkspaceInfo]) {
@@ -987,27 +1114,31 @@ fn build_error_response(id: Option<u64>, message: &str) -> Option<String> {
     let id = id?;
     Some(
         serde_json::to_string(&json!({
+            "jsonrpc": JSONRPC_VERSION,
             "id": id,
             "error": { "message": message }
         }))
-        .unwrap_or_else(|_| "{\"id\":0,\"error\":{\"message\":\"serialization failed\"}}".to_string()),
+        .unwrap_or_else(|_| SERIALIZATION_FAILED.to_string()),
     )
 }
 
 fn build_result_response(id: Option<u64>, result: Value) -> Option<String> {
     let id = id?;
-    Some(serde_json::to_string(&json!({ "id": id, "result": result })).unwrap_or_else(|_| {
-        "{\"id\":0,\"error\":{\"message\":\"serialization failed\"}}".to_string()
-    }))
+    Some(
+        serde_json::to_string(&json!({ "jsonrpc": JSONRPC_VERSION, "id": id, "result": result }))
+            .unwrap_or_else(|_| SERIALIZATION_FAILED.to_string()),
+    )
 }
 
 fn build_event_notification(event: DaemonEvent) -> Option<String> {
     let payload = match event {
         DaemonEvent::AppServer(payload) => json!({
+            "jsonrpc": JSONRPC_VERSION,
             "method": "app-server-event",
             "params": payload,
         }),
         DaemonEvent::TerminalOutput(payload) => json!({
+            "jsonrpc": JSONRPC_VERSION,
             "method": "terminal-output",
             "params": payload,
         }),
@@ -1015,6 +1146,15 @@ fn build_event_notification(event: DaemonEvent) -> Option<String> {
     serde_json::to_string(&payload).ok()
 }
 
+/// Clients may omit `jsonrpc`; when present it has to be `"2.0"`.
+fn check_jsonrpc_version(message: &Value) -> Result<(), String> {
+    match message.get("jsonrpc") {
+        None => Ok(()),
+        Some(Value::String(version)) if version == JSONRPC_VERSION => Ok(()),
+        Some(other) => Err(format!("unsupported jsonrpc version {other}")),
+    }
+}
+
 fn parse_auth_token(params: &Value) -> Option<String> {
     match params {
         Value::String(value) => Some(value.clone()),
@@ -1101,7 +1241,15 @@ async fn handle_rpc_request(
         "add_workspace" => {
             let path = parse_string(&params, "path")?;
             let codex_bin = parse_optional_string(&params, "codex_bin");
-            let workspace = state.add_workspace(path, codex_bin, client_version).await?;
+            let remote: Option<RemoteWorkspace> = match params.get("remote") {
+                Some(value) if !value.is_null() => {
+                    Some(serde_json::from_value(value.clone()).map_err(|err| err.to_string())?)
+                }
+                _ => None,
+            };
+            let workspace = state
+                .add_workspace(path, codex_bin, remote, client_version)
+                .await?;
             serde_json::to_value(workspace).map_err(|err| err.to_string())
         }
         "add_worktree" => {
@@ -1146,7 +1294,11 @@ async fn handle_rpc_request(
         }
         "list_workspace_files" => {
             let workspace_id = parse_string(&params, "workspaceId")?;
-            let files = state.list_workspace_files(workspace_id).await?;
+            let query = parse_optional_string(&params, "query");
+            let limit = parse_optional_u32(&params, "limit");
+            let files = state
+                .list_workspace_files(workspace_id, query, limit)
+                .await?;
             serde_json::to_value(files).map_err(|err| err.to_string())
         }
         "get_app_settings" => {
@@ -1200,6 +1352,7 @@ async fn handle_rpc_request(
             let effort = parse_optional_string(&params, "effort");
             let access_mode = parse_optional_string(&params, "accessMode");
             let images = parse_optional_string_array(&params, "images");
+            let attachments = parse_optional_string_array(&params, "attachments");
             let collaboration_mode = parse_optional_value(&params, "collaborationMode");
             state
                 .send_user_message(
@@ -1210,6 +1363,7 @@ async fn handle_rpc_request(
                     effort,
                     access_mode,
                     images,
+                    attachments,
                     collaboration_mode,
                 )
                 .await
@@ -1220,6 +1374,18 @@ async fn handle_rpc_request(
             let turn_id = parse_string(&params, "turnId")?;
             state.turn_interrupt(workspace_id, thread_id, turn_id).await
         }
+        "interrupt_all" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            state.interrupt_all(workspace_id).await
+        }
+        "interrupt_workspace_group" => {
+            let group_id = parse_string(&params, "groupId")?;
+            state.interrupt_workspace_group(group_id).await
+        }
+        "kill_backend" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            state.kill_backend(workspace_id).await
+        }
         "start_review" => {
             let workspace_id = parse_string(&params, "workspaceId")?;
             let thread_id = parse_string(&params, "threadId")?;
@@ -1247,6 +1413,32 @@ async fn handle_rpc_request(
             let workspace_id = parse_string(&params, "workspaceId")?;
             state.skills_list(workspace_id).await
         }
+        "get_backend_logs" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let lines = parse_optional_u32(&params, "lines");
+            state.get_backend_logs(workspace_id, lines).await
+        }
+        "get_request_queue_metrics" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            state.get_request_queue_metrics(workspace_id).await
+        }
+        "get_session_stats" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            state.get_session_stats(workspace_id).await
+        }
+        "get_backend_capabilities" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            state.get_backend_capabilities(workspace_id).await
+        }
+        "replay_events" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let since_seq = params
+                .as_object()
+                .and_then(|map| map.get("sinceSeq"))
+                .and_then(|value| value.as_u64())
+                .unwrap_or(0);
+            state.replay_events(workspace_id, since_seq).await
+        }
         "respond_to_server_request" => {
             let workspace_id = parse_string(&params, "workspaceId")?;
             let map = params.as_object().ok_or("missing requestId")?;
@@ -1259,6 +1451,10 @@ async fn handle_rpc_request(
                 .respond_to_server_request(workspace_id, request_id, result)
                 .await
         }
+        "list_server_requests" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            state.list_server_requests(workspace_id).await
+        }
         _ => Err(format!("unknown method: {method}")),
     }
 }
@@ -1333,6 +1529,13 @@ async fn handle_client(
             .to_string();
         let params = message.get("params").cloned().unwrap_or(Value::Null);
 
+        if let Err(err) = check_jsonrpc_version(&message) {
+            if let Some(response) = build_error_response(id, &err) {
+                let _ = out_tx.send(response);
+            }
+            continue;
+        }
+
         if !authenticated {
             if method != "auth" {
                 if let Some(response) = build_error_response(id, "unauthorized") {
@@ -1398,10 +1601,27 @@ fn main() {
         let (events_tx, _events_rx) = broadcast::channel::<DaemonEvent>(2048);
         let event_sink = DaemonEventSink {
             tx: events_tx.clone(),
+            file_index: Arc::new(FileIndexCache::default()),
+        };
+        let state = match DaemonState::load(&config, event_sink) {
+            Ok(state) => Arc::new(state),
+            Err(err) => {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }
         };
-        let state = Arc::new(DaemonState::load(&config, event_sink));
         let config = Arc::new(config);
 
+        {
+            let state = Arc::clone(&state);
+            tokio::spawn(async move {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    state.shutdown_sessions().await;
+                    std::process::exit(0);
+                }
+            });
+        }
+
         let listener = TcpListener::bind(config.listen)
             .await
             .unwrap_or_else(|err| panic!("failed to bind {}: {err}", config.listen));
@@ -1430,3 +1650,33 @@ fn main() {
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{build_error_response, build_result_response, check_jsonrpc_version};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn responses_carry_jsonrpc_version() {
+        let result: Value =
+            serde_json::from_str(&build_result_response(Some(4), json!({ "ok": true })).unwrap())
+                .unwrap();
+        assert_eq!(
+            result,
+            json!({ "jsonrpc": "2.0", "id": 4, "result": { "ok": true } })
+        );
+        let error: Value =
+            serde_json::from_str(&build_error_response(Some(5), "unauthorized").unwrap()).unwrap();
+        assert_eq!(error["jsonrpc"], "2.0");
+        assert_eq!(error["error"]["message"], "unauthorized");
+        assert!(build_result_response(None, Value::Null).is_none());
+    }
+
+    #[test]
+    fn jsonrpc_version_is_optional_but_checked() {
+        assert!(check_jsonrpc_version(&json!({ "id": 1, "method": "ping" })).is_ok());
+        assert!(check_jsonrpc_version(&json!({ "jsonrpc": "2.0", "id": 1 })).is_ok());
+        assert!(check_jsonrpc_version(&json!({ "jsonrpc": "1.0", "id": 1 })).is_err());
+        assert!(check_jsonrpc_version(&json!({ "jsonrpc": 2, "id": 1 })).is_err());
+    }
+}
@@ -0,0 +1,651 @@
+#[allow(dead_code)]
+#[path = "../backend/mod.rs"]
+mod backend;
+#[allow(dead_code)]
+#[path = "../monitor_event.rs"]
+mod monitor_event;
+#[allow(dead_code)]
+#[path = "../types.rs"]
+mod types;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::env;
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::mpsc;
+use tokio::time::{timeout, timeout_at, Instant};
+
+use backend::app_server::ClientInfo;
+use backend::protocol::{
+    ApprovalPolicy, ClientRequest, SandboxPolicy, ThreadArchiveParams, ThreadListParams,
+    ThreadResumeParams, ThreadStartParams, TurnInterruptParams, TurnStartParams,
+};
+use backend::server_requests::{ServerRequest, ServerRequestKind};
+use monitor_event::{normalize, MonitorEvent};
+use types::AppSettings;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long to wait for an exit status once stdout closes.
+const EXIT_GRACE: Duration = Duration::from_secs(1);
+const DEFAULT_TURN_TIMEOUT_SECS: u64 = 180;
+const DEFAULT_PROMPT: &str = "Run `echo conformance` in the shell, then reply with its output.";
+const INTERRUPT_PROMPT: &str =
+    "Count from 1 to 300, one number per line, running `sleep 1` before each number.";
+/// Item types produced by a tool call.
+const TOOL_ITEM_TYPES: [&str; 4] = ["commandExecution", "fileChange", "mcpToolCall", "webSearch"];
+const METHOD_NOT_FOUND_CODE: i64 = -32601;
+
+struct Config {
+    command: Vec<String>,
+    cwd: String,
+    prompt: String,
+    skip_turn: bool,
+    turn_timeout: Duration,
+    json: bool,
+}
+
+fn usage() -> String {
+    format!(
+        "\
+USAGE:\n  protocol_conformance [--cwd <path>] [--prompt <text>] [--turn-timeout <secs>] [--skip-turn] [--json] [-- <command> <args>...]\n\n\
+Runs an app-server through initialize, the thread lifecycle, a turn with a\n\
+tool call and an interrupted turn, and checks the shapes CodexMonitor\n\
+relies on. The command defaults to `codex app-server`.\n\n\
+OPTIONS:\n  --cwd <path>            Workspace for the session (default: current directory)\n  --prompt <text>         Prompt for the tool-call turn\n  --turn-timeout <secs>   Limit per turn (default: {DEFAULT_TURN_TIMEOUT_SECS})\n  --skip-turn             Only check initialize and the thread lifecycle\n  --json                  Print the report as JSON\n  -h, --help              Show this help\n"
+    )
+}
+
+fn parse_args() -> Result<Config, String> {
+    let mut command = Vec::new();
+    let mut cwd: Option<String> = None;
+    let mut prompt = DEFAULT_PROMPT.to_string();
+    let mut skip_turn = false;
+    let mut turn_timeout = Duration::from_secs(DEFAULT_TURN_TIMEOUT_SECS);
+    let mut json = false;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{}", usage());
+                std::process::exit(0);
+            }
+            "--cwd" => {
+                let value = args.next().ok_or("--cwd requires a value")?;
+                cwd = Some(value);
+            }
+            "--prompt" => {
+                let value = args.next().ok_or("--prompt requires a value")?;
+                if value.trim().is_empty() {
+                    return Err("--prompt requires a non-empty value".to_string());
+                }
+                prompt = value;
+            }
+            "--turn-timeout" => {
+                let value = args.next().ok_or("--turn-timeout requires a value")?;
+                let secs = value.parse::<u64>().map_err(|err| err.to_string())?;
+                turn_timeout = Duration::from_secs(secs);
+            }
+            "--skip-turn" => skip_turn = true,
+            "--json" => json = true,
+            "--" => {
+                command.extend(args.by_ref());
+            }
+            _ => return Err(format!("Unknown argument: {arg}")),
+        }
+    }
+
+    if command.is_empty() {
+        command = vec!["codex".to_string(), "app-server".to_string()];
+    }
+    let cwd = match cwd {
+        Some(cwd) => cwd,
+        None => env::current_dir()
+            .map_err(|err| err.to_string())?
+            .to_string_lossy()
+            .to_string(),
+    };
+    Ok(Config {
+        command,
+        cwd,
+        prompt,
+        skip_turn,
+        turn_timeout,
+        json,
+    })
+}
+
+/// Fields CodexMonitor reads from `message` that are missing or empty.
+fn shape_problems(message: &Value) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut require = |present: bool, what: &str| {
+        if !present {
+            problems.push(format!("missing {what}"));
+        }
+    };
+    match normalize(message) {
+        MonitorEvent::TurnStarted { thread_id, turn_id }
+        | MonitorEvent::TurnCompleted { thread_id, turn_id } => {
+            require(!thread_id.is_empty(), "threadId");
+            require(!turn_id.is_empty(), "turn.id");
+        }
+        MonitorEvent::ItemStarted { thread_id, item }
+        | MonitorEvent::ItemCompleted { thread_id, item } => {
+            require(!thread_id.is_empty(), "threadId");
+            require(item.get("id").and_then(Value::as_str).is_some(), "item.id");
+            require(
+                item.get("type").and_then(Value::as_str).is_some(),
+                "item.type",
+            );
+        }
+        MonitorEvent::ItemDelta {
+            thread_id, item_id, ..
+        } => {
+            require(!thread_id.is_empty(), "threadId");
+            require(!item_id.is_empty(), "itemId");
+        }
+        MonitorEvent::TurnError { message, .. } => {
+            require(!message.is_empty(), "error.message");
+        }
+        _ => {}
+    }
+    problems
+}
+
+fn string_at(value: &Value, pointer: &str) -> Option<String> {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+fn is_turn_event(message: &Value, completed: bool, expected: &str) -> bool {
+    match normalize(message) {
+        MonitorEvent::TurnStarted { turn_id, .. } => !completed && turn_id == expected,
+        MonitorEvent::TurnCompleted { turn_id, .. } => completed && turn_id == expected,
+        _ => false,
+    }
+}
+
+/// A raw JSON-RPC connection to the app-server under test.
+struct Session {
+    child: Child,
+    stdin: ChildStdin,
+    messages: mpsc::UnboundedReceiver<Result<Value, String>>,
+    next_id: u64,
+    notifications: Vec<Value>,
+    problems: Vec<String>,
+    unmapped: BTreeSet<String>,
+}
+
+impl Session {
+    fn spawn(config: &Config) -> Result<Self, String> {
+        let (program, args) = config
+            .command
+            .split_first()
+            .ok_or("missing app-server command")?;
+        let mut command = Command::new(program);
+        command.args(args);
+        command.current_dir(&config.cwd);
+        command.stdin(Stdio::piped());
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::inherit());
+        command.kill_on_drop(true);
+        let mut child = command
+            .spawn()
+            .map_err(|err| format!("Failed to start `{program}`: {err}"))?;
+        let stdin = child.stdin.take().ok_or("missing app-server stdin")?;
+        let stdout = child.stdout.take().ok_or("missing app-server stdout")?;
+
+        let (tx, messages) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let message = serde_json::from_str::<Value>(&line)
+                    .map_err(|err| format!("unparseable line ({err}): {line}"));
+                if tx.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            messages,
+            next_id: 1,
+            notifications: Vec::new(),
+            problems: Vec::new(),
+            unmapped: BTreeSet::new(),
+        })
+    }
+
+    async fn write(&mut self, message: Value) -> Result<(), String> {
+        let mut line = serde_json::to_string(&message).map_err(|err| err.to_string())?;
+        line.push('\n');
+        self.stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|err| err.to_string())
+    }
+
+    async fn notify(&mut self, method: &str) -> Result<(), String> {
+        self.write(json!({ "method": method })).await
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.write(json!({ "id": id, "method": method, "params": params }))
+            .await?;
+        let deadline = Instant::now() + REQUEST_TIMEOUT;
+        loop {
+            let Some((response_id, response)) = self.pump(deadline).await? else {
+                continue;
+            };
+            if response_id != id {
+                continue;
+            }
+            if let Some(error) = response.get("error") {
+                let text = error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or("request failed");
+                return Err(format!("{method}: {text}"));
+            }
+            return Ok(response.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    /// Sends typed params and reads the typed part of the result.
+    async fn call<R: ClientRequest>(&mut self, params: &R) -> Result<R::Result, String> {
+        let params = serde_json::to_value(params).map_err(|err| err.to_string())?;
+        let result = self.request(R::METHOD, params).await?;
+        R::Result::deserialize(&result)
+            .map_err(|err| format!("unexpected result ({err}): {result}"))
+    }
+
+    /// The first notification from index `since` on that `matches`,
+    /// reading more until `deadline`.
+    async fn wait_for(
+        &mut self,
+        since: usize,
+        deadline: Instant,
+        matches: impl Fn(&Value) -> bool,
+    ) -> Result<Value, String> {
+        let mut index = since;
+        loop {
+            while index < self.notifications.len() {
+                if matches(&self.notifications[index]) {
+                    return Ok(self.notifications[index].clone());
+                }
+                index += 1;
+            }
+            self.pump(deadline).await?;
+        }
+    }
+
+    /// Reads one message. Responses are returned; notifications are
+    /// shape-checked and kept, and server requests are answered.
+    async fn pump(&mut self, deadline: Instant) -> Result<Option<(u64, Value)>, String> {
+        let message = match timeout_at(deadline, self.messages.recv()).await {
+            Err(_) => return Err("timed out waiting for the app-server".to_string()),
+            Ok(None) => return Err(self.exit_reason().await),
+            Ok(Some(Err(problem))) => {
+                self.problems.push(problem);
+                return Ok(None);
+            }
+            Ok(Some(Ok(message))) => message,
+        };
+        let id = message.get("id").and_then(Value::as_u64);
+        match (id, message.get("method").is_some()) {
+            (Some(_), true) => self.answer(&message).await?,
+            (Some(id), false) => return Ok(Some((id, message))),
+            (None, true) => self.record(message),
+            (None, false) => self
+                .problems
+                .push(format!("message with neither id nor method: {message}")),
+        }
+        Ok(None)
+    }
+
+    fn record(&mut self, message: Value) {
+        let method = message
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string();
+        for problem in shape_problems(&message) {
+            self.problems.push(format!("{method}: {problem}"));
+        }
+        if let MonitorEvent::Other { method, .. } = normalize(&message) {
+            self.unmapped.insert(method);
+        }
+        self.notifications.push(message);
+    }
+
+    /// Declines approvals, so tool calls never leave the read-only sandbox,
+    /// and refuses requests CodexMonitor does not know.
+    async fn answer(&mut self, message: &Value) -> Result<(), String> {
+        let Some(request) = ServerRequest::parse(message, 0) else {
+            return Ok(());
+        };
+        let reply = match request.kind {
+            ServerRequestKind::Other => {
+                self.unmapped.insert(request.method.clone());
+                json!({
+                    "id": request.id,
+                    "error": { "code": METHOD_NOT_FOUND_CODE, "message": "not supported" }
+                })
+            }
+            _ => {
+                if request.thread_id.is_none() {
+                    self.problems
+                        .push(format!("{}: missing threadId", request.method));
+                }
+                let result = request.prepare_result(json!({ "decision": "decline" }))?;
+                json!({ "id": request.id, "result": result })
+            }
+        };
+        self.write(reply).await
+    }
+
+    async fn exit_reason(&mut self) -> String {
+        match timeout(EXIT_GRACE, self.child.wait()).await {
+            Ok(Ok(status)) => format!("app-server exited ({status})"),
+            _ => "app-server closed stdout".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    passed: bool,
+    detail: Option<String>,
+}
+
+#[derive(Debug, Serialize, Default)]
+struct Report {
+    checks: Vec<Check>,
+    /// Methods with no typed mapping in `monitor_event`.
+    unmapped: Vec<String>,
+}
+
+impl Report {
+    fn check<T>(&mut self, name: &'static str, result: Result<T, String>) -> Option<T> {
+        let (value, detail) = match result {
+            Ok(value) => (Some(value), None),
+            Err(err) => (None, Some(err)),
+        };
+        self.checks.push(Check {
+            name,
+            passed: value.is_some(),
+            detail,
+        });
+        value
+    }
+
+    fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+}
+
+async fn initialize(session: &mut Session) -> Result<(), String> {
+    let client = ClientInfo::new(
+        env!("CARGO_PKG_VERSION").to_string(),
+        &AppSettings::default(),
+    );
+    session
+        .request("initialize", client.initialize_params())
+        .await?;
+    session.notify("initialized").await
+}
+
+async fn start_thread(session: &mut Session, cwd: &str) -> Result<String, String> {
+    let params = ThreadStartParams {
+        cwd: cwd.to_string(),
+        approval_policy: ApprovalPolicy::OnRequest,
+    };
+    Ok(session.call(&params).await?.thread.id)
+}
+
+async fn list_threads(session: &mut Session) -> Result<(), String> {
+    let params = ThreadListParams {
+        cursor: None,
+        limit: Some(20),
+    };
+    session.call(&params).await.map(|_| ())
+}
+
+async fn resume_thread(session: &mut Session, thread_id: &str) -> Result<(), String> {
+    let params = ThreadResumeParams {
+        thread_id: thread_id.to_string(),
+    };
+    let result = session.call(&params).await?;
+    match string_at(&result.thread, "/id") {
+        Some(id) if id == thread_id => Ok(()),
+        other => Err(format!("expected thread.id `{thread_id}`, got {other:?}")),
+    }
+}
+
+async fn start_turn(
+    session: &mut Session,
+    thread_id: &str,
+    cwd: &str,
+    prompt: &str,
+) -> Result<String, String> {
+    let params = TurnStartParams {
+        thread_id: thread_id.to_string(),
+        input: vec![json!({ "type": "text", "text": prompt })],
+        cwd: cwd.to_string(),
+        approval_policy: ApprovalPolicy::OnRequest,
+        sandbox_policy: SandboxPolicy::ReadOnly,
+        model: None,
+        effort: None,
+        collaboration_mode: None,
+    };
+    Ok(session.call(&params).await?.turn.id)
+}
+
+/// A full turn that should call at least one tool and answer with an
+/// agent message.
+async fn run_turn(session: &mut Session, thread_id: &str, config: &Config) -> Result<(), String> {
+    let since = session.notifications.len();
+    let deadline = Instant::now() + config.turn_timeout;
+    let turn_id = start_turn(session, thread_id, &config.cwd, &config.prompt).await?;
+    session
+        .wait_for(since, deadline, |message| {
+            is_turn_event(message, false, &turn_id)
+        })
+        .await?;
+    session
+        .wait_for(since, deadline, |message| {
+            is_turn_event(message, true, &turn_id)
+        })
+        .await?;
+
+    let item_types: Vec<String> = session.notifications[since..]
+        .iter()
+        .filter_map(|message| match normalize(message) {
+            MonitorEvent::ItemCompleted { item, .. } => string_at(&item, "/type"),
+            _ => None,
+        })
+        .collect();
+    if !item_types
+        .iter()
+        .any(|kind| TOOL_ITEM_TYPES.contains(&kind.as_str()))
+    {
+        return Err(format!("no tool call item completed (saw {item_types:?})"));
+    }
+    if !item_types.iter().any(|kind| kind == "agentMessage") {
+        return Err(format!(
+            "no agentMessage item completed (saw {item_types:?})"
+        ));
+    }
+    Ok(())
+}
+
+async fn interrupt_turn(
+    session: &mut Session,
+    thread_id: &str,
+    config: &Config,
+) -> Result<(), String> {
+    let since = session.notifications.len();
+    let deadline = Instant::now() + config.turn_timeout;
+    let turn_id = start_turn(session, thread_id, &config.cwd, INTERRUPT_PROMPT).await?;
+    session
+        .wait_for(since, deadline, |message| {
+            is_turn_event(message, false, &turn_id)
+        })
+        .await?;
+    let params = TurnInterruptParams {
+        thread_id: thread_id.to_string(),
+        turn_id: turn_id.clone(),
+    };
+    session.call(&params).await?;
+    let completed = session
+        .wait_for(since, deadline, |message| {
+            is_turn_event(message, true, &turn_id)
+        })
+        .await?;
+    match completed
+        .pointer("/params/turn/status")
+        .and_then(Value::as_str)
+    {
+        None | Some("interrupted") => Ok(()),
+        Some(status) => Err(format!("turn completed as `{status}`, not `interrupted`")),
+    }
+}
+
+async fn archive_thread(session: &mut Session, thread_id: &str) -> Result<(), String> {
+    let params = ThreadArchiveParams {
+        thread_id: thread_id.to_string(),
+    };
+    session.call(&params).await.map(|_| ())
+}
+
+async fn run_scenario(session: &mut Session, config: &Config, report: &mut Report) {
+    if report
+        .check("initialize", initialize(session).await)
+        .is_none()
+    {
+        return;
+    }
+    let Some(thread_id) = report.check("thread/start", start_thread(session, &config.cwd).await)
+    else {
+        return;
+    };
+    report.check("thread/list", list_threads(session).await);
+    report.check("thread/resume", resume_thread(session, &thread_id).await);
+    if !config.skip_turn {
+        report.check("turn", run_turn(session, &thread_id, config).await);
+        report.check(
+            "turn/interrupt",
+            interrupt_turn(session, &thread_id, config).await,
+        );
+    }
+    report.check("thread/archive", archive_thread(session, &thread_id).await);
+}
+
+async fn run(config: &Config) -> Report {
+    let mut report = Report::default();
+    let Some(mut session) = report.check("spawn", Session::spawn(config)) else {
+        return report;
+    };
+    run_scenario(&mut session, config, &mut report).await;
+    let shapes = if session.problems.is_empty() {
+        Ok(())
+    } else {
+        Err(session.problems.join("\n  "))
+    };
+    report.check("notification shapes", shapes);
+    report.unmapped = session.unmapped.into_iter().collect();
+    report
+}
+
+fn print_report(report: &Report) {
+    for check in &report.checks {
+        let status = if check.passed { "PASS" } else { "FAIL" };
+        match &check.detail {
+            Some(detail) => println!("{status} {}: {detail}", check.name),
+            None => println!("{status} {}", check.name),
+        }
+    }
+    if !report.unmapped.is_empty() {
+        println!("unmapped methods: {}", report.unmapped.join(", "));
+    }
+}
+
+fn main() {
+    let config = match parse_args() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}\n\n{}", usage());
+            std::process::exit(2);
+        }
+    };
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build tokio runtime");
+    let report = runtime.block_on(run(&config));
+
+    if config.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(text) => println!("{text}"),
+            Err(err) => eprintln!("{err}"),
+        }
+    } else {
+        print_report(&report);
+    }
+    if !report.passed() {
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::shape_problems;
+    use serde_json::json;
+
+    #[test]
+    fn shape_problems_flags_missing_ids() {
+        let started = json!({
+            "method": "turn/started",
+            "params": { "threadId": "t1", "turn": { "id": "turn-1" } }
+        });
+        assert!(shape_problems(&started).is_empty());
+
+        let completed = json!({ "method": "turn/completed", "params": { "threadId": "t1" } });
+        assert_eq!(shape_problems(&completed), vec!["missing turn.id"]);
+
+        let item = json!({
+            "method": "item/completed",
+            "params": { "threadId": "t1", "item": { "type": "agentMessage" } }
+        });
+        assert_eq!(shape_problems(&item), vec!["missing item.id"]);
+
+        let delta = json!({
+            "method": "item/agentMessage/delta",
+            "params": { "delta": "hi" }
+        });
+        assert_eq!(
+            shape_problems(&delta),
+            vec!["missing threadId", "missing itemId"]
+        );
+
+        let unknown = json!({ "method": "vendor/custom", "params": {} });
+        assert!(shape_problems(&unknown).is_empty());
+    }
+}
@@ -0,0 +1,322 @@
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+
+use tauri::{AppHandle, Emitter, Manager, State};
+use uuid::Uuid;
+
+use crate::backend::app_server::now_millis;
+use crate::backend::events::AppServerEvent;
+use crate::backend::protocol::{parse_result, TurnStartParams};
+use crate::codex::{start_thread_id, start_turn};
+use crate::state::AppState;
+use crate::utils::turn_outcome;
+
+/// Finished broadcasts kept around for `list_broadcasts`.
+const MAX_BROADCASTS: usize = 50;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum BroadcastStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct BroadcastTarget {
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    #[serde(rename = "threadId")]
+    pub(crate) thread_id: Option<String>,
+    pub(crate) status: BroadcastStatus,
+    pub(crate) error: Option<String>,
+}
+
+/// One prompt sent to several workspaces, tracked until every turn ends.
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct Broadcast {
+    pub(crate) id: String,
+    pub(crate) text: String,
+    pub(crate) model: Option<String>,
+    #[serde(rename = "startedAt")]
+    pub(crate) started_at: u64,
+    #[serde(rename = "completedAt")]
+    pub(crate) completed_at: Option<u64>,
+    pub(crate) targets: Vec<BroadcastTarget>,
+}
+
+#[derive(Default)]
+pub(crate) struct BroadcastRegistry {
+    broadcasts: Vec<Broadcast>,
+    /// Thread id -> broadcast id, for threads whose turn is still running.
+    threads: HashMap<String, String>,
+}
+
+impl BroadcastRegistry {
+    fn insert(&mut self, broadcast: Broadcast) {
+        self.broadcasts.push(broadcast);
+        while self.broadcasts.len() > MAX_BROADCASTS {
+            let Some(index) = self
+                .broadcasts
+                .iter()
+                .position(|broadcast| broadcast.completed_at.is_some())
+            else {
+                break;
+            };
+            self.broadcasts.remove(index);
+        }
+    }
+
+    fn update_target(
+        &mut self,
+        broadcast_id: &str,
+        workspace_id: &str,
+        update: impl FnOnce(&mut BroadcastTarget),
+    ) -> Option<Broadcast> {
+        let broadcast = self
+            .broadcasts
+            .iter_mut()
+            .find(|broadcast| broadcast.id == broadcast_id)?;
+        let target = broadcast
+            .targets
+            .iter_mut()
+            .find(|target| target.workspace_id == workspace_id)?;
+        update(target);
+        if let Some(thread_id) = &target.thread_id {
+            if target.status == BroadcastStatus::Running {
+                self.threads
+                    .insert(thread_id.clone(), broadcast_id.to_string());
+            } else {
+                self.threads.remove(thread_id);
+            }
+        }
+        if broadcast.completed_at.is_none()
+            && broadcast
+                .targets
+                .iter()
+                .all(|target| target.status != BroadcastStatus::Running)
+        {
+            broadcast.completed_at = Some(now_millis());
+        }
+        Some(broadcast.clone())
+    }
+
+    /// Marks the target owning `thread_id` as finished. Returns the updated
+    /// broadcast when the thread belonged to one.
+    fn finish_thread(&mut self, thread_id: &str, error: Option<String>) -> Option<Broadcast> {
+        let broadcast_id = self.threads.get(thread_id)?.clone();
+        let workspace_id = self
+            .broadcasts
+            .iter()
+            .find(|broadcast| broadcast.id == broadcast_id)?
+            .targets
+            .iter()
+            .find(|target| target.thread_id.as_deref() == Some(thread_id))?
+            .workspace_id
+            .clone();
+        self.update_target(&broadcast_id, &workspace_id, |target| {
+            target.status = if error.is_some() {
+                BroadcastStatus::Failed
+            } else {
+                BroadcastStatus::Completed
+            };
+            target.error = error;
+        })
+    }
+}
+
+fn emit_update(app: &AppHandle, broadcast: Option<Broadcast>) {
+    if let Some(broadcast) = broadcast {
+        let _ = app.emit("broadcast-updated", broadcast);
+    }
+}
+
+/// Correlates turn completions with running broadcasts.
+pub(crate) fn observe_event(app: &AppHandle, event: &AppServerEvent) {
+    let Some((thread_id, error)) = turn_outcome(&event.message) else {
+        return;
+    };
+    let updated = app
+        .state::<AppState>()
+        .broadcasts
+        .lock()
+        .unwrap()
+        .finish_thread(&thread_id, error);
+    emit_update(app, updated);
+}
+
+async fn start_target(
+    app: &AppHandle,
+    broadcast_id: &str,
+    workspace_id: &str,
+    text: &str,
+    model: Option<String>,
+) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let session = state
+        .sessions
+        .lock()
+        .await
+        .get(workspace_id)
+        .cloned()
+        .ok_or("workspace not connected")?;
+    let thread_id = start_thread_id(&session).await?;
+    // Register the thread before the turn starts so a fast completion is
+    // still attributed to this broadcast.
+    let updated = {
+        let mut broadcasts = state.broadcasts.lock().unwrap();
+        broadcasts.update_target(broadcast_id, workspace_id, |target| {
+            target.thread_id = Some(thread_id.clone())
+        })
+    };
+    emit_update(app, updated);
+    if let Some(model) = model.as_deref() {
+        state
+            .usage
+            .lock()
+            .await
+            .note_thread_model(&thread_id, model);
+    }
+    let response = start_turn(
+        &session,
+        thread_id,
+        vec![json!({ "type": "text", "text": text })],
+        model,
+        None,
+        None,
+        None,
+    )
+    .await?;
+    parse_result::<TurnStartParams>(&response)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn broadcast_turn(
+    workspace_ids: Vec<String>,
+    text: String,
+    model: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Broadcast, String> {
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err("empty user message".to_string());
+    }
+    let mut seen = HashSet::new();
+    let workspace_ids: Vec<String> = workspace_ids
+        .into_iter()
+        .filter(|workspace_id| seen.insert(workspace_id.clone()))
+        .collect();
+    if workspace_ids.is_empty() {
+        return Err("no workspaces selected".to_string());
+    }
+    let broadcast_id = Uuid::new_v4().to_string();
+    state.broadcasts.lock().unwrap().insert(Broadcast {
+        id: broadcast_id.clone(),
+        text: text.clone(),
+        model: model.clone(),
+        started_at: now_millis(),
+        completed_at: None,
+        targets: workspace_ids
+            .iter()
+            .map(|workspace_id| BroadcastTarget {
+                workspace_id: workspace_id.clone(),
+                thread_id: None,
+                status: BroadcastStatus::Running,
+                error: None,
+            })
+            .collect(),
+    });
+
+    let handles: Vec<_> = workspace_ids
+        .into_iter()
+        .map(|workspace_id| {
+            let app = app.clone();
+            let broadcast_id = broadcast_id.clone();
+            let text = text.clone();
+            let model = model.clone();
+            tauri::async_runtime::spawn(async move {
+                let result = start_target(&app, &broadcast_id, &workspace_id, &text, model).await;
+                (workspace_id, result)
+            })
+        })
+        .collect();
+    for handle in handles {
+        let Ok((workspace_id, Err(error))) = handle.await else {
+            continue;
+        };
+        let updated = state.broadcasts.lock().unwrap().update_target(
+            &broadcast_id,
+            &workspace_id,
+            |target| {
+                target.status = BroadcastStatus::Failed;
+                target.error = Some(error);
+            },
+        );
+        emit_update(&app, updated);
+    }
+
+    state
+        .broadcasts
+        .lock()
+        .unwrap()
+        .broadcasts
+        .iter()
+        .find(|broadcast| broadcast.id == broadcast_id)
+        .cloned()
+        .ok_or_else(|| "broadcast not found".to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn list_broadcasts(state: State<'_, AppState>) -> Result<Vec<Broadcast>, String> {
+    let mut broadcasts = state.broadcasts.lock().unwrap().broadcasts.clone();
+    broadcasts.reverse();
+    Ok(broadcasts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Broadcast, BroadcastRegistry, BroadcastStatus, BroadcastTarget};
+
+    fn target(workspace_id: &str) -> BroadcastTarget {
+        BroadcastTarget {
+            workspace_id: workspace_id.to_string(),
+            thread_id: None,
+            status: BroadcastStatus::Running,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn broadcast_completes_when_every_thread_finishes() {
+        let mut registry = BroadcastRegistry::default();
+        registry.insert(Broadcast {
+            id: "b1".to_string(),
+            text: "bump deps".to_string(),
+            model: None,
+            started_at: 0,
+            completed_at: None,
+            targets: vec![target("ws-a"), target("ws-b")],
+        });
+        for (workspace_id, thread_id) in [("ws-a", "t-a"), ("ws-b", "t-b")] {
+            registry.update_target("b1", workspace_id, |target| {
+                target.thread_id = Some(thread_id.to_string())
+            });
+        }
+
+        assert!(registry.finish_thread("t-unknown", None).is_none());
+        let partial = registry.finish_thread("t-a", None).expect("broadcast");
+        assert!(partial.completed_at.is_none());
+        assert_eq!(partial.targets[0].status, BroadcastStatus::Completed);
+
+        let done = registry
+            .finish_thread("t-b", Some("boom".to_string()))
+            .expect("broadcast");
+        assert!(done.completed_at.is_some());
+        assert_eq!(done.targets[1].status, BroadcastStatus::Failed);
+        assert_eq!(done.targets[1].error.as_deref(), Some("boom"));
+        assert!(registry.finish_thread("t-b", None).is_none());
+    }
+}
@@ -4,18 +4,33 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Manager, State};
 use tokio::process::Command;
 use tokio::time::timeout;
 
+use crate::archive::retain_thread;
+use crate::attachments::{attachment_inputs, image_input};
 pub(crate) use crate::backend::app_server::WorkspaceSession;
 use crate::backend::app_server::{
-    build_codex_command_with_bin, build_codex_path_env, check_codex_installation,
-    spawn_workspace_session as spawn_workspace_session_inner,
+    build_app_server_command, build_codex_command_with_bin, build_codex_path_env,
+    check_codex_installation, spawn_workspace_session as spawn_workspace_session_inner,
+    validate_codex_args, BackendCapabilities, BackendLogEntry, ClientInfo, SessionStats,
+    DEFAULT_BACKEND_LOG_LINES,
 };
+use crate::backend::events::AppServerEvent;
+use crate::backend::protocol::{
+    access_policies, parse_result, ApprovalPolicy, CollaborationModeListParams, ModelListParams,
+    RateLimitsReadParams, ReviewStartParams, SkillsListParams, ThreadArchiveParams,
+    ThreadListParams, ThreadResumeParams, ThreadStartParams, TurnInterruptParams, TurnStartParams,
+};
+use crate::backend::request_queue::RequestQueueMetrics;
+use crate::backend::server_requests::ServerRequest;
+use crate::doctor::{environment_checks, CheckStatus, DoctorCheck};
 use crate::event_sink::TauriEventSink;
+use crate::mcp::{mcp_resource_inputs, McpResourceRef};
 use crate::state::AppState;
-use crate::types::WorkspaceEntry;
+use crate::turn_history::fail_running_turns;
+use crate::types::{workspace_ids_in_group, WorkspaceEntry};
 
 pub(crate) async fn spawn_workspace_session(
     entry: WorkspaceEntry,
@@ -24,32 +39,76 @@ pub(crate) async fn spawn_workspace_session(
     codex_home: Option<PathBuf>,
 ) -> Result<Arc<WorkspaceSession>, String> {
     let client_version = app_handle.package_info().version.to_string();
+    let settings = {
+        let state = app_handle.state::<AppState>();
+        let settings = state.app_settings.lock().await;
+        settings.clone()
+    };
     let event_sink = TauriEventSink::new(app_handle);
-    spawn_workspace_session_inner(
+    let session = spawn_workspace_session_inner(
         entry,
         default_codex_bin,
-        client_version,
+        settings.codex_args.clone(),
+        ClientInfo::new(client_version, &settings),
         event_sink,
         codex_home,
     )
-    .await
+    .await?;
+    session.apply_settings(&settings);
+    Ok(session)
+}
+
+/// Runs `codex app-server <args> --help` so unknown or malformed flags show
+/// up in the doctor instead of as a session that never starts.
+async fn check_app_server_args(
+    codex_bin: Option<String>,
+    args: &[String],
+) -> (bool, Option<String>) {
+    let mut command = build_app_server_command(codex_bin, args);
+    command.arg("--help");
+    command.stdout(std::process::Stdio::piped());
+    command.stderr(std::process::Stdio::piped());
+    match timeout(Duration::from_secs(5), command.output()).await {
+        Ok(Ok(output)) if output.status.success() => (true, None),
+        Ok(Ok(output)) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let detail = stderr.trim();
+            (
+                false,
+                Some(if detail.is_empty() {
+                    "codex app-server rejected the extra arguments.".to_string()
+                } else {
+                    detail.to_string()
+                }),
+            )
+        }
+        Ok(Err(err)) => (false, Some(err.to_string())),
+        Err(_) => (
+            false,
+            Some("Timed out while checking app-server arguments.".to_string()),
+        ),
+    }
 }
 
 #[tauri::command]
 pub(crate) async fn codex_doctor(
     codex_bin: Option<String>,
+    codex_args: Option<Vec<String>>,
     state: State<'_, AppState>,
 ) -> Result<Value, String> {
-    let default_bin = {
+    let (default_bin, default_args) = {
         let settings = state.app_settings.lock().await;
-        settings.codex_bin.clone()
+        (settings.codex_bin.clone(), settings.codex_args.clone())
     };
     let resolved = codex_bin
         .clone()
         .filter(|value| !value.trim().is_empty())
         .or(default_bin);
     let path_env = build_codex_path_env(resolved.as_deref());
-    let version = check_codex_installation(resolved.clone()).await?;
+    let (version, codex_error) = match check_codex_installation(resolved.clone()).await {
+        Ok(version) => (version, None),
+        Err(err) => (None, Some(err)),
+    };
     let mut command = build_codex_command_with_bin(resolved.clone());
     command.arg("app-server");
     command.arg("--help");
@@ -109,13 +168,61 @@ pub(crate) async fn codex_doctor(
             Err(_) => (false, None, Some("Timed out while checking Node.".to_string())),
         }
     };
+    let requested_args = codex_args.unwrap_or(default_args);
+    let (args_ok, args_details) = match validate_codex_args(&requested_args) {
+        Err(err) => (false, Some(err)),
+        Ok(args) if args.is_empty() => (true, None),
+        Ok(args) => check_app_server_args(resolved.clone(), &args).await,
+    };
     let details = if app_server_ok {
         None
     } else {
-        Some("Failed to run `codex app-server --help`.".to_string())
+        codex_error
+            .clone()
+            .or_else(|| Some("Failed to run `codex app-server --help`.".to_string()))
     };
+    let mut checks = vec![
+        DoctorCheck::new(
+            "codex",
+            "Codex CLI",
+            if version.is_some() {
+                CheckStatus::Pass
+            } else {
+                CheckStatus::Fail
+            },
+        )
+        .detail(version.clone().or(codex_error)),
+        if app_server_ok {
+            DoctorCheck::new("app-server", "codex app-server", CheckStatus::Pass)
+        } else {
+            DoctorCheck::new("app-server", "codex app-server", CheckStatus::Fail)
+                .detail(details.clone())
+                .hint("Update Codex; CodexMonitor needs a CLI with `app-server`.")
+        },
+        if args_ok {
+            DoctorCheck::new("app-server-args", "App-server arguments", CheckStatus::Pass)
+        } else {
+            DoctorCheck::new("app-server-args", "App-server arguments", CheckStatus::Fail)
+                .detail(args_details.clone())
+                .hint("Fix or clear the extra arguments under Settings > Codex.")
+        },
+        if node_ok {
+            DoctorCheck::new("node", "Node.js", CheckStatus::Pass).detail(node_version.clone())
+        } else {
+            DoctorCheck::new("node", "Node.js", CheckStatus::Warn)
+                .detail(node_details.clone())
+                .hint("npm installs of Codex need Node on PATH.")
+        },
+    ];
+    let data_dir = state
+        .settings_path
+        .parent()
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    checks.extend(environment_checks(resolved.as_deref(), path_env.as_deref(), &data_dir).await);
+    let ok = checks.iter().all(|check| check.status != CheckStatus::Fail);
     Ok(json!({
-        "ok": version.is_some() && app_server_ok,
+        "ok": ok,
         "codexBin": resolved,
         "version": version,
         "appServerOk": app_server_ok,
@@ -124,6 +231,9 @@ pub(crate) async fn codex_doctor(
         "nodeOk": node_ok,
         "nodeVersion": node_version,
         "nodeDetails": node_details,
+        "argsOk": args_ok,
+        "argsDetails": args_details,
+        "checks": checks,
     }))
 }
 
@@ -132,15 +242,33 @@ pub(crate) async fn start_thread(
     workspace_id: String,
     state: State<'_, AppState>,
 ) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let params = json!({
-        "cwd": session.entry.path,
-        "approvalPolicy": "on-request"
-    });
-    session.send_request("thread/start", params).await
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    let params = ThreadStartParams {
+        cwd: session.entry.path.clone(),
+        approval_policy: ApprovalPolicy::OnRequest,
+    };
+    let response = session.request(&params).await?;
+    if let Ok(result) = parse_result::<ThreadStartParams>(&response) {
+        state
+            .last_threads
+            .lock()
+            .await
+            .remember(&workspace_id, &result.thread.id);
+        if let Some(model) = result.model.as_deref() {
+            state
+                .usage
+                .lock()
+                .await
+                .note_thread_model(&result.thread.id, model);
+        }
+    }
+    Ok(response)
 }
 
 #[tauri::command]
@@ -149,14 +277,34 @@ pub(crate) async fn resume_thread(
     thread_id: String,
     state: State<'_, AppState>,
 ) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let params = json!({
-        "threadId": thread_id
-    });
-    session.send_request("thread/resume", params).await
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    state
+        .last_threads
+        .lock()
+        .await
+        .remember(&workspace_id, &thread_id);
+    let response = session
+        .request(&ThreadResumeParams {
+            thread_id: thread_id.clone(),
+        })
+        .await?;
+    let model = parse_result::<ThreadResumeParams>(&response)
+        .ok()
+        .and_then(|result| result.model);
+    if let Some(model) = model {
+        state
+            .usage
+            .lock()
+            .await
+            .note_thread_model(&thread_id, &model);
+    }
+    Ok(response)
 }
 
 #[tauri::command]
@@ -166,15 +314,14 @@ pub(crate) async fn list_threads(
     limit: Option<u32>,
     state: State<'_, AppState>,
 ) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let params = json!({
-        "cursor": cursor,
-        "limit": limit,
-    });
-    session.send_request("thread/list", params).await
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    session.request(&ThreadListParams { cursor, limit }).await
 }
 
 #[tauri::command]
@@ -183,14 +330,25 @@ pub(crate) async fn archive_thread(
     thread_id: String,
     state: State<'_, AppState>,
 ) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let params = json!({
-        "threadId": thread_id
-    });
-    session.send_request("thread/archive", params).await
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    // Keep a local copy so the thread stays searchable once the backend
+    // drops it. Archiving goes ahead when there is nothing to keep, but not
+    // when the copy could not be written.
+    retain_thread(&state, &workspace_id, &thread_id)
+        .await
+        .map_err(|err| format!("Could not save a local copy of the thread: {err}"))?;
+    state
+        .last_threads
+        .lock()
+        .await
+        .forget_thread(&workspace_id, &thread_id);
+    session.request(&ThreadArchiveParams { thread_id }).await
 }
 
 #[tauri::command]
@@ -202,34 +360,35 @@ pub(crate) async fn send_user_message(
     effort: Option<String>,
     access_mode: Option<String>,
     images: Option<Vec<String>>,
+    attachments: Option<Vec<String>>,
+    mcp_resources: Option<Vec<McpResourceRef>>,
     collaboration_mode: Option<Value>,
     state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let access_mode = access_mode.unwrap_or_else(|| "current".to_string());
-    let sandbox_policy = match access_mode.as_str() {
-        "full-access" => json!({
-            "type": "dangerFullAccess"
-        }),
-        "read-only" => json!({
-            "type": "readOnly"
-        }),
-        _ => json!({
-            "type": "workspaceWrite",
-            "writableRoots": [session.entry.path],
-            "networkAccess": true
-        }),
-    };
-
-    let approval_policy = if access_mode == "full-access" {
-        "never"
-    } else {
-        "on-request"
+    // Read MCP resources before taking the sessions lock; servers can be slow.
+    let client_version = app.package_info().version.to_string();
+    let mcp_inputs = mcp_resource_inputs(
+        &state,
+        &workspace_id,
+        &mcp_resources.unwrap_or_default(),
+        &client_version,
+    )
+    .await?;
+    let steer_enabled = state.app_settings.lock().await.experimental_steer_enabled;
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
     };
-
+    // With steering on, a message sent mid-turn is meant for the running turn.
+    if !steer_enabled {
+        if let Some(response) = session.turn_in_progress(&thread_id).await {
+            return Ok(response);
+        }
+    }
     let trimmed_text = text.trim();
     let mut input: Vec<Value> = Vec::new();
     if !trimmed_text.is_empty() {
@@ -238,34 +397,76 @@ pub(crate) async fn send_user_message(
     if let Some(paths) = images {
         for path in paths {
             let trimmed = path.trim();
-            if trimmed.is_empty() {
-                continue;
-            }
-            if trimmed.starts_with("data:")
-                || trimmed.starts_with("http://")
-                || trimmed.starts_with("https://")
-            {
-                input.push(json!({ "type": "image", "url": trimmed }));
-            } else {
-                input.push(json!({ "type": "localImage", "path": trimmed }));
+            if !trimmed.is_empty() {
+                input.push(image_input(trimmed));
             }
         }
     }
+    if let Some(paths) = attachments {
+        input.extend(attachment_inputs(&paths)?);
+    }
+    input.extend(mcp_inputs);
     if input.is_empty() {
         return Err("empty user message".to_string());
     }
+    if let Some(model) = model.as_deref() {
+        state
+            .usage
+            .lock()
+            .await
+            .note_thread_model(&thread_id, model);
+    }
+    state
+        .last_threads
+        .lock()
+        .await
+        .remember(&workspace_id, &thread_id);
+    start_turn(
+        &session,
+        thread_id,
+        input,
+        model,
+        effort,
+        access_mode,
+        collaboration_mode,
+    )
+    .await
+}
 
-    let params = json!({
-        "threadId": thread_id,
-        "input": input,
-        "cwd": session.entry.path,
-        "approvalPolicy": approval_policy,
-        "sandboxPolicy": sandbox_policy,
-        "model": model,
-        "effort": effort,
-        "collaborationMode": collaboration_mode,
-    });
-    session.send_request("turn/start", params).await
+/// Starts a thread rooted at the workspace and returns its id.
+pub(crate) async fn start_thread_id(session: &WorkspaceSession) -> Result<String, String> {
+    let params = ThreadStartParams {
+        cwd: session.entry.path.clone(),
+        approval_policy: ApprovalPolicy::OnRequest,
+    };
+    let response = session.request(&params).await?;
+    Ok(parse_result::<ThreadStartParams>(&response)?.thread.id)
+}
+
+/// Sends `turn/start` with the sandbox and approval policy implied by
+/// `access_mode` (`current`, `read-only`, or `full-access`).
+pub(crate) async fn start_turn(
+    session: &WorkspaceSession,
+    thread_id: String,
+    input: Vec<Value>,
+    model: Option<String>,
+    effort: Option<String>,
+    access_mode: Option<String>,
+    collaboration_mode: Option<Value>,
+) -> Result<Value, String> {
+    let (approval_policy, sandbox_policy) =
+        access_policies(access_mode.as_deref(), &session.entry.path);
+    let params = TurnStartParams {
+        thread_id,
+        input,
+        cwd: session.entry.path.clone(),
+        approval_policy,
+        sandbox_policy,
+        model,
+        effort,
+        collaboration_mode,
+    };
+    session.request(&params).await
 }
 
 #[tauri::command]
@@ -273,12 +474,15 @@ pub(crate) async fn collaboration_mode_list(
     workspace_id: String,
     state: State<'_, AppState>,
 ) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
     session
-        .send_request("collaborationMode/list", json!({}))
+        .request(&CollaborationModeListParams::default())
         .await
 }
 
@@ -289,15 +493,73 @@ pub(crate) async fn turn_interrupt(
     turn_id: String,
     state: State<'_, AppState>,
 ) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    session
+        .request(&TurnInterruptParams { thread_id, turn_id })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn interrupt_all(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Value, String> {
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    Ok(session.interrupt_all().await)
+}
+
+/// Runs `interrupt_all` on every connected workspace in a sidebar group.
+#[tauri::command]
+pub(crate) async fn interrupt_workspace_group(
+    group_id: String,
+    state: State<'_, AppState>,
+) -> Result<Value, String> {
+    let workspace_ids = {
+        let workspaces = state.workspaces.lock().await;
+        workspace_ids_in_group(&workspaces, &group_id)
+    };
+    let mut results = Map::new();
+    let mut not_connected = Vec::new();
+    for workspace_id in workspace_ids {
+        let session = state.sessions.lock().await.get(&workspace_id).cloned();
+        match session {
+            Some(session) => {
+                results.insert(workspace_id, session.interrupt_all().await);
+            }
+            None => not_connected.push(workspace_id),
+        }
+    }
+    Ok(json!({ "results": results, "notConnected": not_connected }))
+}
+
+/// Kills the workspace's app-server process group outright, without a
+/// grace period. The workspace has to be reconnected afterwards.
+#[tauri::command]
+pub(crate) async fn kill_backend(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let session = state
+        .sessions
+        .lock()
+        .await
+        .remove(&workspace_id)
         .ok_or("workspace not connected")?;
-    let params = json!({
-        "threadId": thread_id,
-        "turnId": turn_id,
-    });
-    session.send_request("turn/interrupt", params).await
+    session.shutdown(Duration::ZERO).await;
+    fail_running_turns(&state, Some(&workspace_id)).await;
+    Ok(())
 }
 
 #[tauri::command]
@@ -308,19 +570,19 @@ pub(crate) async fn start_review(
     delivery: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let mut params = Map::new();
-    params.insert("threadId".to_string(), json!(thread_id));
-    params.insert("target".to_string(), target);
-    if let Some(delivery) = delivery {
-        params.insert("delivery".to_string(), json!(delivery));
-    }
-    session
-        .send_request("review/start", Value::Object(params))
-        .await
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    let params = ReviewStartParams {
+        thread_id,
+        target,
+        delivery,
+    };
+    session.request(&params).await
 }
 
 #[tauri::command]
@@ -328,12 +590,14 @@ pub(crate) async fn model_list(
     workspace_id: String,
     state: State<'_, AppState>,
 ) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let params = json!({});
-    session.send_request("model/list", params).await
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    session.request(&ModelListParams::default()).await
 }
 
 #[tauri::command]
@@ -341,13 +605,14 @@ pub(crate) async fn account_rate_limits(
     workspace_id: String,
     state: State<'_, AppState>,
 ) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    session
-        .send_request("account/rateLimits/read", Value::Null)
-        .await
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    session.request(&RateLimitsReadParams).await
 }
 
 #[tauri::command]
@@ -355,14 +620,17 @@ pub(crate) async fn skills_list(
     workspace_id: String,
     state: State<'_, AppState>,
 ) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let params = json!({
-        "cwd": session.entry.path
-    });
-    session.send_request("skills/list", params).await
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    let params = SkillsListParams {
+        cwd: session.entry.path.clone(),
+    };
+    session.request(&params).await
 }
 
 #[tauri::command]
@@ -372,9 +640,129 @@ pub(crate) async fn respond_to_server_request(
     result: Value,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    session.send_response(request_id, result).await
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    session.respond_to_server_request(request_id, result).await
+}
+
+#[tauri::command]
+pub(crate) async fn list_server_requests(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ServerRequest>, String> {
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    Ok(session.pending_server_requests().await)
+}
+
+#[tauri::command]
+pub(crate) async fn get_backend_logs(
+    workspace_id: String,
+    lines: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<BackendLogEntry>, String> {
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    Ok(session
+        .recent_logs(lines.unwrap_or(DEFAULT_BACKEND_LOG_LINES))
+        .await)
+}
+
+#[tauri::command]
+pub(crate) async fn get_request_queue_metrics(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<RequestQueueMetrics, String> {
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    Ok(session.request_queue_metrics())
+}
+
+#[tauri::command]
+pub(crate) async fn get_session_stats(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<SessionStats, String> {
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    Ok(session.stats().await)
+}
+
+#[tauri::command]
+pub(crate) async fn get_backend_capabilities(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<BackendCapabilities, String> {
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    let capabilities = session.capabilities.lock().await.clone();
+    Ok(capabilities)
+}
+
+#[tauri::command]
+pub(crate) async fn replay_events(
+    workspace_id: String,
+    since_seq: u64,
+    state: State<'_, AppState>,
+) -> Result<Vec<AppServerEvent>, String> {
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    Ok(session.events_since(since_seq).await)
+}
+
+#[tauri::command]
+pub(crate) async fn export_backend_logs(
+    workspace_id: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    let contents: String = session
+        .recent_logs(usize::MAX)
+        .await
+        .iter()
+        .map(|entry| format!("{} [{}] {}\n", entry.timestamp, entry.stream, entry.line))
+        .collect();
+    std::fs::write(&path, contents).map_err(|e| e.to_string())
 }
@@ -53,7 +53,7 @@ fn config_toml_path() -> Option<PathBuf> {
     resolve_codex_home().map(|home| home.join("config.toml"))
 }
 
-fn resolve_codex_home() -> Option<PathBuf> {
+pub(crate) fn resolve_codex_home() -> Option<PathBuf> {
     if let Ok(value) = env::var("CODEX_HOME") {
         if !value.trim().is_empty() {
             return Some(PathBuf::from(value.trim()));
@@ -0,0 +1,290 @@
+use serde::Serialize;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::backend::app_server::new_codex_command;
+use crate::codex_config::resolve_codex_home;
+
+const LOW_DISK_WARN_BYTES: u64 = 1024 * 1024 * 1024;
+const LOW_DISK_FAIL_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Backends CodexMonitor can drive besides `codex`. Missing ones are skipped.
+const OPTIONAL_BACKENDS: [(&str, &str, Option<&str>); 2] = [
+    (
+        "claude-app-server",
+        "Claude app-server",
+        Some("ANTHROPIC_API_KEY"),
+    ),
+    ("pi-adapter", "Pi adapter", None),
+];
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+/// One line of the doctor report. `hint` says how to fix a warn or fail.
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct DoctorCheck {
+    pub(crate) id: String,
+    pub(crate) label: String,
+    pub(crate) status: CheckStatus,
+    pub(crate) detail: Option<String>,
+    pub(crate) hint: Option<String>,
+}
+
+impl DoctorCheck {
+    pub(crate) fn new(id: &str, label: &str, status: CheckStatus) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            status,
+            detail: None,
+            hint: None,
+        }
+    }
+
+    pub(crate) fn detail(mut self, detail: Option<String>) -> Self {
+        self.detail = detail;
+        self
+    }
+
+    pub(crate) fn hint(mut self, hint: &str) -> Self {
+        self.hint = Some(hint.to_string());
+        self
+    }
+}
+
+/// Where `program` resolves on `path_env`, the way a spawned child would
+/// find it. Paths with a directory component are checked as-is.
+pub(crate) fn find_on_path(program: &str, path_env: Option<&str>) -> Option<PathBuf> {
+    let candidate = |base: &Path| -> Option<PathBuf> {
+        if base.is_file() {
+            return Some(base.to_path_buf());
+        }
+        if cfg!(windows) {
+            return ["exe", "cmd", "bat"]
+                .iter()
+                .map(|ext| base.with_extension(ext))
+                .find(|path| path.is_file());
+        }
+        None
+    };
+    let program_path = Path::new(program);
+    if program_path.is_absolute() || program_path.components().count() > 1 {
+        return candidate(program_path);
+    }
+    let search = path_env
+        .map(std::ffi::OsString::from)
+        .or_else(|| env::var_os("PATH"))?;
+    env::split_paths(&search).find_map(|dir| candidate(&dir.join(program)))
+}
+
+async fn probe_version(program: &Path, path_env: Option<&str>) -> Result<String, String> {
+    let mut command = new_codex_command(&program.to_string_lossy(), path_env);
+    if let Some(path_env) = path_env {
+        command.env("PATH", path_env);
+    }
+    command.arg("--version");
+    command.stdout(std::process::Stdio::piped());
+    command.stderr(std::process::Stdio::piped());
+    let output = timeout(Duration::from_secs(5), command.output())
+        .await
+        .map_err(|_| "Timed out while running --version.".to_string())?
+        .map_err(|err| err.to_string())?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() {
+        return Ok(stdout);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Err(if stderr.is_empty() { stdout } else { stderr })
+}
+
+fn env_present(name: &str) -> bool {
+    env::var_os(name).is_some_and(|value| !value.is_empty())
+}
+
+/// Free bytes from `df -Pk` output: the "Available" column of the first
+/// filesystem row, in 1024-byte blocks.
+fn parse_df_available(output: &str) -> Option<u64> {
+    let row = output.lines().nth(1)?;
+    let available: u64 = row.split_whitespace().nth(3)?.parse().ok()?;
+    Some(available * 1024)
+}
+
+async fn free_space(path: &Path) -> Option<u64> {
+    if cfg!(windows) {
+        return None;
+    }
+    let mut command = Command::new("df");
+    command.arg("-Pk").arg(path);
+    command.stdout(std::process::Stdio::piped());
+    command.stderr(std::process::Stdio::null());
+    let output = timeout(Duration::from_secs(5), command.output())
+        .await
+        .ok()?
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_df_available(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The nearest existing ancestor, so `df` works before a directory is created.
+fn existing_ancestor(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|ancestor| ancestor.exists())
+}
+
+async fn disk_check(id: &str, label: &str, path: &Path) -> DoctorCheck {
+    let Some(free) = existing_ancestor(path).map(free_space) else {
+        return DoctorCheck::new(id, label, CheckStatus::Skip)
+            .detail(Some(format!("{} does not exist.", path.display())));
+    };
+    let Some(free) = free.await else {
+        return DoctorCheck::new(id, label, CheckStatus::Skip).detail(Some(
+            "Free space is not available on this platform.".to_string(),
+        ));
+    };
+    let detail = Some(format!(
+        "{} MiB free at {}",
+        free / (1024 * 1024),
+        path.display()
+    ));
+    let status = if free < LOW_DISK_FAIL_BYTES {
+        CheckStatus::Fail
+    } else if free < LOW_DISK_WARN_BYTES {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    };
+    let check = DoctorCheck::new(id, label, status).detail(detail);
+    if status == CheckStatus::Pass {
+        check
+    } else {
+        check.hint("Free up disk space; threads and logs stop saving when the disk is full.")
+    }
+}
+
+/// Checks that do not depend on the Codex binary itself: PATH resolution,
+/// git, credentials (presence only, never values), disk space and the
+/// optional non-Codex backends.
+pub(crate) async fn environment_checks(
+    codex_bin: Option<&str>,
+    path_env: Option<&str>,
+    data_dir: &Path,
+) -> Vec<DoctorCheck> {
+    let mut checks = Vec::new();
+
+    let codex_program = codex_bin.unwrap_or("codex");
+    checks.push(match find_on_path(codex_program, path_env) {
+        Some(path) => DoctorCheck::new("codex-path", "Codex on PATH", CheckStatus::Pass)
+            .detail(Some(path.display().to_string())),
+        None => DoctorCheck::new("codex-path", "Codex on PATH", CheckStatus::Fail)
+            .detail(Some(format!("`{codex_program}` was not found.")))
+            .hint("Install Codex or set its full path under Settings > Codex."),
+    });
+
+    checks.push(match find_on_path("git", path_env) {
+        None => DoctorCheck::new("git", "Git", CheckStatus::Fail)
+            .detail(Some("`git` was not found on PATH.".to_string()))
+            .hint("Install Git (on macOS: `xcode-select --install`)."),
+        Some(path) => match probe_version(&path, path_env).await {
+            Ok(version) => DoctorCheck::new("git", "Git", CheckStatus::Pass).detail(Some(version)),
+            Err(err) => DoctorCheck::new("git", "Git", CheckStatus::Fail)
+                .detail(Some(err))
+                .hint("Make sure `git --version` runs in Terminal."),
+        },
+    });
+
+    let codex_auth = resolve_codex_home().map(|home| home.join("auth.json"));
+    let has_login = codex_auth.as_deref().is_some_and(Path::is_file);
+    checks.push(if env_present("OPENAI_API_KEY") {
+        DoctorCheck::new("codex-auth", "Codex credentials", CheckStatus::Pass)
+            .detail(Some("OPENAI_API_KEY is set.".to_string()))
+    } else if has_login {
+        DoctorCheck::new("codex-auth", "Codex credentials", CheckStatus::Pass)
+            .detail(Some("Signed in with `codex login`.".to_string()))
+    } else {
+        DoctorCheck::new("codex-auth", "Codex credentials", CheckStatus::Warn)
+            .detail(Some("No login or OPENAI_API_KEY found.".to_string()))
+            .hint("Run `codex login` in Terminal, or set OPENAI_API_KEY.")
+    });
+
+    for (program, label, key) in OPTIONAL_BACKENDS {
+        let Some(path) = find_on_path(program, path_env) else {
+            checks.push(
+                DoctorCheck::new(program, label, CheckStatus::Skip)
+                    .detail(Some("Not installed (optional).".to_string())),
+            );
+            continue;
+        };
+        checks.push(match probe_version(&path, path_env).await {
+            Ok(version) => DoctorCheck::new(program, label, CheckStatus::Pass)
+                .detail(Some(format!("{version} at {}", path.display()))),
+            Err(err) => DoctorCheck::new(program, label, CheckStatus::Warn)
+                .detail(Some(err))
+                .hint("Reinstall the backend or remove it from PATH."),
+        });
+        if let Some(key) = key {
+            let id = format!("{program}-auth");
+            checks.push(if env_present(key) {
+                DoctorCheck::new(&id, &format!("{label} credentials"), CheckStatus::Pass)
+                    .detail(Some(format!("{key} is set.")))
+            } else {
+                DoctorCheck::new(&id, &format!("{label} credentials"), CheckStatus::Warn)
+                    .detail(Some(format!("{key} is not set.")))
+                    .hint("Export the key in your shell profile and restart CodexMonitor.")
+            });
+        }
+    }
+
+    checks.push(disk_check("disk-data", "Disk space (app data)", data_dir).await);
+    if let Some(codex_home) = resolve_codex_home() {
+        checks.push(disk_check("disk-codex-home", "Disk space (CODEX_HOME)", &codex_home).await);
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_on_path, parse_df_available};
+    use uuid::Uuid;
+
+    #[test]
+    fn parse_df_available_reads_posix_output() {
+        let output = "Filesystem 1024-blocks Used Available Capacity Mounted on\n\
+                      /dev/disk3s1 971350180 612309016 359041164 64% /\n";
+        assert_eq!(parse_df_available(output), Some(359_041_164 * 1024));
+        assert_eq!(parse_df_available("Filesystem\n"), None);
+        assert_eq!(parse_df_available(""), None);
+    }
+
+    #[test]
+    fn find_on_path_searches_given_path_only() {
+        let dir = std::env::temp_dir().join(format!("codex-monitor-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let tool = dir.join("fake-tool");
+        std::fs::write(&tool, "").expect("write tool");
+        let path_env = dir.to_string_lossy().to_string();
+
+        assert_eq!(
+            find_on_path("fake-tool", Some(&path_env)),
+            Some(tool.clone())
+        );
+        assert_eq!(
+            find_on_path(&tool.to_string_lossy(), Some("")),
+            Some(tool.clone())
+        );
+        assert_eq!(find_on_path("missing-tool", Some(&path_env)), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,6 +1,8 @@
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 
 use crate::backend::events::{AppServerEvent, EventSink, TerminalOutput};
+use crate::file_index::invalidates_index;
+use crate::state::AppState;
 
 #[derive(Clone)]
 pub(crate) struct TauriEventSink {
@@ -15,6 +17,26 @@ impl TauriEventSink {
 
 impl EventSink for TauriEventSink {
     fn emit_app_server_event(&self, event: AppServerEvent) {
+        if crate::approval_rules::intercept(&self.app, &event) {
+            return;
+        }
+        if !crate::scheduler::observe_event(&self.app, &event) {
+            crate::notifications::notify_for_event(&self.app, &event);
+        }
+        crate::broadcast::observe_event(&self.app, &event);
+        crate::archive::observe_event(&self.app, &event);
+        crate::usage::record_event(&self.app, &event);
+        crate::turn_history::record_event(&self.app, &event);
+        if invalidates_index(&event.message) {
+            let app = self.app.clone();
+            let workspace_id = event.workspace_id.clone();
+            tauri::async_runtime::spawn(async move {
+                app.state::<AppState>()
+                    .file_index
+                    .invalidate(&workspace_id)
+                    .await;
+            });
+        }
         let _ = self.app.emit("app-server-event", event);
     }
 
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tokio::sync::Mutex;
+use tokio::task;
+
+const INDEX_TTL: Duration = Duration::from_secs(30);
+
+struct IndexEntry {
+    files: Arc<Vec<String>>,
+    built_at: Instant,
+}
+
+/// Per-workspace cache of the file list used for @-mention completion, so
+/// each keystroke filters in memory instead of walking the tree again.
+#[derive(Default)]
+pub(crate) struct FileIndexCache {
+    entries: Mutex<HashMap<String, IndexEntry>>,
+}
+
+impl FileIndexCache {
+    pub(crate) async fn get_or_build<F>(
+        &self,
+        workspace_id: &str,
+        build: F,
+    ) -> Result<Arc<Vec<String>>, String>
+    where
+        F: FnOnce() -> Vec<String> + Send + 'static,
+    {
+        let mut entries = self.entries.lock().await;
+        if let Some(entry) = entries.get(workspace_id) {
+            if entry.built_at.elapsed() < INDEX_TTL {
+                return Ok(entry.files.clone());
+            }
+        }
+        let files = Arc::new(
+            task::spawn_blocking(build)
+                .await
+                .map_err(|_| "file index build failed".to_string())?,
+        );
+        entries.insert(
+            workspace_id.to_string(),
+            IndexEntry {
+                files: files.clone(),
+                built_at: Instant::now(),
+            },
+        );
+        Ok(files)
+    }
+
+    pub(crate) async fn invalidate(&self, workspace_id: &str) {
+        self.entries.lock().await.remove(workspace_id);
+    }
+}
+
+/// Whether an app-server message means files on disk likely changed.
+pub(crate) fn invalidates_index(message: &Value) -> bool {
+    match message.get("method").and_then(Value::as_str) {
+        Some("turn/completed") => true,
+        Some("item/completed") => {
+            let item_type = message
+                .get("params")
+                .and_then(|params| params.get("item"))
+                .and_then(|item| item.get("type"))
+                .and_then(Value::as_str);
+            matches!(item_type, Some("fileChange") | Some("commandExecution"))
+        }
+        _ => false,
+    }
+}
+
+fn is_boundary(prev: Option<char>) -> bool {
+    matches!(prev, None | Some('/' | '_' | '-' | '.' | ' '))
+}
+
+/// Scores `candidate` as a subsequence match of `query` (both lowercase).
+/// Consecutive runs and matches at word boundaries score higher.
+fn subsequence_score(candidate: &str, query: &str) -> Option<i64> {
+    let mut score = 0i64;
+    let mut query_chars = query.chars().peekable();
+    let mut prev: Option<char> = None;
+    let mut last_match: Option<usize> = None;
+    for (index, ch) in candidate.chars().enumerate() {
+        let Some(&wanted) = query_chars.peek() else {
+            break;
+        };
+        if ch == wanted {
+            score += 1;
+            if last_match.is_some_and(|last| last + 1 == index) {
+                score += 5;
+            }
+            if is_boundary(prev) {
+                score += 8;
+            }
+            last_match = Some(index);
+            query_chars.next();
+        }
+        prev = Some(ch);
+    }
+    if query_chars.peek().is_some() {
+        None
+    } else {
+        Some(score)
+    }
+}
+
+/// Fuzzy-matches a relative path, preferring hits inside the file name and
+/// shorter paths.
+pub(crate) fn fuzzy_score(path: &str, query: &str) -> Option<i64> {
+    let lower = path.to_lowercase();
+    let file_name = lower.rsplit('/').next().unwrap_or(&lower);
+    let path_score = subsequence_score(&lower, query)?;
+    let name_score = subsequence_score(file_name, query)
+        .map(|score| score * 2 + 20)
+        .unwrap_or(0);
+    Some(path_score.max(name_score) - lower.len() as i64 / 8)
+}
+
+pub(crate) fn search_files(files: &[String], query: &str, limit: usize) -> Vec<String> {
+    let query: String = query
+        .trim()
+        .trim_start_matches('@')
+        .to_lowercase()
+        .split_whitespace()
+        .collect();
+    if query.is_empty() {
+        return files.iter().take(limit).cloned().collect();
+    }
+    let mut scored: Vec<(i64, &String)> = files
+        .iter()
+        .filter_map(|path| fuzzy_score(path, &query).map(|score| (score, path)))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, path)| path.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fuzzy_score, search_files, FileIndexCache};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn search_files_ranks_file_name_matches_first() {
+        let files = vec![
+            "src/features/app/hooks/useAppServerEvents.ts".to_string(),
+            "src/services/tauri.ts".to_string(),
+            "src-tauri/src/types.rs".to_string(),
+        ];
+        let results = search_files(&files, "@tauri", 10);
+        assert_eq!(
+            results,
+            vec![
+                "src/services/tauri.ts".to_string(),
+                "src-tauri/src/types.rs".to_string()
+            ]
+        );
+        assert!(!results.contains(&"src/features/app/hooks/useAppServerEvents.ts".to_string()));
+
+        assert_eq!(search_files(&files, "uase", 10), vec![files[0].clone()]);
+        assert_eq!(search_files(&files, "", 2).len(), 2);
+        assert!(fuzzy_score("src/lib.rs", "xyz").is_none());
+    }
+
+    #[test]
+    fn file_index_cache_reuses_until_invalidated() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("runtime");
+        runtime.block_on(async {
+            let cache = FileIndexCache::default();
+            let builds = Arc::new(AtomicUsize::new(0));
+            for _ in 0..2 {
+                let builds = builds.clone();
+                let files = cache
+                    .get_or_build("ws", move || {
+                        builds.fetch_add(1, Ordering::SeqCst);
+                        vec!["a.rs".to_string()]
+                    })
+                    .await
+                    .expect("files");
+                assert_eq!(files.as_slice(), ["a.rs".to_string()]);
+            }
+            assert_eq!(builds.load(Ordering::SeqCst), 1);
+
+            cache.invalidate("ws").await;
+            let counter = builds.clone();
+            cache
+                .get_or_build("ws", move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Vec::new()
+                })
+                .await
+                .expect("files");
+            assert_eq!(builds.load(Ordering::SeqCst), 2);
+        });
+    }
+}
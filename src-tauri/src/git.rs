@@ -11,8 +11,9 @@ use crate::git_utils::{
 };
 use crate::state::AppState;
 use crate::types::{
-    BranchInfo, GitFileDiff, GitFileStatus, GitHubIssue, GitHubIssuesResponse, GitHubPullRequest,
-    GitHubPullRequestDiff, GitHubPullRequestsResponse, GitLogResponse,
+    BranchInfo, GitChangedFile, GitDiffHunk, GitDiffLine, GitFileDiff, GitFileHunks, GitFileStatus,
+    GitHubIssue, GitHubIssuesResponse, GitHubPullRequest, GitHubPullRequestDiff,
+    GitHubPullRequestsResponse, GitLogResponse, WorkspaceGitStatus,
 };
 use crate::utils::normalize_git_path;
 
@@ -130,26 +131,126 @@ fn parse_pr_diff(diff: &str) -> Vec<GitHubPullRequestDiff> {
 
     entries
 }
-#[tauri::command]
-pub(crate) async fn get_git_status(
-    workspace_id: String,
-    state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
 
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+fn status_code(status: Status) -> &'static str {
+    if status.contains(Status::WT_NEW) || status.contains(Status::INDEX_NEW) {
+        "A"
+    } else if status.contains(Status::WT_MODIFIED) || status.contains(Status::INDEX_MODIFIED) {
+        "M"
+    } else if status.contains(Status::WT_DELETED) || status.contains(Status::INDEX_DELETED) {
+        "D"
+    } else if status.contains(Status::WT_RENAMED) || status.contains(Status::INDEX_RENAMED) {
+        "R"
+    } else if status.contains(Status::WT_TYPECHANGE) || status.contains(Status::INDEX_TYPECHANGE) {
+        "T"
+    } else {
+        "--"
+    }
+}
 
-    let branch_name = repo
-        .head()
+fn has_index_changes(status: Status) -> bool {
+    status.intersects(
+        Status::INDEX_NEW
+            | Status::INDEX_MODIFIED
+            | Status::INDEX_DELETED
+            | Status::INDEX_RENAMED
+            | Status::INDEX_TYPECHANGE,
+    )
+}
+
+fn has_workdir_changes(status: Status) -> bool {
+    status.intersects(
+        Status::WT_NEW
+            | Status::WT_MODIFIED
+            | Status::WT_DELETED
+            | Status::WT_RENAMED
+            | Status::WT_TYPECHANGE,
+    )
+}
+
+fn delta_status_code(status: git2::Delta) -> &'static str {
+    match status {
+        git2::Delta::Added | git2::Delta::Untracked => "A",
+        git2::Delta::Deleted => "D",
+        git2::Delta::Renamed => "R",
+        git2::Delta::Copied => "C",
+        git2::Delta::Typechange => "T",
+        _ => "M",
+    }
+}
+
+/// Splits a libgit2 diff into per-file hunks with old/new line numbers.
+fn diff_to_hunks(diff: &git2::Diff) -> Result<Vec<GitFileHunks>, String> {
+    let mut files = Vec::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
+            continue;
+        };
+        let path = normalize_git_path(path.to_string_lossy().as_ref());
+        let old_path = delta
+            .old_file()
+            .path()
+            .map(|old| normalize_git_path(old.to_string_lossy().as_ref()))
+            .filter(|old| *old != path);
+        let Some(patch) = git2::Patch::from_diff(diff, index).map_err(|e| e.to_string())? else {
+            continue;
+        };
+        let binary = delta.flags().is_binary();
+        let mut hunks = Vec::new();
+        for hunk_index in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(hunk_index).map_err(|e| e.to_string())?;
+            let mut lines = Vec::with_capacity(line_count);
+            for line_index in 0..line_count {
+                let line = patch
+                    .line_in_hunk(hunk_index, line_index)
+                    .map_err(|e| e.to_string())?;
+                let kind = match line.origin() {
+                    '+' => "add",
+                    '-' => "delete",
+                    ' ' => "context",
+                    // End-of-file newline markers carry no content of their own.
+                    _ => continue,
+                };
+                let content = String::from_utf8_lossy(line.content());
+                lines.push(GitDiffLine {
+                    kind: kind.to_string(),
+                    content: content.trim_end_matches(['\n', '\r']).to_string(),
+                    old_line: line.old_lineno(),
+                    new_line: line.new_lineno(),
+                });
+            }
+            hunks.push(GitDiffHunk {
+                header: String::from_utf8_lossy(hunk.header())
+                    .trim_end()
+                    .to_string(),
+                old_start: hunk.old_start(),
+                old_lines: hunk.old_lines(),
+                new_start: hunk.new_start(),
+                new_lines: hunk.new_lines(),
+                lines,
+            });
+        }
+        files.push(GitFileHunks {
+            path,
+            old_path,
+            status: delta_status_code(delta.status()).to_string(),
+            binary,
+            hunks,
+        });
+    }
+    Ok(files)
+}
+
+fn head_branch_name(repo: &Repository) -> String {
+    repo.head()
         .ok()
         .and_then(|head| head.shorthand().map(|s| s.to_string()))
-        .unwrap_or_else(|| "unknown".to_string());
+        .unwrap_or_else(|| "unknown".to_string())
+}
 
+/// Walks the working tree status once and reports every changed file with
+/// its staged/unstaged flags, rename source, and line stats.
+fn changed_files(repo: &Repository) -> Result<Vec<GitChangedFile>, String> {
     let mut status_options = StatusOptions::new();
     status_options
         .include_untracked(true)
@@ -157,69 +258,70 @@ pub(crate) async fn get_git_status(
         .renames_head_to_index(true)
         .renames_index_to_workdir(true)
         .include_ignored(false);
-
     let statuses = repo
         .statuses(Some(&mut status_options))
         .map_err(|e| e.to_string())?;
-
     let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
 
     let mut files = Vec::new();
-    let mut total_additions = 0i64;
-    let mut total_deletions = 0i64;
     for entry in statuses.iter() {
         let path = entry.path().unwrap_or("");
         if path.is_empty() {
             continue;
         }
         let status = entry.status();
-        let status_str = if status.contains(Status::WT_NEW) || status.contains(Status::INDEX_NEW) {
-            "A"
-        } else if status.contains(Status::WT_MODIFIED) || status.contains(Status::INDEX_MODIFIED) {
-            "M"
-        } else if status.contains(Status::WT_DELETED) || status.contains(Status::INDEX_DELETED) {
-            "D"
-        } else if status.contains(Status::WT_RENAMED) || status.contains(Status::INDEX_RENAMED) {
-            "R"
-        } else if status.contains(Status::WT_TYPECHANGE)
-            || status.contains(Status::INDEX_TYPECHANGE)
-        {
-            "T"
-        } else {
-            "--"
-        };
-        let normalized_path = normalize_git_path(path);
-        let include_index = status.intersects(
-            Status::INDEX_NEW
-                | Status::INDEX_MODIFIED
-                | Status::INDEX_DELETED
-                | Status::INDEX_RENAMED
-                | Status::INDEX_TYPECHANGE,
-        );
-        let include_workdir = status.intersects(
-            Status::WT_NEW
-                | Status::WT_MODIFIED
-                | Status::WT_DELETED
-                | Status::WT_RENAMED
-                | Status::WT_TYPECHANGE,
-        );
-        let (additions, deletions) = diff_stats_for_path(
-            &repo,
-            head_tree.as_ref(),
-            path,
-            include_index,
-            include_workdir,
-        )
-        .map_err(|e| e.to_string())?;
-        total_additions += additions;
-        total_deletions += deletions;
-        files.push(GitFileStatus {
-            path: normalized_path,
-            status: status_str.to_string(),
+        let staged = has_index_changes(status);
+        let unstaged = has_workdir_changes(status);
+        let old_path = entry
+            .head_to_index()
+            .or_else(|| entry.index_to_workdir())
+            .and_then(|delta| delta.old_file().path())
+            .map(|old| normalize_git_path(old.to_string_lossy().as_ref()))
+            .filter(|old| old != &normalize_git_path(path));
+        let (additions, deletions) =
+            diff_stats_for_path(repo, head_tree.as_ref(), path, staged, unstaged)
+                .map_err(|e| e.to_string())?;
+        files.push(GitChangedFile {
+            path: normalize_git_path(path),
+            old_path,
+            status: status_code(status).to_string(),
+            staged,
+            unstaged,
             additions,
             deletions,
         });
     }
+    Ok(files)
+}
+
+#[tauri::command]
+pub(crate) async fn get_git_status(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+
+    let repo_root = resolve_git_root(&entry)?;
+    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+    let branch_name = head_branch_name(&repo);
+
+    let mut files = Vec::new();
+    let mut total_additions = 0i64;
+    let mut total_deletions = 0i64;
+    for file in changed_files(&repo)? {
+        total_additions += file.additions;
+        total_deletions += file.deletions;
+        files.push(GitFileStatus {
+            path: file.path,
+            status: file.status,
+            additions: file.additions,
+            deletions: file.deletions,
+        });
+    }
 
     Ok(json!({
         "branchName": branch_name,
@@ -311,6 +413,60 @@ pub(crate) async fn get_git_diffs(
     Ok(results)
 }
 
+#[tauri::command]
+pub(crate) async fn workspace_git_status(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceGitStatus, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .ok_or("workspace not found")?
+            .clone()
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+    let branch_name = head_branch_name(&repo);
+    let files = changed_files(&repo)?;
+
+    Ok(WorkspaceGitStatus { branch_name, files })
+}
+
+#[tauri::command]
+pub(crate) async fn workspace_git_diff(
+    workspace_id: String,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<GitFileHunks>, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .ok_or("workspace not found")?
+            .clone()
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+
+    let mut options = DiffOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    if let Some(path) = path.as_deref().filter(|path| !path.trim().is_empty()) {
+        options.pathspec(path).disable_pathspec_match(true);
+    }
+    let mut diff = repo
+        .diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut options))
+        .map_err(|e| e.to_string())?;
+    diff.find_similar(None).map_err(|e| e.to_string())?;
+    diff_to_hunks(&diff)
+}
+
 #[tauri::command]
 pub(crate) async fn get_git_log(
     workspace_id: String,
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use tauri::State;
+
+use crate::backend::app_server::now_millis;
+use crate::backend::protocol::ThreadResumeParams;
+use crate::state::AppState;
+use crate::storage::write_json_file;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct LastThread {
+    #[serde(rename = "threadId")]
+    pub(crate) thread_id: String,
+    #[serde(rename = "updatedAt")]
+    pub(crate) updated_at: u64,
+}
+
+/// Most recently active thread per workspace, persisted so the app can
+/// reopen straight into it.
+#[derive(Default)]
+pub(crate) struct LastThreadStore {
+    path: PathBuf,
+    threads: HashMap<String, LastThread>,
+}
+
+impl LastThreadStore {
+    pub(crate) fn load(path: PathBuf) -> Self {
+        let threads = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str::<Value>(&data).ok())
+            .and_then(|value| value.get("threads").cloned())
+            .and_then(|threads| serde_json::from_value(threads).ok())
+            .unwrap_or_default();
+        Self { path, threads }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        write_json_file(
+            &self.path,
+            &json!({ "version": 1, "threads": self.threads }),
+        )
+    }
+
+    /// Records activity on a thread. Only a change of thread is written to
+    /// disk; repeat activity just bumps the timestamp in memory.
+    fn touch(&mut self, workspace_id: &str, thread_id: &str, now: u64) -> bool {
+        let changed = self
+            .threads
+            .get(workspace_id)
+            .is_none_or(|last| last.thread_id != thread_id);
+        self.threads.insert(
+            workspace_id.to_string(),
+            LastThread {
+                thread_id: thread_id.to_string(),
+                updated_at: now,
+            },
+        );
+        changed
+    }
+
+    pub(crate) fn remember(&mut self, workspace_id: &str, thread_id: &str) {
+        if self.touch(workspace_id, thread_id, now_millis()) {
+            let _ = self.save();
+        }
+    }
+
+    /// Drops the entry if it still points at `thread_id`.
+    pub(crate) fn forget_thread(&mut self, workspace_id: &str, thread_id: &str) {
+        if self
+            .threads
+            .get(workspace_id)
+            .is_some_and(|last| last.thread_id == thread_id)
+        {
+            self.threads.remove(workspace_id);
+            let _ = self.save();
+        }
+    }
+
+    pub(crate) fn get(&self, workspace_id: &str) -> Option<LastThread> {
+        self.threads.get(workspace_id).cloned()
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn get_last_thread(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<LastThread>, String> {
+    Ok(state.last_threads.lock().await.get(&workspace_id))
+}
+
+#[tauri::command]
+pub(crate) async fn resume_last_thread(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Value, String> {
+    let last = state
+        .last_threads
+        .lock()
+        .await
+        .get(&workspace_id)
+        .ok_or("no recent thread for workspace")?;
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    let params = ThreadResumeParams {
+        thread_id: last.thread_id.clone(),
+    };
+    let response = session.request(&params).await?;
+    if let Some(error) = response.get("error") {
+        // The thread was archived or deleted outside the app.
+        state
+            .last_threads
+            .lock()
+            .await
+            .forget_thread(&workspace_id, &last.thread_id);
+        return Err(error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("thread/resume failed")
+            .to_string());
+    }
+    Ok(json!({ "threadId": last.thread_id, "response": response }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LastThreadStore;
+
+    #[test]
+    fn touch_reports_only_thread_changes() {
+        let mut store = LastThreadStore::default();
+        assert!(store.touch("ws", "t1", 1));
+        assert!(!store.touch("ws", "t1", 2));
+        assert_eq!(store.get("ws").map(|last| last.updated_at), Some(2));
+        assert!(store.touch("ws", "t2", 3));
+        assert!(store.touch("other", "t2", 3));
+
+        store.forget_thread("other", "t1");
+        assert!(store.get("other").is_some());
+        store.forget_thread("other", "t2");
+        assert_eq!(
+            store.get("ws").map(|last| last.thread_id),
+            Some("t2".into())
+        );
+        assert!(store.get("other").is_none());
+    }
+}
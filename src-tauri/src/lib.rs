@@ -1,19 +1,34 @@
 use tauri::menu::{Menu, MenuItemBuilder, PredefinedMenuItem, Submenu};
-use tauri::{Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri::{Manager, RunEvent, WebviewUrl, WebviewWindowBuilder};
 
+mod approval_rules;
+mod archive;
+mod attachments;
 mod backend;
+mod broadcast;
 mod codex;
 mod codex_config;
 mod dictation;
+mod doctor;
 mod event_sink;
+mod file_index;
 mod git;
 mod git_utils;
+mod last_thread;
+mod mcp;
+mod notifications;
 mod prompts;
+mod scheduler;
 mod settings;
 mod state;
+mod templates;
 mod terminal;
+mod thread_export;
+mod thread_search;
 mod storage;
+mod turn_history;
 mod types;
+mod usage;
 mod utils;
 mod workspaces;
 
@@ -130,6 +145,7 @@ pub fn run() {
         .setup(|app| {
             let state = state::AppState::load(&app.handle());
             app.manage(state);
+            scheduler::spawn_scheduler(app.handle().clone());
             #[cfg(desktop)]
             app.handle()
                 .plugin(tauri_plugin_updater::Builder::new().build())?;
@@ -144,24 +160,41 @@ pub fn run() {
             codex::codex_doctor,
             workspaces::list_workspaces,
             workspaces::add_workspace,
+            workspaces::discover_workspaces,
             workspaces::add_worktree,
             workspaces::remove_workspace,
             workspaces::remove_worktree,
+            workspaces::create_thread_worktree,
+            workspaces::merge_thread_worktree,
+            workspaces::discard_thread_worktree,
             workspaces::update_workspace_settings,
             workspaces::update_workspace_codex_bin,
             codex::start_thread,
             codex::send_user_message,
             codex::turn_interrupt,
+            codex::interrupt_all,
+            codex::interrupt_workspace_group,
+            codex::kill_backend,
             codex::start_review,
             codex::respond_to_server_request,
+            codex::list_server_requests,
             codex::resume_thread,
             codex::list_threads,
+            thread_search::search_threads,
+            thread_export::export_thread,
             codex::archive_thread,
+            archive::list_archived_threads,
+            approval_rules::list_auto_approvals,
+            mcp::list_mcp_resources,
+            archive::export_archived_thread,
+            archive::delete_archived_thread,
             codex::collaboration_mode_list,
             workspaces::connect_workspace,
             git::get_git_status,
             git::list_git_roots,
             git::get_git_diffs,
+            git::workspace_git_status,
+            git::workspace_git_diff,
             git::get_git_log,
             git::get_git_remote,
             git::get_github_issues,
@@ -175,7 +208,24 @@ pub fn run() {
             codex::model_list,
             codex::account_rate_limits,
             codex::skills_list,
+            codex::get_backend_logs,
+            codex::export_backend_logs,
+            codex::replay_events,
+            codex::get_request_queue_metrics,
+            codex::get_session_stats,
+            codex::get_backend_capabilities,
+            usage::usage_summary,
+            turn_history::get_turn_history,
+            scheduler::list_schedules,
+            scheduler::save_schedule,
+            scheduler::delete_schedule,
+            scheduler::run_schedule_now,
             prompts::prompts_list,
+            templates::send_template_message,
+            last_thread::get_last_thread,
+            last_thread::resume_last_thread,
+            broadcast::broadcast_turn,
+            broadcast::list_broadcasts,
             terminal::terminal_open,
             terminal::terminal_write,
             terminal::terminal_resize,
@@ -188,6 +238,12 @@ pub fn run() {
             dictation::dictation_stop,
             dictation::dictation_cancel
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                let state = app.state::<state::AppState>();
+                tauri::async_runtime::block_on(state.shutdown());
+            }
+        });
 }
@@ -0,0 +1,440 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::{AppHandle, State};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+use tokio::time::timeout;
+
+use crate::attachments::{MAX_FILE_BYTES, MAX_TOTAL_BYTES};
+use crate::backend::app_server::build_codex_path_env;
+use crate::state::AppState;
+use crate::types::McpServerConfig;
+
+const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+const MCP_REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// Pages of `resources/list` followed per server.
+const MAX_LIST_PAGES: usize = 10;
+/// JSON-RPC "method not found", sent back for server-initiated requests.
+const METHOD_NOT_FOUND_CODE: i64 = -32601;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub(crate) struct McpResource {
+    #[serde(rename = "serverId")]
+    pub(crate) server_id: String,
+    #[serde(rename = "serverName")]
+    pub(crate) server_name: String,
+    pub(crate) uri: String,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    #[serde(rename = "mimeType")]
+    pub(crate) mime_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct McpServerError {
+    #[serde(rename = "serverId")]
+    pub(crate) server_id: String,
+    pub(crate) error: String,
+}
+
+/// Resources from every server that answered, plus why the others did not.
+#[derive(Debug, Serialize, Clone, Default)]
+pub(crate) struct McpResourceList {
+    pub(crate) resources: Vec<McpResource>,
+    pub(crate) errors: Vec<McpServerError>,
+}
+
+/// A resource picked in the composer.
+#[derive(Debug, Deserialize, Clone)]
+pub(crate) struct McpResourceRef {
+    #[serde(rename = "serverId")]
+    pub(crate) server_id: String,
+    pub(crate) uri: String,
+}
+
+struct McpIo {
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+}
+
+/// One running MCP server. Requests are sent one at a time.
+pub(crate) struct McpClient {
+    config: McpServerConfig,
+    child: Mutex<Child>,
+    io: Mutex<McpIo>,
+    next_id: AtomicU64,
+}
+
+impl McpClient {
+    async fn start(
+        config: &McpServerConfig,
+        cwd: &str,
+        client_version: &str,
+    ) -> Result<Self, String> {
+        let mut command = Command::new(&config.command);
+        command.args(&config.args);
+        if let Some(path) = build_codex_path_env(None) {
+            command.env("PATH", path);
+        }
+        command.envs(&config.env);
+        command.current_dir(cwd);
+        command.stdin(std::process::Stdio::piped());
+        command.stdout(std::process::Stdio::piped());
+        command.stderr(std::process::Stdio::null());
+        command.kill_on_drop(true);
+        let mut child = command
+            .spawn()
+            .map_err(|err| format!("Failed to start `{}`: {err}", config.command))?;
+        let stdin = child.stdin.take().ok_or("missing MCP server stdin")?;
+        let stdout = child.stdout.take().ok_or("missing MCP server stdout")?;
+        let client = Self {
+            config: config.clone(),
+            child: Mutex::new(child),
+            io: Mutex::new(McpIo {
+                stdin,
+                stdout: BufReader::new(stdout).lines(),
+            }),
+            next_id: AtomicU64::new(1),
+        };
+        client
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": MCP_PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": { "name": "codex_monitor", "version": client_version }
+                }),
+            )
+            .await?;
+        client
+            .write(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .await?;
+        Ok(client)
+    }
+
+    async fn write(&self, message: Value) -> Result<(), String> {
+        let mut io = self.io.lock().await;
+        write_line(&mut io.stdin, &message).await
+    }
+
+    async fn is_running(&self) -> bool {
+        matches!(self.child.lock().await.try_wait(), Ok(None))
+    }
+
+    pub(crate) async fn kill(&self) {
+        let _ = self.child.lock().await.kill().await;
+    }
+
+    /// Sends a request and waits for its result. Notifications are skipped
+    /// and requests from the server are refused, since we offer no
+    /// client features.
+    async fn request(&self, method: &str, params: Value) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let mut io = self.io.lock().await;
+        write_line(
+            &mut io.stdin,
+            &json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }),
+        )
+        .await?;
+        let read = async {
+            loop {
+                let line = io
+                    .stdout
+                    .next_line()
+                    .await
+                    .map_err(|err| err.to_string())?
+                    .ok_or_else(|| format!("`{}` exited", self.config.name))?;
+                let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                    continue;
+                };
+                if message.get("method").is_some() {
+                    if let Some(request_id) = message.get("id") {
+                        let reply = json!({
+                            "jsonrpc": "2.0",
+                            "id": request_id,
+                            "error": { "code": METHOD_NOT_FOUND_CODE, "message": "not supported" }
+                        });
+                        write_line(&mut io.stdin, &reply).await?;
+                    }
+                    continue;
+                }
+                if message.get("id").and_then(Value::as_u64) != Some(id) {
+                    continue;
+                }
+                if let Some(error) = message.get("error") {
+                    let text = error
+                        .get("message")
+                        .and_then(Value::as_str)
+                        .unwrap_or("request failed");
+                    return Err(format!("{method}: {text}"));
+                }
+                return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+            }
+        };
+        timeout(MCP_REQUEST_TIMEOUT, read).await.map_err(|_| {
+            format!(
+                "`{}` did not answer `{method}` within {}s.",
+                self.config.name,
+                MCP_REQUEST_TIMEOUT.as_secs()
+            )
+        })?
+    }
+
+    async fn list_resources(&self) -> Result<Vec<McpResource>, String> {
+        let mut resources = Vec::new();
+        let mut cursor: Option<String> = None;
+        for _ in 0..MAX_LIST_PAGES {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let result = self.request("resources/list", params).await?;
+            resources.extend(parse_resources(&self.config, &result));
+            cursor = result
+                .get("nextCursor")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(resources)
+    }
+}
+
+async fn write_line(stdin: &mut ChildStdin, message: &Value) -> Result<(), String> {
+    let mut line = serde_json::to_string(message).map_err(|err| err.to_string())?;
+    line.push('\n');
+    stdin
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|err| err.to_string())
+}
+
+fn parse_resources(config: &McpServerConfig, result: &Value) -> Vec<McpResource> {
+    let text = |resource: &Value, key: &str| {
+        resource
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    result
+        .get("resources")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|resource| {
+            let uri = text(resource, "uri")?;
+            Some(McpResource {
+                server_id: config.id.clone(),
+                server_name: config.name.clone(),
+                name: text(resource, "name").unwrap_or_else(|| uri.clone()),
+                uri,
+                description: text(resource, "description"),
+                mime_type: text(resource, "mimeType"),
+            })
+        })
+        .collect()
+}
+
+/// Turns a `resources/read` result into `turn/start` input items. Text is
+/// inlined and images become data URLs; other binary content is refused.
+/// `total_bytes` is shared across resources so the attachment caps apply
+/// to the whole message.
+fn resource_inputs(
+    server: &str,
+    uri: &str,
+    result: &Value,
+    total_bytes: &mut u64,
+) -> Result<Vec<Value>, String> {
+    let mut inputs = Vec::new();
+    let contents = result
+        .get("contents")
+        .and_then(Value::as_array)
+        .ok_or_else(|| format!("{uri} returned no contents"))?;
+    for content in contents {
+        let mime = content
+            .get("mimeType")
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        let size = content
+            .get("text")
+            .or_else(|| content.get("blob"))
+            .and_then(Value::as_str)
+            .map_or(0, |body| body.len() as u64);
+        if size > MAX_FILE_BYTES {
+            return Err(format!(
+                "Resource {uri} is too large ({size} bytes, limit {MAX_FILE_BYTES})"
+            ));
+        }
+        *total_bytes += size;
+        if *total_bytes > MAX_TOTAL_BYTES {
+            return Err(format!(
+                "Resources exceed {MAX_TOTAL_BYTES} bytes of inlined content"
+            ));
+        }
+        if let Some(text) = content.get("text").and_then(Value::as_str) {
+            let body = text.strip_suffix('\n').unwrap_or(text);
+            inputs.push(json!({
+                "type": "text",
+                "text": format!(
+                    "<mcp_resource server=\"{server}\" uri=\"{uri}\">\n{body}\n</mcp_resource>"
+                )
+            }));
+        } else if let Some(blob) = content.get("blob").and_then(Value::as_str) {
+            if !mime.starts_with("image/") {
+                return Err(format!(
+                    "Resource {uri} is binary ({mime}) and cannot be attached"
+                ));
+            }
+            inputs.push(json!({ "type": "image", "url": format!("data:{mime};base64,{blob}") }));
+        }
+    }
+    Ok(inputs)
+}
+
+/// The running client for `config`, started (or restarted after a crash
+/// or config change) on demand.
+async fn client_for(
+    state: &AppState,
+    workspace_id: &str,
+    cwd: &str,
+    config: &McpServerConfig,
+    client_version: &str,
+) -> Result<Arc<McpClient>, String> {
+    let key = format!("{workspace_id}/{}", config.id);
+    let mut clients = state.mcp_clients.lock().await;
+    if let Some(client) = clients.get(&key) {
+        if client.config == *config && client.is_running().await {
+            return Ok(Arc::clone(client));
+        }
+        client.kill().await;
+    }
+    let client = Arc::new(McpClient::start(config, cwd, client_version).await?);
+    clients.insert(key, Arc::clone(&client));
+    Ok(client)
+}
+
+async fn workspace_servers(
+    state: &AppState,
+    workspace_id: &str,
+) -> Result<(String, Vec<McpServerConfig>), String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces.get(workspace_id).ok_or("workspace not found")?;
+    Ok((entry.path.clone(), entry.settings.mcp_servers.clone()))
+}
+
+/// Reads the selected resources and returns them as input items.
+pub(crate) async fn mcp_resource_inputs(
+    state: &AppState,
+    workspace_id: &str,
+    refs: &[McpResourceRef],
+    client_version: &str,
+) -> Result<Vec<Value>, String> {
+    if refs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (cwd, servers) = workspace_servers(state, workspace_id).await?;
+    let mut inputs = Vec::new();
+    let mut total_bytes = 0u64;
+    for resource in refs {
+        let config = servers
+            .iter()
+            .find(|server| server.id == resource.server_id)
+            .ok_or_else(|| format!("Unknown MCP server `{}`", resource.server_id))?;
+        let client = client_for(state, workspace_id, &cwd, config, client_version).await?;
+        let result = client
+            .request("resources/read", json!({ "uri": resource.uri }))
+            .await?;
+        inputs.extend(resource_inputs(
+            &config.name,
+            &resource.uri,
+            &result,
+            &mut total_bytes,
+        )?);
+    }
+    Ok(inputs)
+}
+
+#[tauri::command]
+pub(crate) async fn list_mcp_resources(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<McpResourceList, String> {
+    let client_version = app.package_info().version.to_string();
+    let (cwd, servers) = workspace_servers(&state, &workspace_id).await?;
+    let mut list = McpResourceList::default();
+    for config in &servers {
+        let resources = match client_for(&state, &workspace_id, &cwd, config, &client_version).await
+        {
+            Ok(client) => client.list_resources().await,
+            Err(err) => Err(err),
+        };
+        match resources {
+            Ok(resources) => list.resources.extend(resources),
+            Err(error) => list.errors.push(McpServerError {
+                server_id: config.id.clone(),
+                error,
+            }),
+        }
+    }
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_resources, resource_inputs};
+    use crate::attachments::MAX_FILE_BYTES;
+    use crate::types::McpServerConfig;
+    use serde_json::json;
+
+    #[test]
+    fn parse_resources_and_inline_contents() {
+        let config = McpServerConfig {
+            id: "jira".to_string(),
+            name: "Jira".to_string(),
+            command: "jira-mcp".to_string(),
+            args: Vec::new(),
+            env: Default::default(),
+        };
+        let resources = parse_resources(
+            &config,
+            &json!({ "resources": [
+                { "uri": "jira://PROJ-1", "name": "PROJ-1", "mimeType": "text/plain" },
+                { "name": "no uri" }
+            ] }),
+        );
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].server_id, "jira");
+        assert_eq!(resources[0].mime_type.as_deref(), Some("text/plain"));
+
+        let mut total = 0;
+        let inputs = resource_inputs(
+            "Jira",
+            "jira://PROJ-1",
+            &json!({ "contents": [
+                { "uri": "jira://PROJ-1", "text": "Login fails on Safari\n" },
+                { "uri": "jira://PROJ-1/shot", "mimeType": "image/png", "blob": "iVBORw0K" }
+            ] }),
+            &mut total,
+        )
+        .expect("inputs");
+        assert_eq!(
+            inputs[0]["text"],
+            "<mcp_resource server=\"Jira\" uri=\"jira://PROJ-1\">\nLogin fails on Safari\n</mcp_resource>"
+        );
+        assert_eq!(inputs[1]["url"], "data:image/png;base64,iVBORw0K");
+        assert_eq!(total, 30);
+
+        let binary = json!({ "contents": [{ "mimeType": "application/pdf", "blob": "JVBE" }] });
+        assert!(resource_inputs("Jira", "jira://doc", &binary, &mut total).is_err());
+        let huge = json!({ "contents": [{ "text": "x".repeat(MAX_FILE_BYTES as usize + 1) }] });
+        assert!(resource_inputs("Jira", "jira://big", &huge, &mut total).is_err());
+    }
+}
@@ -0,0 +1,315 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::backend::app_server::SessionStatus;
+
+/// Which stream an `itemDelta` extends.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum DeltaStream {
+    AgentMessage,
+    ReasoningSummary,
+    ReasoningText,
+    CommandOutput,
+    FileChangeOutput,
+}
+
+/// Backend-neutral view of an app-server notification. The conformance
+/// harness reports against it; the UI still reads raw `app-server-event`s.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum MonitorEvent {
+    Connected,
+    #[serde(rename_all = "camelCase")]
+    Disconnected {
+        exit_code: Option<i64>,
+        will_retry: bool,
+        error: Option<String>,
+    },
+    StatusChanged {
+        status: SessionStatus,
+    },
+    #[serde(rename_all = "camelCase")]
+    ApprovalRequest {
+        request_id: u64,
+        method: String,
+        params: Value,
+    },
+    #[serde(rename_all = "camelCase")]
+    TurnStarted {
+        thread_id: String,
+        turn_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    TurnCompleted {
+        thread_id: String,
+        turn_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    TurnError {
+        thread_id: String,
+        turn_id: String,
+        message: String,
+        will_retry: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    TurnPlanUpdated {
+        thread_id: String,
+        turn_id: String,
+        explanation: Value,
+        plan: Value,
+    },
+    #[serde(rename_all = "camelCase")]
+    TurnDiffUpdated {
+        thread_id: String,
+        diff: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    ItemStarted {
+        thread_id: String,
+        item: Value,
+    },
+    #[serde(rename_all = "camelCase")]
+    ItemCompleted {
+        thread_id: String,
+        item: Value,
+    },
+    #[serde(rename_all = "camelCase")]
+    ItemDelta {
+        stream: DeltaStream,
+        thread_id: String,
+        item_id: String,
+        delta: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    TokenUsageUpdated {
+        thread_id: String,
+        token_usage: Value,
+    },
+    #[serde(rename_all = "camelCase")]
+    RateLimitsUpdated {
+        rate_limits: Value,
+    },
+    Log {
+        stream: String,
+        message: String,
+    },
+    /// Anything without a typed mapping yet, passed through as-is.
+    Other {
+        method: String,
+        params: Value,
+    },
+}
+
+/// Reads a string field that the server may send in camelCase or snake_case.
+fn field(params: &Value, camel: &str, snake: &str) -> String {
+    params
+        .get(camel)
+        .or_else(|| params.get(snake))
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .to_string()
+}
+
+fn delta_stream(method: &str) -> Option<DeltaStream> {
+    match method {
+        "item/agentMessage/delta" => Some(DeltaStream::AgentMessage),
+        "item/reasoning/summaryTextDelta" => Some(DeltaStream::ReasoningSummary),
+        "item/reasoning/textDelta" => Some(DeltaStream::ReasoningText),
+        "item/commandExecution/outputDelta" => Some(DeltaStream::CommandOutput),
+        "item/fileChange/outputDelta" => Some(DeltaStream::FileChangeOutput),
+        _ => None,
+    }
+}
+
+pub(crate) fn normalize(message: &Value) -> MonitorEvent {
+    let method = message
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .to_string();
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let thread_id = field(&params, "threadId", "thread_id");
+
+    if method.contains("requestApproval") {
+        if let Some(request_id) = message.get("id").and_then(Value::as_u64) {
+            return MonitorEvent::ApprovalRequest {
+                request_id,
+                method,
+                params,
+            };
+        }
+    }
+    if let Some(stream) = delta_stream(&method) {
+        return MonitorEvent::ItemDelta {
+            stream,
+            thread_id,
+            item_id: field(&params, "itemId", "item_id"),
+            delta: field(&params, "delta", "delta"),
+        };
+    }
+    match method.as_str() {
+        "codex/connected" => MonitorEvent::Connected,
+        "codex/disconnected" => MonitorEvent::Disconnected {
+            exit_code: params.get("exitCode").and_then(Value::as_i64),
+            will_retry: params
+                .get("willRetry")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            error: params
+                .get("error")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        },
+        "workspace/statusChanged" => {
+            match params
+                .get("status")
+                .cloned()
+                .and_then(|status| serde_json::from_value(status).ok())
+            {
+                Some(status) => MonitorEvent::StatusChanged { status },
+                None => MonitorEvent::Other { method, params },
+            }
+        }
+        "turn/started" | "turn/completed" => {
+            let turn = params.get("turn").cloned().unwrap_or(Value::Null);
+            let thread_id = Some(field(&turn, "threadId", "thread_id"))
+                .filter(|id| !id.is_empty())
+                .unwrap_or(thread_id);
+            let turn_id = field(&turn, "id", "id");
+            if method == "turn/started" {
+                MonitorEvent::TurnStarted { thread_id, turn_id }
+            } else {
+                MonitorEvent::TurnCompleted { thread_id, turn_id }
+            }
+        }
+        "error" => MonitorEvent::TurnError {
+            thread_id,
+            turn_id: field(&params, "turnId", "turn_id"),
+            message: params
+                .get("error")
+                .and_then(|error| error.get("message"))
+                .and_then(Value::as_str)
+                .unwrap_or("")
+                .to_string(),
+            will_retry: params
+                .get("willRetry")
+                .or_else(|| params.get("will_retry"))
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+        },
+        "turn/plan/updated" => MonitorEvent::TurnPlanUpdated {
+            thread_id,
+            turn_id: field(&params, "turnId", "turn_id"),
+            explanation: params.get("explanation").cloned().unwrap_or(Value::Null),
+            plan: params.get("plan").cloned().unwrap_or(Value::Null),
+        },
+        "turn/diff/updated" => MonitorEvent::TurnDiffUpdated {
+            thread_id,
+            diff: field(&params, "diff", "diff"),
+        },
+        "item/started" | "item/completed" => {
+            let item = params.get("item").cloned().unwrap_or(Value::Null);
+            if method == "item/started" {
+                MonitorEvent::ItemStarted { thread_id, item }
+            } else {
+                MonitorEvent::ItemCompleted { thread_id, item }
+            }
+        }
+        "thread/tokenUsage/updated" => MonitorEvent::TokenUsageUpdated {
+            thread_id,
+            token_usage: params
+                .get("tokenUsage")
+                .or_else(|| params.get("token_usage"))
+                .cloned()
+                .unwrap_or(Value::Null),
+        },
+        "account/rateLimits/updated" => MonitorEvent::RateLimitsUpdated {
+            rate_limits: params
+                .get("rateLimits")
+                .or_else(|| params.get("rate_limits"))
+                .cloned()
+                .unwrap_or(Value::Null),
+        },
+        "codex/stderr" => MonitorEvent::Log {
+            stream: "stderr".to_string(),
+            message: field(&params, "message", "message"),
+        },
+        "codex/parseError" => MonitorEvent::Log {
+            stream: "parseError".to_string(),
+            message: field(&params, "error", "error"),
+        },
+        _ => MonitorEvent::Other { method, params },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize, DeltaStream, MonitorEvent};
+    use serde_json::json;
+
+    #[test]
+    fn normalize_maps_turn_and_delta_notifications() {
+        let started = normalize(&json!({
+            "method": "turn/started",
+            "params": { "turn": { "id": "turn-1", "threadId": "thread-1" } }
+        }));
+        assert_eq!(
+            started,
+            MonitorEvent::TurnStarted {
+                thread_id: "thread-1".to_string(),
+                turn_id: "turn-1".to_string(),
+            }
+        );
+
+        let delta = normalize(&json!({
+            "method": "item/commandExecution/outputDelta",
+            "params": { "thread_id": "thread-1", "itemId": "item-1", "delta": "ok\n" }
+        }));
+        assert_eq!(
+            serde_json::to_value(&delta).unwrap(),
+            json!({
+                "type": "itemDelta",
+                "stream": "commandOutput",
+                "threadId": "thread-1",
+                "itemId": "item-1",
+                "delta": "ok\n"
+            })
+        );
+        assert!(matches!(
+            delta,
+            MonitorEvent::ItemDelta {
+                stream: DeltaStream::CommandOutput,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn normalize_tags_approvals_and_passes_through_unknown() {
+        let approval = normalize(&json!({
+            "id": 9,
+            "method": "item/commandExecution/requestApproval",
+            "params": { "command": "ls" }
+        }));
+        assert_eq!(
+            serde_json::to_value(&approval).unwrap()["type"],
+            "approvalRequest"
+        );
+
+        let other = normalize(&json!({ "method": "mcp/startup", "params": { "ok": true } }));
+        assert_eq!(
+            serde_json::to_value(&other).unwrap(),
+            json!({ "type": "other", "method": "mcp/startup", "params": { "ok": true } })
+        );
+
+        let status = normalize(&json!({
+            "method": "workspace/statusChanged",
+            "params": { "status": "unresponsive" }
+        }));
+        assert_eq!(
+            serde_json::to_value(&status).unwrap(),
+            json!({ "type": "statusChanged", "status": "unresponsive" })
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::backend::events::AppServerEvent;
+use crate::state::AppState;
+
+const NOTIFICATION_BODY_MAX_CHARS: usize = 160;
+
+fn truncate_body(text: &str) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(NOTIFICATION_BODY_MAX_CHARS) {
+        Some((index, _)) => format!("{}…", &text[..index]),
+        None => text.to_string(),
+    }
+}
+
+/// Maps an app-server message to the notification body it should raise, if any.
+fn notification_body(message: &Value) -> Option<String> {
+    let method = message.get("method").and_then(Value::as_str)?;
+    if method == "turn/completed" {
+        return Some("Turn completed.".to_string());
+    }
+    if method == "error" {
+        let params = message.get("params")?;
+        let will_retry = params
+            .get("willRetry")
+            .or_else(|| params.get("will_retry"))
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        if will_retry {
+            return None;
+        }
+        let detail = params
+            .get("error")
+            .and_then(|error| error.get("message"))
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        return Some(if detail.trim().is_empty() {
+            "Turn failed.".to_string()
+        } else {
+            format!("Turn failed: {}", truncate_body(detail))
+        });
+    }
+    if method.contains("requestApproval") && message.get("id").is_some() {
+        let command = message
+            .get("params")
+            .and_then(|params| params.get("command"))
+            .and_then(|command| match command {
+                Value::String(value) => Some(value.clone()),
+                Value::Array(parts) => Some(
+                    parts
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ),
+                _ => None,
+            })
+            .filter(|value| !value.trim().is_empty());
+        return Some(match command {
+            Some(command) => format!("Approval requested: {}", truncate_body(&command)),
+            None => "Approval requested.".to_string(),
+        });
+    }
+    None
+}
+
+fn any_window_focused(app: &AppHandle) -> bool {
+    app.webview_windows()
+        .values()
+        .any(|window| window.is_focused().unwrap_or(false))
+}
+
+/// Raises a desktop notification for turn completions, failures, and approval
+/// requests while the app is in the background, honoring the global toggle
+/// and per-workspace mute.
+pub(crate) fn notify_for_event(app: &AppHandle, event: &AppServerEvent) {
+    let Some(body) = notification_body(&event.message) else {
+        return;
+    };
+    if any_window_focused(app) {
+        return;
+    }
+    let app = app.clone();
+    let workspace_id = event.workspace_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        if !state.app_settings.lock().await.system_notifications_enabled {
+            return;
+        }
+        let workspace_name = {
+            let workspaces = state.workspaces.lock().await;
+            match workspaces.get(&workspace_id) {
+                Some(entry) if entry.settings.notifications_muted => return,
+                Some(entry) => entry.name.clone(),
+                None => return,
+            }
+        };
+        show_notification(&workspace_name, &body).await;
+    });
+}
+
+/// Raises a notification regardless of window focus, honoring the global toggle.
+pub(crate) async fn notify(app: &AppHandle, subtitle: &str, body: &str) {
+    let state = app.state::<AppState>();
+    if !state.app_settings.lock().await.system_notifications_enabled {
+        return;
+    }
+    show_notification(subtitle, &truncate_body(body)).await;
+}
+
+#[cfg(target_os = "macos")]
+async fn show_notification(subtitle: &str, body: &str) {
+    fn quote(value: &str) -> String {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+    let script = format!(
+        "display notification {} with title \"CodexMonitor\" subtitle {}",
+        quote(body),
+        quote(subtitle)
+    );
+    let _ = tokio::process::Command::new("osascript").arg("-e").arg(script).status().await;
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+async fn show_notification(subtitle: &str, body: &str) {
+    let _ = tokio::process::Command::new("notify-send")
+        .arg("--app-name=CodexMonitor")
+        .arg(format!("CodexMonitor — {subtitle}"))
+        .arg(body)
+        .status()
+        .await;
+}
+
+// There is no dependency-free notifier to shell out to here yet.
+#[cfg(not(unix))]
+async fn show_notification(_subtitle: &str, _body: &str) {}
+
+#[cfg(test)]
+mod tests {
+    use super::notification_body;
+    use serde_json::json;
+
+    #[test]
+    fn notification_body_covers_turn_outcomes_and_approvals() {
+        assert_eq!(
+            notification_body(&json!({ "method": "turn/completed", "params": {} })).as_deref(),
+            Some("Turn completed.")
+        );
+        assert_eq!(
+            notification_body(&json!({
+                "method": "error",
+                "params": { "error": { "message": "boom" }, "willRetry": false }
+            }))
+            .as_deref(),
+            Some("Turn failed: boom")
+        );
+        assert!(notification_body(&json!({
+            "method": "error",
+            "params": { "error": { "message": "boom" }, "willRetry": true }
+        }))
+        .is_none());
+        assert_eq!(
+            notification_body(&json!({
+                "id": 4,
+                "method": "item/commandExecution/requestApproval",
+                "params": { "command": ["git", "push"] }
+            }))
+            .as_deref(),
+            Some("Approval requested: git push")
+        );
+        assert!(notification_body(&json!({ "method": "item/agentMessage/delta" })).is_none());
+    }
+}
@@ -0,0 +1,521 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, State};
+use uuid::Uuid;
+
+use crate::backend::app_server::now_millis;
+use crate::backend::events::AppServerEvent;
+use crate::backend::protocol::{parse_result, TurnStartParams};
+use crate::codex::{start_thread_id, start_turn};
+use crate::notifications::notify;
+use crate::state::AppState;
+use crate::storage::write_json_file;
+use crate::utils::{civil_from_days, turn_outcome};
+
+const TICK_INTERVAL: Duration = Duration::from_secs(20);
+
+/// A saved prompt that runs in a fresh thread whenever `schedule` matches.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct ScheduledRun {
+    #[serde(default)]
+    pub(crate) id: String,
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    pub(crate) name: String,
+    pub(crate) prompt: String,
+    /// Five-field cron expression (`minute hour day month weekday`) in UTC.
+    pub(crate) schedule: String,
+    #[serde(default = "default_enabled")]
+    pub(crate) enabled: bool,
+    #[serde(default)]
+    pub(crate) model: Option<String>,
+    #[serde(default, rename = "lastRunAt")]
+    pub(crate) last_run_at: Option<u64>,
+    #[serde(default, rename = "lastThreadId")]
+    pub(crate) last_thread_id: Option<String>,
+    /// `running`, `completed`, or `failed`.
+    #[serde(default, rename = "lastStatus")]
+    pub(crate) last_status: Option<String>,
+    #[serde(default, rename = "lastError")]
+    pub(crate) last_error: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Default)]
+pub(crate) struct SchedulerState {
+    path: PathBuf,
+    pub(crate) schedules: Vec<ScheduledRun>,
+}
+
+/// Error recorded for a run that was still going when the app quit.
+const INTERRUPTED_RUN_ERROR: &str = "CodexMonitor quit before the run finished.";
+
+impl SchedulerState {
+    /// Loads saved schedules. Which threads belong to a run is only kept in
+    /// memory, so a run left `running` by the previous session can never
+    /// finish and is marked `failed`.
+    pub(crate) fn load(path: PathBuf) -> Self {
+        let mut schedules: Vec<ScheduledRun> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str::<Value>(&data).ok())
+            .and_then(|value| value.get("schedules").cloned())
+            .and_then(|schedules| serde_json::from_value(schedules).ok())
+            .unwrap_or_default();
+        for schedule in &mut schedules {
+            if schedule.last_status.as_deref() == Some("running") {
+                schedule.last_status = Some("failed".to_string());
+                schedule.last_error = Some(INTERRUPTED_RUN_ERROR.to_string());
+            }
+        }
+        Self { path, schedules }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        write_json_file(
+            &self.path,
+            &json!({ "version": 1, "schedules": self.schedules }),
+        )
+    }
+
+    fn update(&mut self, id: &str, apply: impl FnOnce(&mut ScheduledRun)) {
+        if let Some(schedule) = self.schedules.iter_mut().find(|entry| entry.id == id) {
+            apply(schedule);
+            let _ = self.save();
+        }
+    }
+}
+
+/// Threads started by the scheduler, mapped to their schedule id. Kept
+/// behind a std mutex so the event sink can check it synchronously.
+#[derive(Default)]
+pub(crate) struct ScheduledThreads(StdMutex<HashMap<String, String>>);
+
+#[derive(Debug, PartialEq)]
+struct CronField(Vec<u32>);
+
+impl CronField {
+    fn parse(field: &str, min: u32, max: u32) -> Result<Self, String> {
+        let mut values = Vec::new();
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => (
+                    range,
+                    step.parse::<u32>()
+                        .ok()
+                        .filter(|step| *step > 0)
+                        .ok_or_else(|| format!("invalid step in `{part}`"))?,
+                ),
+                None => (part, 1),
+            };
+            let (start, end) = if range == "*" {
+                (min, max)
+            } else if let Some((start, end)) = range.split_once('-') {
+                (parse_cron_value(start)?, parse_cron_value(end)?)
+            } else {
+                let value = parse_cron_value(range)?;
+                (value, if step > 1 { max } else { value })
+            };
+            if start < min || end > max || start > end {
+                return Err(format!("`{part}` is outside {min}-{max}"));
+            }
+            values.extend((start..=end).step_by(step as usize));
+        }
+        values.sort_unstable();
+        values.dedup();
+        Ok(Self(values))
+    }
+
+    fn contains(&self, value: u32) -> bool {
+        self.0.binary_search(&value).is_ok()
+    }
+}
+
+fn parse_cron_value(value: &str) -> Result<u32, String> {
+    value
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| format!("invalid cron value `{value}`"))
+}
+
+#[derive(Debug)]
+struct CronSchedule {
+    minutes: CronField,
+    hours: CronField,
+    days: CronField,
+    months: CronField,
+    weekdays: CronField,
+    /// Cron ORs day-of-month and day-of-week when both are restricted.
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl CronSchedule {
+    fn parse(expression: &str) -> Result<Self, String> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err("schedule needs five fields: minute hour day month weekday".to_string());
+        };
+        let mut weekdays = CronField::parse(weekday, 0, 7)?;
+        // Both 0 and 7 mean Sunday.
+        if weekdays.contains(7) {
+            weekdays.0.retain(|value| *value != 7);
+            if !weekdays.contains(0) {
+                weekdays.0.insert(0, 0);
+            }
+        }
+        Ok(Self {
+            minutes: CronField::parse(minute, 0, 59)?,
+            hours: CronField::parse(hour, 0, 23)?,
+            days: CronField::parse(day, 1, 31)?,
+            months: CronField::parse(month, 1, 12)?,
+            weekdays,
+            days_restricted: day != "*",
+            weekdays_restricted: weekday != "*",
+        })
+    }
+
+    /// Whether the schedule fires during the UTC minute `epoch_minute`.
+    fn matches(&self, epoch_minute: u64) -> bool {
+        let minute = (epoch_minute % 60) as u32;
+        let hour = (epoch_minute / 60 % 24) as u32;
+        let days = (epoch_minute / 1440) as i64;
+        let (_, month, day) = civil_from_days(days);
+        // 1970-01-01 was a Thursday.
+        let weekday = ((days + 4) % 7) as u32;
+        let day_matches = match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => self.days.contains(day) || self.weekdays.contains(weekday),
+            _ => self.days.contains(day) && self.weekdays.contains(weekday),
+        };
+        self.minutes.contains(minute)
+            && self.hours.contains(hour)
+            && self.months.contains(month)
+            && day_matches
+    }
+}
+
+/// Schedules due in `epoch_minute` that have not already fired in it.
+fn due_schedules(schedules: &[ScheduledRun], epoch_minute: u64) -> Vec<ScheduledRun> {
+    schedules
+        .iter()
+        .filter(|schedule| schedule.enabled)
+        .filter(|schedule| {
+            schedule
+                .last_run_at
+                .is_none_or(|last| last / 60_000 < epoch_minute)
+        })
+        .filter(|schedule| {
+            CronSchedule::parse(&schedule.schedule)
+                .map(|cron| cron.matches(epoch_minute))
+                .unwrap_or(false)
+        })
+        .cloned()
+        .collect()
+}
+
+async fn start_run(app: &AppHandle, schedule: &ScheduledRun) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    let session = state
+        .sessions
+        .lock()
+        .await
+        .get(&schedule.workspace_id)
+        .cloned()
+        .ok_or("workspace not connected")?;
+    let thread_id = start_thread_id(&session).await?;
+    state
+        .scheduled_threads
+        .0
+        .lock()
+        .unwrap()
+        .insert(thread_id.clone(), schedule.id.clone());
+    if let Some(model) = schedule.model.as_deref() {
+        state
+            .usage
+            .lock()
+            .await
+            .note_thread_model(&thread_id, model);
+    }
+    let started = start_turn(
+        &session,
+        thread_id.clone(),
+        vec![json!({ "type": "text", "text": schedule.prompt })],
+        schedule.model.clone(),
+        None,
+        None,
+        None,
+    )
+    .await
+    .and_then(|response| parse_result::<TurnStartParams>(&response));
+    if let Err(err) = started {
+        // No turn will complete for this thread, so nothing else would
+        // clear the mapping.
+        state.scheduled_threads.0.lock().unwrap().remove(&thread_id);
+        return Err(err);
+    }
+    Ok(thread_id)
+}
+
+async fn run_schedule(app: AppHandle, schedule: ScheduledRun) {
+    let state = app.state::<AppState>();
+    let started_at = now_millis();
+    state.schedules.lock().await.update(&schedule.id, |entry| {
+        entry.last_run_at = Some(started_at);
+        entry.last_status = Some("running".to_string());
+        entry.last_error = None;
+    });
+    match start_run(&app, &schedule).await {
+        Ok(thread_id) => {
+            state.schedules.lock().await.update(&schedule.id, |entry| {
+                entry.last_thread_id = Some(thread_id);
+            });
+        }
+        Err(err) => finish_run(&app, &schedule.id, Some(err)).await,
+    }
+}
+
+async fn finish_run(app: &AppHandle, schedule_id: &str, error: Option<String>) {
+    let state = app.state::<AppState>();
+    let mut finished = None;
+    state.schedules.lock().await.update(schedule_id, |entry| {
+        entry.last_status = Some(
+            if error.is_some() {
+                "failed"
+            } else {
+                "completed"
+            }
+            .to_string(),
+        );
+        entry.last_error = error.clone();
+        finished = Some((entry.workspace_id.clone(), entry.name.clone()));
+    });
+    let Some((workspace_id, name)) = finished else {
+        return;
+    };
+    let workspace_name = state
+        .workspaces
+        .lock()
+        .await
+        .get(&workspace_id)
+        .map(|entry| entry.name.clone())
+        .unwrap_or(workspace_id);
+    let body = match error {
+        Some(err) => format!("Scheduled run \u{201c}{name}\u{201d} failed: {err}"),
+        None => format!("Scheduled run \u{201c}{name}\u{201d} completed."),
+    };
+    notify(app, &workspace_name, &body).await;
+}
+
+/// Watches for the end of scheduled threads' turns. Returns `true` when the
+/// event belongs to a scheduled run, so the generic notification is skipped.
+pub(crate) fn observe_event(app: &AppHandle, event: &AppServerEvent) -> bool {
+    let outcome = turn_outcome(&event.message);
+    let Some((thread_id, error)) = outcome else {
+        return false;
+    };
+    let schedule_id = app
+        .state::<AppState>()
+        .scheduled_threads
+        .0
+        .lock()
+        .unwrap()
+        .remove(&thread_id);
+    let Some(schedule_id) = schedule_id else {
+        return false;
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        finish_run(&app, &schedule_id, error).await;
+    });
+    true
+}
+
+/// Starts the background loop that fires due schedules.
+pub(crate) fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(TICK_INTERVAL).await;
+            let epoch_minute = now_millis() / 60_000;
+            let due = {
+                let state = app.state::<AppState>();
+                let schedules = state.schedules.lock().await;
+                due_schedules(&schedules.schedules, epoch_minute)
+            };
+            for schedule in due {
+                tauri::async_runtime::spawn(run_schedule(app.clone(), schedule));
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub(crate) async fn list_schedules(
+    state: State<'_, AppState>,
+) -> Result<Vec<ScheduledRun>, String> {
+    Ok(state.schedules.lock().await.schedules.clone())
+}
+
+#[tauri::command]
+pub(crate) async fn save_schedule(
+    mut schedule: ScheduledRun,
+    state: State<'_, AppState>,
+) -> Result<ScheduledRun, String> {
+    CronSchedule::parse(&schedule.schedule)?;
+    if schedule.prompt.trim().is_empty() {
+        return Err("Prompt is required.".to_string());
+    }
+    if !state
+        .workspaces
+        .lock()
+        .await
+        .contains_key(&schedule.workspace_id)
+    {
+        return Err("workspace not found".to_string());
+    }
+    let mut schedules = state.schedules.lock().await;
+    match schedules
+        .schedules
+        .iter_mut()
+        .find(|entry| !schedule.id.is_empty() && entry.id == schedule.id)
+    {
+        Some(existing) => {
+            // Run history is owned by the scheduler, not the editor.
+            schedule.last_run_at = existing.last_run_at;
+            schedule.last_thread_id = existing.last_thread_id.take();
+            schedule.last_status = existing.last_status.take();
+            schedule.last_error = existing.last_error.take();
+            *existing = schedule.clone();
+        }
+        None => {
+            schedule.id = Uuid::new_v4().to_string();
+            schedules.schedules.push(schedule.clone());
+        }
+    }
+    schedules.save()?;
+    Ok(schedule)
+}
+
+#[tauri::command]
+pub(crate) async fn delete_schedule(id: String, state: State<'_, AppState>) -> Result<(), String> {
+    let mut schedules = state.schedules.lock().await;
+    schedules.schedules.retain(|entry| entry.id != id);
+    schedules.save()
+}
+
+#[tauri::command]
+pub(crate) async fn run_schedule_now(
+    id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let schedule = state
+        .schedules
+        .lock()
+        .await
+        .schedules
+        .iter()
+        .find(|entry| entry.id == id)
+        .cloned()
+        .ok_or("schedule not found")?;
+    tauri::async_runtime::spawn(run_schedule(app, schedule));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{due_schedules, CronSchedule, ScheduledRun, SchedulerState};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    // 2024-03-04 is a Monday; 02:30 UTC.
+    const MONDAY_0230: u64 = 28_491_990;
+
+    fn schedule(expression: &str, last_run_at: Option<u64>) -> ScheduledRun {
+        ScheduledRun {
+            id: "s1".to_string(),
+            workspace_id: "ws".to_string(),
+            name: "nightly".to_string(),
+            prompt: "update dependencies and run tests".to_string(),
+            schedule: expression.to_string(),
+            enabled: true,
+            model: None,
+            last_run_at,
+            last_thread_id: None,
+            last_status: None,
+            last_error: None,
+        }
+    }
+
+    #[test]
+    fn cron_schedule_matches_fields_in_utc() {
+        assert!(CronSchedule::parse("30 2 * * *")
+            .unwrap()
+            .matches(MONDAY_0230));
+        assert!(CronSchedule::parse("*/15 2 * * 1-5")
+            .unwrap()
+            .matches(MONDAY_0230));
+        assert!(CronSchedule::parse("30 2 4 3 *")
+            .unwrap()
+            .matches(MONDAY_0230));
+        assert!(!CronSchedule::parse("30 2 * * 0,6")
+            .unwrap()
+            .matches(MONDAY_0230));
+        // Day-of-month and weekday are ORed when both are set.
+        assert!(CronSchedule::parse("30 2 1 * 1")
+            .unwrap()
+            .matches(MONDAY_0230));
+        assert!(!CronSchedule::parse("@daily").unwrap().matches(MONDAY_0230));
+        assert!(CronSchedule::parse("61 * * * *").is_err());
+        assert!(CronSchedule::parse("* * *").is_err());
+    }
+
+    #[test]
+    fn due_schedules_fire_once_per_minute() {
+        let fresh = schedule("30 2 * * *", None);
+        let already = schedule("30 2 * * *", Some(MONDAY_0230 * 60_000 + 5_000));
+        let mut disabled = schedule("30 2 * * *", None);
+        disabled.enabled = false;
+        let due = due_schedules(&[fresh, already, disabled], MONDAY_0230);
+        assert_eq!(due.len(), 1);
+        assert!(due[0].last_run_at.is_none());
+    }
+
+    #[test]
+    fn load_fails_runs_left_running_by_a_previous_session() {
+        let dir = std::env::temp_dir().join(format!("codex-monitor-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let path = dir.join("schedules.json");
+        let mut running = schedule("0 3 * * *", Some(MONDAY_0230));
+        running.last_status = Some("running".to_string());
+        let mut done = schedule("0 4 * * *", Some(MONDAY_0230));
+        done.id = "s2".to_string();
+        done.last_status = Some("completed".to_string());
+        std::fs::write(
+            &path,
+            json!({ "version": 1, "schedules": [running, done] }).to_string(),
+        )
+        .expect("write schedules");
+
+        let state = SchedulerState::load(path);
+        assert_eq!(state.schedules[0].last_status.as_deref(), Some("failed"));
+        assert!(state.schedules[0].last_error.is_some());
+        assert_eq!(state.schedules[1].last_status.as_deref(), Some("completed"));
+        assert!(state.schedules[1].last_error.is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
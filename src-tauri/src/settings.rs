@@ -1,5 +1,7 @@
 use tauri::State;
 
+use crate::approval_rules::validate_approval_rules;
+use crate::backend::app_server::{validate_codex_args, validate_verbosity};
 use crate::codex_config;
 use crate::state::AppState;
 use crate::storage::write_settings;
@@ -22,13 +24,23 @@ pub(crate) async fn get_app_settings(state: State<'_, AppState>) -> Result<AppSe
 
 #[tauri::command]
 pub(crate) async fn update_app_settings(
-    settings: AppSettings,
+    mut settings: AppSettings,
     state: State<'_, AppState>,
 ) -> Result<AppSettings, String> {
+    settings.codex_args = validate_codex_args(&settings.codex_args)?;
+    settings.verbosity = validate_verbosity(settings.verbosity.as_deref())?;
+    for group in &mut settings.workspace_groups {
+        group.normalize_color()?;
+    }
+    validate_approval_rules(&settings.approval_rules)?;
     let _ = codex_config::write_collab_enabled(settings.experimental_collab_enabled);
     let _ = codex_config::write_steer_enabled(settings.experimental_steer_enabled);
     let _ = codex_config::write_unified_exec_enabled(settings.experimental_unified_exec_enabled);
     write_settings(&state.settings_path, &settings)?;
+    for session in state.sessions.lock().await.values() {
+        session.apply_settings(&settings);
+    }
+    *state.approval_rules.lock().unwrap() = settings.approval_rules.clone();
     let mut current = state.app_settings.lock().await;
     *current = settings.clone();
     Ok(settings)
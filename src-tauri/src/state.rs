@@ -1,13 +1,25 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use tauri::{AppHandle, Manager};
 use tokio::sync::Mutex;
 
+use crate::approval_rules::ApprovalLog;
+use crate::archive::ThreadJournal;
+use crate::broadcast::BroadcastRegistry;
 use crate::dictation::DictationState;
+use crate::file_index::FileIndexCache;
+use crate::last_thread::LastThreadStore;
+use crate::mcp::McpClient;
+use crate::scheduler::{ScheduledThreads, SchedulerState};
 use crate::storage::{read_settings, read_workspaces};
-use crate::types::{AppSettings, WorkspaceEntry};
+use crate::turn_history::{fail_running_turns, TurnHistoryStore};
+use crate::types::{AppSettings, ApprovalRule, WorkspaceEntry};
+use crate::usage::UsageStore;
+
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(2);
 
 pub(crate) struct AppState {
     pub(crate) workspaces: Mutex<HashMap<String, WorkspaceEntry>>,
@@ -18,6 +30,20 @@ pub(crate) struct AppState {
     pub(crate) settings_path: PathBuf,
     pub(crate) app_settings: Mutex<AppSettings>,
     pub(crate) dictation: Mutex<DictationState>,
+    pub(crate) file_index: FileIndexCache,
+    pub(crate) usage: Mutex<UsageStore>,
+    pub(crate) schedules: Mutex<SchedulerState>,
+    pub(crate) scheduled_threads: ScheduledThreads,
+    pub(crate) broadcasts: std::sync::Mutex<BroadcastRegistry>,
+    pub(crate) last_threads: Mutex<LastThreadStore>,
+    pub(crate) archive_dir: PathBuf,
+    pub(crate) thread_journal: std::sync::Mutex<ThreadJournal>,
+    pub(crate) turn_history: Mutex<TurnHistoryStore>,
+    /// Copy of `AppSettings::approval_rules` readable from the event sink.
+    pub(crate) approval_rules: std::sync::Mutex<Vec<ApprovalRule>>,
+    pub(crate) approval_log: std::sync::Mutex<ApprovalLog>,
+    /// Running MCP servers keyed by `<workspace id>/<server id>`.
+    pub(crate) mcp_clients: Mutex<HashMap<String, Arc<McpClient>>>,
 }
 
 impl AppState {
@@ -28,8 +54,20 @@ impl AppState {
             .unwrap_or_else(|_| std::env::current_dir().unwrap_or_else(|_| ".".into()));
         let storage_path = data_dir.join("workspaces.json");
         let settings_path = data_dir.join("settings.json");
-        let workspaces = read_workspaces(&storage_path).unwrap_or_default();
-        let app_settings = read_settings(&settings_path).unwrap_or_default();
+        // An unreadable file is left alone: the app starts without it and
+        // `write_workspaces` / `write_settings` refuse to replace it.
+        let workspaces = read_workspaces(&storage_path).unwrap_or_else(|err| {
+            eprintln!("Failed to read {}: {err}", storage_path.display());
+            HashMap::new()
+        });
+        let app_settings = read_settings(&settings_path).unwrap_or_else(|err| {
+            eprintln!("Failed to read {}: {err}", settings_path.display());
+            AppSettings::default()
+        });
+        let approval_rules = app_settings.approval_rules.clone();
+        let usage = UsageStore::load(data_dir.join("usage.json"));
+        let schedules = SchedulerState::load(data_dir.join("schedules.json"));
+        let last_threads = LastThreadStore::load(data_dir.join("last_threads.json"));
         Self {
             workspaces: Mutex::new(workspaces),
             sessions: Mutex::new(HashMap::new()),
@@ -38,6 +76,64 @@ impl AppState {
             settings_path,
             app_settings: Mutex::new(app_settings),
             dictation: Mutex::new(DictationState::default()),
+            file_index: FileIndexCache::default(),
+            usage: Mutex::new(usage),
+            schedules: Mutex::new(schedules),
+            scheduled_threads: ScheduledThreads::default(),
+            broadcasts: std::sync::Mutex::new(BroadcastRegistry::default()),
+            last_threads: Mutex::new(last_threads),
+            archive_dir: data_dir.join("archive"),
+            thread_journal: std::sync::Mutex::new(ThreadJournal::default()),
+            turn_history: Mutex::new(TurnHistoryStore::new(data_dir.join("history"))),
+            approval_rules: std::sync::Mutex::new(approval_rules),
+            approval_log: std::sync::Mutex::new(ApprovalLog::default()),
+            mcp_clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Stops every app-server and terminal child so quitting the app does
+    /// not leave orphaned processes behind.
+    pub(crate) async fn shutdown(&self) {
+        let sessions: Vec<_> = self
+            .sessions
+            .lock()
+            .await
+            .drain()
+            .map(|(_, session)| session)
+            .collect();
+        let handles: Vec<_> = sessions
+            .into_iter()
+            .map(|session| {
+                tokio::spawn(async move {
+                    session.shutdown(SHUTDOWN_GRACE).await;
+                })
+            })
+            .collect();
+        for handle in handles {
+            let _ = handle.await;
+        }
+        fail_running_turns(self, None).await;
+
+        let terminals: Vec<_> = self
+            .terminal_sessions
+            .lock()
+            .await
+            .drain()
+            .map(|(_, terminal)| terminal)
+            .collect();
+        for terminal in terminals {
+            let _ = terminal.child.lock().await.kill();
+        }
+        let mcp_clients: Vec<_> = self
+            .mcp_clients
+            .lock()
+            .await
+            .drain()
+            .map(|(_, client)| client)
+            .collect();
+        for client in mcp_clients {
+            client.kill().await;
         }
+        let _ = self.usage.lock().await.flush();
     }
 }
@@ -1,14 +1,83 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
 
 use crate::types::{AppSettings, WorkspaceEntry};
 
+const WORKSPACES_SCHEMA_VERSION: u64 = 1;
+const SETTINGS_SCHEMA_VERSION: u64 = 1;
+
+/// Writes through a sibling temp file so a crash mid-write never leaves a
+/// truncated document behind.
+pub(crate) fn write_json_file(path: &PathBuf, value: &Value) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, data).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp_path, path).map_err(|e| e.to_string())
+}
+
+/// Key holding the schema version in both `workspaces.json` and
+/// `settings.json`.
+const SCHEMA_VERSION_KEY: &str = "version";
+
+/// Reads the schema version of a stored document. Files written before
+/// versioning have none and count as version 0. A version newer than this
+/// build knows is an error rather than being read as the current one.
+fn schema_version(value: &Value, file: &str, current: u64) -> Result<u64, String> {
+    let version = value
+        .get(SCHEMA_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    if version > current {
+        return Err(format!(
+            "{file} has schema version {version}, but this build only reads up to {current}."
+        ));
+    }
+    Ok(version)
+}
+
+/// Upgrades a `workspaces.json` document to the current schema. Version 0 was
+/// a bare array of entries; version 1 wraps it as `{ version, workspaces }`.
+fn migrate_workspaces(value: Value) -> Result<Vec<WorkspaceEntry>, String> {
+    if value.is_array() {
+        return serde_json::from_value(value).map_err(|e| e.to_string());
+    }
+    schema_version(&value, "workspaces.json", WORKSPACES_SCHEMA_VERSION)?;
+    let list = value
+        .get("workspaces")
+        .cloned()
+        .unwrap_or_else(|| json!([]));
+    serde_json::from_value(list).map_err(|e| e.to_string())
+}
+
+/// Upgrades a `settings.json` document to the current schema. Version 0 had no
+/// `version` key and otherwise matches version 1.
+fn migrate_settings(mut value: Value) -> Result<AppSettings, String> {
+    schema_version(&value, "settings.json", SETTINGS_SCHEMA_VERSION)?;
+    if let Some(object) = value.as_object_mut() {
+        object.remove(SCHEMA_VERSION_KEY);
+    }
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+fn not_overwriting(path: &Path, err: String) -> String {
+    format!(
+        "Not saving to {}: the existing file could not be read ({err}).",
+        path.display()
+    )
+}
+
 pub(crate) fn read_workspaces(path: &PathBuf) -> Result<HashMap<String, WorkspaceEntry>, String> {
     if !path.exists() {
         return Ok(HashMap::new());
     }
     let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
-    let list: Vec<WorkspaceEntry> = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    let value: Value = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    let list = migrate_workspaces(value)?;
     Ok(list
         .into_iter()
         .map(|entry| (entry.id.clone(), entry))
@@ -16,11 +85,14 @@ pub(crate) fn read_workspaces(path: &PathBuf) -> Result<HashMap<String, Workspac
 }
 
 pub(crate) fn write_workspaces(path: &PathBuf, entries: &[WorkspaceEntry]) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-    let data = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
-    std::fs::write(path, data).map_err(|e| e.to_string())
+    // Never replace a file this build could not read (newer schema or
+    // corrupt) with whatever was loaded in its place.
+    read_workspaces(path).map_err(|err| not_overwriting(path, err))?;
+    let document = json!({
+        SCHEMA_VERSION_KEY: WORKSPACES_SCHEMA_VERSION,
+        "workspaces": entries,
+    });
+    write_json_file(path, &document)
 }
 
 pub(crate) fn read_settings(path: &PathBuf) -> Result<AppSettings, String> {
@@ -28,21 +100,26 @@ pub(crate) fn read_settings(path: &PathBuf) -> Result<AppSettings, String> {
         return Ok(AppSettings::default());
     }
     let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&data).map_err(|e| e.to_string())
+    let value: Value = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    migrate_settings(value)
 }
 
 pub(crate) fn write_settings(path: &PathBuf, settings: &AppSettings) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    read_settings(path).map_err(|err| not_overwriting(path, err))?;
+    let mut document = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    if let Some(object) = document.as_object_mut() {
+        object.insert(
+            SCHEMA_VERSION_KEY.to_string(),
+            json!(SETTINGS_SCHEMA_VERSION),
+        );
     }
-    let data = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
-    std::fs::write(path, data).map_err(|e| e.to_string())
+    write_json_file(path, &document)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{read_workspaces, write_workspaces};
-    use crate::types::{WorkspaceEntry, WorkspaceKind, WorkspaceSettings};
+    use super::{read_settings, read_workspaces, write_settings, write_workspaces};
+    use crate::types::{AppSettings, WorkspaceEntry, WorkspaceKind, WorkspaceSettings};
     use uuid::Uuid;
 
     #[test]
@@ -77,4 +154,67 @@ mod tests {
         assert!(stored.settings.sidebar_collapsed);
         assert_eq!(stored.settings.git_root.as_deref(), Some("/tmp"));
     }
+
+    #[test]
+    fn read_workspaces_migrates_legacy_array() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("codex-monitor-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&temp_dir).expect("create temp dir");
+        let path = temp_dir.join("workspaces.json");
+        std::fs::write(
+            &path,
+            r#"[{"id":"w1","name":"Workspace","path":"/tmp","codex_bin":null}]"#,
+        )
+        .expect("write legacy workspaces");
+
+        let read = read_workspaces(&path).expect("read legacy workspaces");
+        let entries: Vec<_> = read.values().cloned().collect();
+        assert_eq!(entries.len(), 1);
+
+        write_workspaces(&path, &entries).expect("write workspaces");
+        let data = std::fs::read_to_string(&path).expect("read file");
+        let value: serde_json::Value = serde_json::from_str(&data).expect("parse file");
+        assert_eq!(value["version"], 1);
+        assert_eq!(value["workspaces"][0]["id"], "w1");
+        assert!(read_workspaces(&path).expect("reread").contains_key("w1"));
+    }
+
+    #[test]
+    fn write_read_settings_stamps_schema_version() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("codex-monitor-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&temp_dir).expect("create temp dir");
+        let path = temp_dir.join("settings.json");
+
+        let mut settings = AppSettings::default();
+        settings.ui_scale = 1.25;
+        write_settings(&path, &settings).expect("write settings");
+
+        let data = std::fs::read_to_string(&path).expect("read file");
+        let value: serde_json::Value = serde_json::from_str(&data).expect("parse file");
+        assert_eq!(value["version"], 1);
+        let read = read_settings(&path).expect("read settings");
+        assert_eq!(read.ui_scale, 1.25);
+    }
+
+    #[test]
+    fn read_rejects_newer_schema_versions() {
+        let temp_dir =
+            std::env::temp_dir().join(format!("codex-monitor-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&temp_dir).expect("create temp dir");
+        let workspaces_path = temp_dir.join("workspaces.json");
+        std::fs::write(&workspaces_path, r#"{"version":2,"workspaces":[]}"#)
+            .expect("write workspaces");
+        let err = read_workspaces(&workspaces_path).expect_err("newer workspaces");
+        assert!(err.contains("schema version 2"));
+
+        let settings_path = temp_dir.join("settings.json");
+        std::fs::write(&settings_path, r#"{"version":2}"#).expect("write settings");
+        assert!(read_settings(&settings_path).is_err());
+
+        assert!(write_workspaces(&workspaces_path, &[]).is_err());
+        assert!(write_settings(&settings_path, &AppSettings::default()).is_err());
+        let kept = std::fs::read_to_string(&settings_path).expect("read settings");
+        assert_eq!(kept, r#"{"version":2}"#);
+    }
 }
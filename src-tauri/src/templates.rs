@@ -0,0 +1,149 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde_json::{json, Value};
+use tauri::State;
+
+use crate::backend::protocol::{parse_result, TurnStartParams};
+use crate::codex::{start_thread_id, start_turn};
+use crate::state::AppState;
+
+/// Replaces `{name}` placeholders in a template body. `{{` and `}}` produce
+/// literal braces; every placeholder must have a value.
+pub(crate) fn expand_template(
+    body: &str,
+    vars: &HashMap<String, String>,
+) -> Result<String, String> {
+    let mut output = String::with_capacity(body.len());
+    let mut missing = BTreeSet::new();
+    let mut chars = body.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                output.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                output.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                let mut closed = false;
+                for next in chars.by_ref() {
+                    if next == '}' {
+                        closed = true;
+                        break;
+                    }
+                    name.push(next);
+                }
+                if !closed {
+                    return Err("unterminated placeholder in template".to_string());
+                }
+                let key = name.trim();
+                if key.is_empty() {
+                    return Err("empty placeholder in template".to_string());
+                }
+                match vars.get(key) {
+                    Some(value) => output.push_str(value),
+                    None => {
+                        missing.insert(key.to_string());
+                    }
+                }
+            }
+            _ => output.push(ch),
+        }
+    }
+    if !missing.is_empty() {
+        let names: Vec<String> = missing.into_iter().collect();
+        return Err(format!("missing template variables: {}", names.join(", ")));
+    }
+    Ok(output)
+}
+
+#[tauri::command]
+pub(crate) async fn send_template_message(
+    workspace_id: String,
+    template_id: String,
+    vars: HashMap<String, String>,
+    thread_id: Option<String>,
+    model: Option<String>,
+    effort: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Value, String> {
+    let template = state
+        .app_settings
+        .lock()
+        .await
+        .prompt_templates
+        .iter()
+        .find(|template| template.id == template_id)
+        .cloned()
+        .ok_or("template not found")?;
+    let text = expand_template(&template.body, &vars)?;
+    if text.trim().is_empty() {
+        return Err("template expanded to an empty message".to_string());
+    }
+
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    let thread_id = match thread_id {
+        Some(thread_id) => thread_id,
+        None => start_thread_id(&session).await?,
+    };
+    if let Some(response) = session.turn_in_progress(&thread_id).await {
+        return Ok(json!({ "threadId": thread_id, "response": response }));
+    }
+    if let Some(model) = model.as_deref() {
+        state
+            .usage
+            .lock()
+            .await
+            .note_thread_model(&thread_id, model);
+    }
+    let response = start_turn(
+        &session,
+        thread_id.clone(),
+        vec![json!({ "type": "text", "text": text.trim() })],
+        model,
+        effort,
+        None,
+        None,
+    )
+    .await?;
+    parse_result::<TurnStartParams>(&response)?;
+    Ok(json!({ "threadId": thread_id, "response": response }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand_template;
+    use std::collections::HashMap;
+
+    #[test]
+    fn expand_template_fills_placeholders_and_escapes() {
+        let vars = HashMap::from([
+            ("file".to_string(), "src/lib.rs".to_string()),
+            ("focus".to_string(), "error handling".to_string()),
+        ]);
+        assert_eq!(
+            expand_template("Review {file} for { focus }. Keep {{json}} as-is.", &vars).unwrap(),
+            "Review src/lib.rs for error handling. Keep {json} as-is."
+        );
+    }
+
+    #[test]
+    fn expand_template_reports_missing_and_malformed() {
+        let vars = HashMap::from([("a".to_string(), "1".to_string())]);
+        assert_eq!(
+            expand_template("{a} {c} {b} {c}", &vars).unwrap_err(),
+            "missing template variables: b, c"
+        );
+        assert!(expand_template("{a", &vars).is_err());
+        assert!(expand_template("{}", &vars).is_err());
+    }
+}
@@ -0,0 +1,390 @@
+use serde_json::Value;
+use std::fmt::Write as _;
+
+use tauri::State;
+
+use crate::backend::protocol::{parse_result, ThreadResumeParams};
+use crate::state::AppState;
+
+fn as_text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn user_inputs_text(inputs: Option<&Value>) -> String {
+    inputs
+        .and_then(Value::as_array)
+        .map(|inputs| {
+            inputs
+                .iter()
+                .filter_map(|input| match input.get("type").and_then(Value::as_str) {
+                    Some("text") => input
+                        .get("text")
+                        .and_then(Value::as_str)
+                        .map(str::to_string),
+                    Some("skill") => input
+                        .get("name")
+                        .and_then(Value::as_str)
+                        .map(|name| format!("${name}")),
+                    Some("image") | Some("localImage") => Some("[image]".to_string()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_default()
+}
+
+/// One rendered entry of a thread transcript, in the order Codex produced it.
+#[derive(Debug, PartialEq)]
+enum ExportEntry {
+    Message {
+        role: &'static str,
+        text: String,
+    },
+    Reasoning(String),
+    Command {
+        command: String,
+        status: String,
+        output: String,
+    },
+    FileChange {
+        path: String,
+        kind: String,
+        diff: String,
+    },
+}
+
+/// Flattens a `thread/resume` thread (`turns[].items[]`) into export entries.
+fn collect_entries(thread: &Value) -> Vec<ExportEntry> {
+    let mut entries = Vec::new();
+    let turns = thread.get("turns").and_then(Value::as_array);
+    for turn in turns.into_iter().flatten() {
+        let items = turn.get("items").and_then(Value::as_array);
+        for item in items.into_iter().flatten() {
+            match item.get("type").and_then(Value::as_str) {
+                Some("userMessage") => entries.push(ExportEntry::Message {
+                    role: "user",
+                    text: user_inputs_text(item.get("content")),
+                }),
+                Some("agentMessage") => entries.push(ExportEntry::Message {
+                    role: "assistant",
+                    text: as_text(item.get("text")),
+                }),
+                Some("reasoning") => {
+                    let summary = as_text(item.get("summary"));
+                    if !summary.trim().is_empty() {
+                        entries.push(ExportEntry::Reasoning(summary));
+                    }
+                }
+                Some("commandExecution") => {
+                    let command = match item.get("command") {
+                        Some(Value::Array(parts)) => parts
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                        other => as_text(other),
+                    };
+                    entries.push(ExportEntry::Command {
+                        command,
+                        status: as_text(item.get("status")),
+                        output: as_text(item.get("aggregatedOutput")),
+                    });
+                }
+                Some("fileChange") => {
+                    let changes = item.get("changes").and_then(Value::as_array);
+                    for change in changes.into_iter().flatten() {
+                        let kind = match change.get("kind") {
+                            Some(Value::Object(kind)) => as_text(kind.get("type")),
+                            other => as_text(other),
+                        };
+                        entries.push(ExportEntry::FileChange {
+                            path: as_text(change.get("path")),
+                            kind: kind.to_lowercase(),
+                            diff: as_text(change.get("diff")),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    entries
+}
+
+/// Plain text of a thread's messages and touched paths, for searching.
+pub(crate) fn searchable_text(thread: &Value) -> String {
+    let mut text = String::new();
+    for entry in collect_entries(thread) {
+        let part = match entry {
+            ExportEntry::Message { text, .. } | ExportEntry::Reasoning(text) => text,
+            ExportEntry::Command { command, .. } => command,
+            ExportEntry::FileChange { path, .. } => path,
+        };
+        text.push_str(&part);
+        text.push('\n');
+    }
+    text
+}
+
+pub(crate) fn thread_title(thread: &Value, thread_id: &str) -> String {
+    thread
+        .get("preview")
+        .and_then(Value::as_str)
+        .and_then(|preview| preview.lines().next())
+        .map(str::trim)
+        .filter(|preview| !preview.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Thread {thread_id}"))
+}
+
+/// Picks a backtick fence longer than any run inside `body`.
+fn fence_for(body: &str) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+    for ch in body.chars() {
+        if ch == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn render_markdown(title: &str, entries: &[ExportEntry]) -> String {
+    let mut out = format!("# {title}\n");
+    for entry in entries {
+        out.push('\n');
+        match entry {
+            ExportEntry::Message { role, text } => {
+                let heading = if *role == "user" { "User" } else { "Assistant" };
+                let _ = writeln!(out, "## {heading}\n\n{}", text.trim());
+            }
+            ExportEntry::Reasoning(summary) => {
+                for line in summary.trim().lines() {
+                    let _ = writeln!(out, "> {line}");
+                }
+            }
+            ExportEntry::Command {
+                command,
+                status,
+                output,
+            } => {
+                let fence = fence_for(output);
+                let _ = writeln!(out, "**Command** `{command}`");
+                if !status.is_empty() {
+                    let _ = writeln!(out, "_Status: {status}_");
+                }
+                if !output.trim().is_empty() {
+                    let _ = writeln!(out, "\n{fence}\n{}\n{fence}", output.trim_end());
+                }
+            }
+            ExportEntry::FileChange { path, kind, diff } => {
+                let fence = fence_for(diff);
+                let label = if kind.is_empty() { "change" } else { kind };
+                let _ = writeln!(out, "**File {label}** `{path}`");
+                if !diff.trim().is_empty() {
+                    let _ = writeln!(out, "\n{fence}diff\n{}\n{fence}", diff.trim_end());
+                }
+            }
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+const HTML_STYLE: &str = "body{font-family:-apple-system,system-ui,sans-serif;max-width:860px;margin:2rem auto;padding:0 1rem;color:#1d1d1f}\
+section{margin:1rem 0}h2{font-size:0.85rem;text-transform:uppercase;color:#6e6e73}\
+.message{white-space:pre-wrap}.reasoning{color:#6e6e73;font-style:italic;white-space:pre-wrap}\
+pre{background:#f5f5f7;padding:0.75rem;overflow-x:auto;border-radius:6px}";
+
+fn render_html(title: &str, entries: &[ExportEntry]) -> String {
+    let mut out = format!(
+        "<!doctype html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n<h1>{0}</h1>\n",
+        escape_html(title)
+    );
+    for entry in entries {
+        match entry {
+            ExportEntry::Message { role, text } => {
+                let heading = if *role == "user" { "User" } else { "Assistant" };
+                let _ = writeln!(
+                    out,
+                    "<section class=\"{role}\"><h2>{heading}</h2><div class=\"message\">{}</div></section>",
+                    escape_html(text.trim())
+                );
+            }
+            ExportEntry::Reasoning(summary) => {
+                let _ = writeln!(
+                    out,
+                    "<section><div class=\"reasoning\">{}</div></section>",
+                    escape_html(summary.trim())
+                );
+            }
+            ExportEntry::Command {
+                command,
+                status,
+                output,
+            } => {
+                let _ = write!(
+                    out,
+                    "<section><h2>Command</h2><code>{}</code>",
+                    escape_html(command)
+                );
+                if !status.is_empty() {
+                    let _ = write!(out, " <em>{}</em>", escape_html(status));
+                }
+                if !output.trim().is_empty() {
+                    let _ = write!(out, "<pre>{}</pre>", escape_html(output.trim_end()));
+                }
+                out.push_str("</section>\n");
+            }
+            ExportEntry::FileChange { path, kind, diff } => {
+                let label = if kind.is_empty() { "change" } else { kind };
+                let _ = write!(
+                    out,
+                    "<section><h2>File {}</h2><code>{}</code>",
+                    escape_html(label),
+                    escape_html(path)
+                );
+                if !diff.trim().is_empty() {
+                    let _ = write!(out, "<pre>{}</pre>", escape_html(diff.trim_end()));
+                }
+                out.push_str("</section>\n");
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Renders a thread as `markdown`, `html`, or `json` (the raw thread object).
+pub(crate) fn render_thread(
+    thread: &Value,
+    thread_id: &str,
+    format: &str,
+) -> Result<String, String> {
+    let title = thread_title(thread, thread_id);
+    match format {
+        "markdown" | "md" => Ok(render_markdown(&title, &collect_entries(thread))),
+        "html" => Ok(render_html(&title, &collect_entries(thread))),
+        "json" => serde_json::to_string_pretty(thread).map_err(|e| e.to_string()),
+        other => Err(format!("unsupported export format: {other}")),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn export_thread(
+    workspace_id: String,
+    thread_id: String,
+    format: String,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    let params = ThreadResumeParams {
+        thread_id: thread_id.clone(),
+    };
+    let response = session.request(&params).await?;
+    let result = parse_result::<ThreadResumeParams>(&response)?;
+    write_rendered(render_thread(&result.thread, &thread_id, &format)?, path)
+}
+
+/// Writes an export to `path` when one is given and returns it either way.
+pub(crate) fn write_rendered(rendered: String, path: Option<String>) -> Result<String, String> {
+    if let Some(path) = path {
+        std::fs::write(&path, &rendered).map_err(|e| e.to_string())?;
+    }
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_entries, render_thread, ExportEntry};
+    use serde_json::json;
+
+    fn sample_thread() -> serde_json::Value {
+        json!({
+            "id": "thread-1",
+            "preview": "Fix <login> bug",
+            "turns": [{
+                "id": "turn-1",
+                "items": [
+                    { "type": "userMessage", "id": "u1", "content": [{ "type": "text", "text": "Fix <login> bug" }] },
+                    { "type": "commandExecution", "id": "c1", "command": ["cargo", "test"], "status": "completed", "aggregatedOutput": "ok" },
+                    { "type": "fileChange", "id": "f1", "changes": [{ "path": "src/login.rs", "kind": { "type": "update" }, "diff": "-a\n+b" }] },
+                    { "type": "agentMessage", "id": "a1", "text": "Done." }
+                ]
+            }]
+        })
+    }
+
+    #[test]
+    fn collect_entries_follows_turn_item_order() {
+        let entries = collect_entries(&sample_thread());
+        assert_eq!(entries.len(), 4);
+        assert_eq!(
+            entries[0],
+            ExportEntry::Message {
+                role: "user",
+                text: "Fix <login> bug".to_string()
+            }
+        );
+        assert_eq!(
+            entries[2],
+            ExportEntry::FileChange {
+                path: "src/login.rs".to_string(),
+                kind: "update".to_string(),
+                diff: "-a\n+b".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn render_thread_supports_each_format() {
+        let thread = sample_thread();
+        let markdown = render_thread(&thread, "thread-1", "markdown").expect("markdown");
+        assert!(markdown.starts_with("# Fix <login> bug\n"));
+        assert!(markdown.contains("**Command** `cargo test`"));
+        assert!(markdown.contains("```diff\n-a\n+b\n```"));
+
+        let html = render_thread(&thread, "thread-1", "html").expect("html");
+        assert!(html.contains("<h1>Fix &lt;login&gt; bug</h1>"));
+
+        let raw = render_thread(&thread, "thread-1", "json").expect("json");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&raw).unwrap(),
+            thread
+        );
+        assert!(render_thread(&thread, "thread-1", "pdf").is_err());
+    }
+}
@@ -0,0 +1,370 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use tauri::State;
+use tokio::task;
+
+use crate::codex_config::resolve_codex_home;
+use crate::state::AppState;
+use crate::types::WorkspaceEntry;
+
+const MAX_ROLLOUT_FILES: usize = 2000;
+const DEFAULT_RESULT_LIMIT: usize = 50;
+const SNIPPET_RADIUS: usize = 60;
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct ThreadSearchResult {
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    #[serde(rename = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) preview: String,
+    pub(crate) snippet: String,
+    pub(crate) score: u32,
+    #[serde(rename = "updatedAt")]
+    pub(crate) updated_at: i64,
+}
+
+#[derive(Debug, Default)]
+struct ThreadTranscript {
+    thread_id: String,
+    cwd: String,
+    preview: String,
+    messages: Vec<String>,
+    files: Vec<String>,
+}
+
+fn message_text(payload: &Value) -> String {
+    payload
+        .get("content")
+        .and_then(Value::as_array)
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|part| part.get("text").and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
+
+/// Pulls file paths out of `apply_patch` bodies (`*** Update File: path`).
+fn patch_files(patch: &str, files: &mut Vec<String>) {
+    for line in patch.lines() {
+        let path = [
+            "*** Add File: ",
+            "*** Update File: ",
+            "*** Delete File: ",
+            "*** Move to: ",
+        ]
+        .iter()
+        .find_map(|prefix| line.trim().strip_prefix(prefix));
+        if let Some(path) = path.map(str::trim).filter(|path| !path.is_empty()) {
+            if !files.iter().any(|existing| existing == path) {
+                files.push(path.to_string());
+            }
+        }
+    }
+}
+
+/// Parses a Codex rollout (`rollout-*.jsonl`) into the text that search cares about.
+fn parse_rollout(contents: &str) -> Option<ThreadTranscript> {
+    let mut transcript = ThreadTranscript::default();
+    for line in contents.lines() {
+        let Ok(value) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let Some(payload) = value.get("payload") else {
+            continue;
+        };
+        match value.get("type").and_then(Value::as_str) {
+            Some("session_meta") => {
+                if let Some(id) = payload.get("id").and_then(Value::as_str) {
+                    transcript.thread_id = id.to_string();
+                }
+                if let Some(cwd) = payload.get("cwd").and_then(Value::as_str) {
+                    transcript.cwd = cwd.to_string();
+                }
+            }
+            Some("response_item") => match payload.get("type").and_then(Value::as_str) {
+                Some("message") => {
+                    let text = message_text(payload);
+                    let trimmed = text.trim();
+                    // Skip context blocks Codex injects ahead of the real prompt.
+                    if trimmed.is_empty() || trimmed.starts_with('<') {
+                        continue;
+                    }
+                    let is_user = payload.get("role").and_then(Value::as_str) == Some("user");
+                    if is_user && transcript.preview.is_empty() {
+                        transcript.preview = trimmed.lines().next().unwrap_or("").to_string();
+                    }
+                    transcript.messages.push(trimmed.to_string());
+                }
+                Some("function_call") | Some("custom_tool_call") => {
+                    let body = payload
+                        .get("input")
+                        .or_else(|| payload.get("arguments"))
+                        .and_then(Value::as_str)
+                        .unwrap_or("");
+                    patch_files(&body.replace("\\n", "\n"), &mut transcript.files);
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    if transcript.thread_id.is_empty() {
+        None
+    } else {
+        Some(transcript)
+    }
+}
+
+/// Byte offset in `text` of the first case-insensitive match of the
+/// lowercase `needle`. Lowercasing can change how many bytes and chars a
+/// character takes, so matching runs on lowered chars that each remember
+/// the byte offset of the character they came from.
+fn find_lowercase(text: &str, needle: &str) -> Option<usize> {
+    let needle: Vec<char> = needle.chars().collect();
+    if needle.is_empty() {
+        return Some(0);
+    }
+    let (lowered, offsets): (Vec<char>, Vec<usize>) = text
+        .char_indices()
+        .flat_map(|(index, ch)| ch.to_lowercase().map(move |lower| (lower, index)))
+        .unzip();
+    lowered
+        .windows(needle.len())
+        .position(|window| window == needle.as_slice())
+        .map(|position| offsets[position])
+}
+
+fn snippet_around(text: &str, needle: &str) -> String {
+    let start = find_lowercase(text, needle).unwrap_or(0);
+    let from = text[..start]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_RADIUS)
+        .map(|(index, _)| index)
+        .unwrap_or(0);
+    let to = text[start..]
+        .char_indices()
+        .nth(needle.chars().count() + SNIPPET_RADIUS)
+        .map(|(index, _)| start + index)
+        .unwrap_or(text.len());
+    let mut snippet = text[from..to]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if from > 0 {
+        snippet.insert(0, '…');
+    }
+    if to < text.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// Scores a transcript against lowercase query terms. Every term has to
+/// appear somewhere; preview hits weigh most, then file paths, then messages.
+fn score_transcript(transcript: &ThreadTranscript, terms: &[String]) -> Option<(u32, String)> {
+    let preview = transcript.preview.to_lowercase();
+    let messages: Vec<String> = transcript
+        .messages
+        .iter()
+        .map(|message| message.to_lowercase())
+        .collect();
+    let mut score = 0u32;
+    for term in terms {
+        let mut term_score = 0u32;
+        if preview.contains(term.as_str()) {
+            term_score += 3;
+        }
+        term_score += 2 * transcript
+            .files
+            .iter()
+            .filter(|file| file.to_lowercase().contains(term.as_str()))
+            .count() as u32;
+        term_score += messages
+            .iter()
+            .map(|message| message.matches(term.as_str()).count() as u32)
+            .sum::<u32>();
+        if term_score == 0 {
+            return None;
+        }
+        score += term_score;
+    }
+    let first = &terms[0];
+    let snippet = messages
+        .iter()
+        .position(|message| message.contains(first.as_str()))
+        .map(|index| snippet_around(&transcript.messages[index], first))
+        .or_else(|| {
+            transcript
+                .files
+                .iter()
+                .find(|file| file.to_lowercase().contains(first.as_str()))
+                .cloned()
+        })
+        .unwrap_or_else(|| transcript.preview.clone());
+    Some((score, snippet))
+}
+
+fn collect_rollouts(dir: &Path, out: &mut Vec<(PathBuf, i64)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            collect_rollouts(&path, out);
+            continue;
+        }
+        let is_rollout = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.starts_with("rollout-") && name.ends_with(".jsonl"))
+            .unwrap_or(false);
+        if !is_rollout {
+            continue;
+        }
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or(0);
+        out.push((path, modified));
+    }
+}
+
+fn workspace_for_cwd<'a>(
+    workspaces: &'a [WorkspaceEntry],
+    cwd: &str,
+) -> Option<&'a WorkspaceEntry> {
+    let cwd = Path::new(cwd);
+    workspaces
+        .iter()
+        .filter(|entry| cwd.starts_with(&entry.path))
+        .max_by_key(|entry| entry.path.len())
+}
+
+fn search_rollouts(
+    codex_homes: &[PathBuf],
+    workspaces: &[WorkspaceEntry],
+    query: &str,
+    limit: usize,
+) -> Vec<ThreadSearchResult> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| term.to_lowercase())
+        .collect();
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    let mut files = Vec::new();
+    for home in codex_homes {
+        collect_rollouts(&home.join("sessions"), &mut files);
+    }
+    files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+    files.truncate(MAX_ROLLOUT_FILES);
+
+    let mut results = Vec::new();
+    for (path, updated_at) in files {
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let Some(transcript) = parse_rollout(&contents) else {
+            continue;
+        };
+        let Some(workspace) = workspace_for_cwd(workspaces, &transcript.cwd) else {
+            continue;
+        };
+        let Some((score, snippet)) = score_transcript(&transcript, &terms) else {
+            continue;
+        };
+        results.push(ThreadSearchResult {
+            workspace_id: workspace.id.clone(),
+            thread_id: transcript.thread_id,
+            preview: transcript.preview,
+            snippet,
+            score,
+            updated_at,
+        });
+    }
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| b.updated_at.cmp(&a.updated_at))
+    });
+    results.truncate(limit);
+    results
+}
+
+#[tauri::command]
+pub(crate) async fn search_threads(
+    query: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ThreadSearchResult>, String> {
+    let workspaces: Vec<WorkspaceEntry> = state.workspaces.lock().await.values().cloned().collect();
+    let mut codex_homes: Vec<PathBuf> = resolve_codex_home().into_iter().collect();
+    for entry in &workspaces {
+        let legacy_home = PathBuf::from(&entry.path).join(".codexmonitor");
+        if legacy_home.is_dir() && !codex_homes.contains(&legacy_home) {
+            codex_homes.push(legacy_home);
+        }
+    }
+    let limit = limit.unwrap_or(DEFAULT_RESULT_LIMIT).clamp(1, 500);
+    task::spawn_blocking(move || search_rollouts(&codex_homes, &workspaces, &query, limit))
+        .await
+        .map_err(|_| "thread search failed".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_rollout, score_transcript, snippet_around};
+
+    const ROLLOUT: &str = r#"{"timestamp":"t","type":"session_meta","payload":{"id":"thread-1","cwd":"/tmp/app"}}
+{"timestamp":"t","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"<environment_context>ignored</environment_context>"}]}}
+{"timestamp":"t","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"Fix the auth bug in login"}]}}
+{"timestamp":"t","type":"response_item","payload":{"type":"custom_tool_call","name":"apply_patch","input":"*** Begin Patch\n*** Update File: src/auth/session.rs\n*** End Patch"}}
+{"timestamp":"t","type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"The auth token expired early; fixed the refresh."}]}}
+"#;
+
+    #[test]
+    fn parse_rollout_collects_preview_messages_and_files() {
+        let transcript = parse_rollout(ROLLOUT).expect("transcript");
+        assert_eq!(transcript.thread_id, "thread-1");
+        assert_eq!(transcript.cwd, "/tmp/app");
+        assert_eq!(transcript.preview, "Fix the auth bug in login");
+        assert_eq!(transcript.messages.len(), 2);
+        assert_eq!(transcript.files, vec!["src/auth/session.rs".to_string()]);
+    }
+
+    #[test]
+    fn score_transcript_requires_every_term() {
+        let transcript = parse_rollout(ROLLOUT).expect("transcript");
+        let terms = vec!["auth".to_string(), "refresh".to_string()];
+        let (score, snippet) = score_transcript(&transcript, &terms).expect("match");
+        assert!(score > 3);
+        assert!(snippet.contains("auth"));
+        assert!(score_transcript(&transcript, &["billing".to_string()]).is_none());
+    }
+
+    #[test]
+    fn snippet_around_handles_case_changes_that_resize_chars() {
+        // `İ` grows by a byte when lowercased and the ohm sign shrinks by
+        // one, so the lowered text has the same length but shifted offsets.
+        assert_eq!(snippet_around("İxééé \u{2126}", "ééé"), "İxééé \u{2126}");
+        assert_eq!(snippet_around("İstanbul", "i̇stanbul"), "İstanbul");
+        assert_eq!(snippet_around("no match here", "absent"), "no match here");
+    }
+}
@@ -0,0 +1,399 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::backend::app_server::now_millis;
+use crate::backend::events::AppServerEvent;
+use crate::state::AppState;
+use crate::storage::write_json_file;
+use crate::utils::file_safe_name;
+
+/// Oldest turns are dropped past this many per thread.
+const MAX_TURNS_PER_THREAD: usize = 500;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct TurnRecord {
+    #[serde(rename = "turnId")]
+    pub(crate) turn_id: String,
+    /// `inProgress` until the turn ends, then the server's final status
+    /// (`completed`, `interrupted`, `failed`).
+    pub(crate) status: String,
+    #[serde(rename = "startedAt")]
+    pub(crate) started_at: u64,
+    #[serde(default, rename = "completedAt")]
+    pub(crate) completed_at: Option<u64>,
+    #[serde(default)]
+    pub(crate) error: Option<String>,
+    #[serde(default)]
+    pub(crate) items: Vec<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct ThreadHistory {
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    #[serde(rename = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) turns: Vec<TurnRecord>,
+}
+
+impl ThreadHistory {
+    fn turn_mut(&mut self, turn_id: &str, now: u64) -> &mut TurnRecord {
+        let index = match self.turns.iter().position(|turn| turn.turn_id == turn_id) {
+            Some(index) => index,
+            None => {
+                self.turns.push(TurnRecord {
+                    turn_id: turn_id.to_string(),
+                    status: "inProgress".to_string(),
+                    started_at: now,
+                    completed_at: None,
+                    error: None,
+                    items: Vec::new(),
+                });
+                if self.turns.len() > MAX_TURNS_PER_THREAD {
+                    self.turns.remove(0);
+                }
+                self.turns.len() - 1
+            }
+        };
+        &mut self.turns[index]
+    }
+}
+
+/// Turn and item events per thread, kept in `history/<thread>.json` so the
+/// UI can rebuild turns it never saw live. A thread is loaded when one of
+/// its turns starts, then written and dropped from memory once no turn on
+/// it is still running.
+pub(crate) struct TurnHistoryStore {
+    dir: PathBuf,
+    threads: HashMap<String, ThreadHistory>,
+}
+
+fn read_field(params: &Value, camel: &str, snake: &str) -> String {
+    params
+        .get(camel)
+        .or_else(|| params.get(snake))
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .to_string()
+}
+
+impl TurnHistoryStore {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            threads: HashMap::new(),
+        }
+    }
+
+    fn path(&self, thread_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", file_safe_name(thread_id)))
+    }
+
+    fn load(&self, thread_id: &str) -> Option<ThreadHistory> {
+        let data = std::fs::read_to_string(self.path(thread_id)).ok()?;
+        let value = serde_json::from_str::<Value>(&data).ok()?;
+        serde_json::from_value(value.get("thread")?.clone()).ok()
+    }
+
+    fn thread_mut(&mut self, workspace_id: &str, thread_id: &str) -> &mut ThreadHistory {
+        if !self.threads.contains_key(thread_id) {
+            let loaded = self.load(thread_id).unwrap_or_else(|| ThreadHistory {
+                workspace_id: workspace_id.to_string(),
+                thread_id: thread_id.to_string(),
+                turns: Vec::new(),
+            });
+            self.threads.insert(thread_id.to_string(), loaded);
+        }
+        self.threads.get_mut(thread_id).expect("thread loaded")
+    }
+
+    /// Writes the thread out and drops it from memory once none of its
+    /// turns is still in progress. Checking the thread rather than the
+    /// event keeps late `item/completed` events from being lost.
+    fn settle(&mut self, thread_id: &str) -> Result<(), String> {
+        let Some(thread) = self.threads.get(thread_id) else {
+            return Ok(());
+        };
+        if thread.turns.iter().any(|turn| turn.status == "inProgress") {
+            return Ok(());
+        }
+        write_json_file(
+            &self.path(thread_id),
+            &json!({ "version": 1, "thread": thread }),
+        )?;
+        self.threads.remove(thread_id);
+        Ok(())
+    }
+
+    /// Folds one app-server message into the history. Returns the id of the
+    /// thread it changed.
+    fn record(&mut self, workspace_id: &str, message: &Value, now: u64) -> Option<String> {
+        let method = message.get("method").and_then(Value::as_str)?;
+        let params = message.get("params")?;
+        match method {
+            "turn/started" | "turn/completed" => {
+                let turn = params.get("turn")?;
+                let thread_id = Some(read_field(turn, "threadId", "thread_id"))
+                    .filter(|id| !id.is_empty())
+                    .unwrap_or_else(|| read_field(params, "threadId", "thread_id"));
+                let turn_id = read_field(turn, "id", "id");
+                if thread_id.is_empty() || turn_id.is_empty() {
+                    return None;
+                }
+                let record = self
+                    .thread_mut(workspace_id, &thread_id)
+                    .turn_mut(&turn_id, now);
+                if method == "turn/started" {
+                    return Some(thread_id);
+                }
+                record.status = Some(read_field(turn, "status", "status"))
+                    .filter(|status| !status.is_empty())
+                    .unwrap_or_else(|| "completed".to_string());
+                record.completed_at = Some(now);
+                if let Some(error) = turn
+                    .get("error")
+                    .and_then(|error| error.get("message"))
+                    .and_then(Value::as_str)
+                {
+                    record.error = Some(error.to_string());
+                }
+                Some(thread_id)
+            }
+            "item/completed" => {
+                let thread_id = read_field(params, "threadId", "thread_id");
+                let turn_id = read_field(params, "turnId", "turn_id");
+                let item = params.get("item")?;
+                if thread_id.is_empty() || turn_id.is_empty() {
+                    return None;
+                }
+                let record = self
+                    .thread_mut(workspace_id, &thread_id)
+                    .turn_mut(&turn_id, now);
+                let item_id = item.get("id").and_then(Value::as_str);
+                match record.items.iter_mut().find(|existing| {
+                    item_id.is_some() && existing.get("id").and_then(Value::as_str) == item_id
+                }) {
+                    Some(existing) => *existing = item.clone(),
+                    None => record.items.push(item.clone()),
+                }
+                Some(thread_id)
+            }
+            "error" => {
+                let will_retry = params
+                    .get("willRetry")
+                    .or_else(|| params.get("will_retry"))
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                let thread_id = read_field(params, "threadId", "thread_id");
+                let turn_id = read_field(params, "turnId", "turn_id");
+                if will_retry || thread_id.is_empty() || turn_id.is_empty() {
+                    return None;
+                }
+                let record = self
+                    .thread_mut(workspace_id, &thread_id)
+                    .turn_mut(&turn_id, now);
+                record.status = "failed".to_string();
+                record.completed_at = Some(now);
+                record.error = params
+                    .get("error")
+                    .and_then(|error| error.get("message"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+                Some(thread_id)
+            }
+            _ => None,
+        }
+    }
+
+    /// Marks turns that can no longer finish as failed and writes their
+    /// threads out. Only threads of `workspace_id` are touched when given.
+    fn fail_running(&mut self, workspace_id: Option<&str>, now: u64) {
+        let mut changed = Vec::new();
+        for (thread_id, thread) in &mut self.threads {
+            if workspace_id.is_some_and(|id| id != thread.workspace_id) {
+                continue;
+            }
+            for turn in &mut thread.turns {
+                if turn.status == "inProgress" {
+                    turn.status = "failed".to_string();
+                    turn.completed_at = Some(now);
+                    turn.error =
+                        Some("The app-server stopped before the turn finished.".to_string());
+                    changed.push(thread_id.clone());
+                }
+            }
+        }
+        for thread_id in changed {
+            let _ = self.settle(&thread_id);
+        }
+    }
+
+    fn history(&self, thread_id: &str) -> Option<ThreadHistory> {
+        match self.threads.get(thread_id) {
+            Some(thread) => Some(thread.clone()),
+            None => self.load(thread_id),
+        }
+    }
+}
+
+pub(crate) fn record_event(app: &AppHandle, event: &AppServerEvent) {
+    let method = event.message.get("method").and_then(Value::as_str);
+    if !matches!(
+        method,
+        Some(
+            "turn/started"
+                | "turn/completed"
+                | "item/completed"
+                | "error"
+                | "codex/disconnected"
+        )
+    ) {
+        return;
+    }
+    let disconnected = method == Some("codex/disconnected");
+    let app = app.clone();
+    let workspace_id = event.workspace_id.clone();
+    let message = event.message.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let mut history = state.turn_history.lock().await;
+        if disconnected {
+            // Turns cut off by a crash never get `turn/completed`.
+            history.fail_running(Some(&workspace_id), now_millis());
+        } else if let Some(thread_id) = history.record(&workspace_id, &message, now_millis()) {
+            let _ = history.settle(&thread_id);
+        }
+    });
+}
+
+/// Fails and saves the running turns of a stopped session, or of every
+/// session when `workspace_id` is `None`.
+pub(crate) async fn fail_running_turns(state: &AppState, workspace_id: Option<&str>) {
+    state
+        .turn_history
+        .lock()
+        .await
+        .fail_running(workspace_id, now_millis());
+}
+
+#[tauri::command]
+pub(crate) async fn get_turn_history(
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<ThreadHistory>, String> {
+    Ok(state.turn_history.lock().await.history(&thread_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TurnHistoryStore;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn record_persists_turns_and_reloads_from_disk() {
+        let dir = std::env::temp_dir().join(format!("codex-monitor-test-{}", Uuid::new_v4()));
+        let mut store = TurnHistoryStore::new(dir.clone());
+        let started = json!({
+            "method": "turn/started",
+            "params": { "threadId": "t1", "turn": { "id": "turn-1" } }
+        });
+        assert_eq!(store.record("ws", &started, 10).as_deref(), Some("t1"));
+        store.settle("t1").expect("settle running turn");
+        assert!(!store.path("t1").exists());
+        let item = |text: &str| {
+            json!({
+                "method": "item/completed",
+                "params": {
+                    "threadId": "t1",
+                    "turnId": "turn-1",
+                    "item": { "id": "item-1", "type": "agentMessage", "text": text }
+                }
+            })
+        };
+        store.record("ws", &item("draft"), 11);
+        store.record("ws", &item("final"), 12);
+        let completed = json!({
+            "method": "turn/completed",
+            "params": { "threadId": "t1", "turn": { "id": "turn-1", "status": "interrupted" } }
+        });
+        assert_eq!(store.record("ws", &completed, 20).as_deref(), Some("t1"));
+        store.settle("t1").expect("save history");
+        assert!(store.threads.is_empty());
+
+        let reloaded = TurnHistoryStore::new(dir.clone());
+        let history = reloaded.history("t1").expect("history");
+        assert_eq!(history.workspace_id, "ws");
+        assert_eq!(history.turns.len(), 1);
+        let turn = &history.turns[0];
+        assert_eq!(turn.status, "interrupted");
+        assert_eq!((turn.started_at, turn.completed_at), (10, Some(20)));
+        assert_eq!(turn.items.len(), 1);
+        assert_eq!(turn.items[0]["text"], "final");
+        assert!(reloaded.history("missing").is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn late_items_are_added_to_the_saved_turn() {
+        let dir = std::env::temp_dir().join(format!("codex-monitor-test-{}", Uuid::new_v4()));
+        let mut store = TurnHistoryStore::new(dir.clone());
+        let completed = json!({
+            "method": "turn/completed",
+            "params": { "threadId": "t1", "turn": { "id": "turn-1", "status": "completed" } }
+        });
+        store.record("ws", &completed, 10);
+        store.settle("t1").expect("save history");
+        let late = json!({
+            "method": "item/completed",
+            "params": {
+                "threadId": "t1",
+                "turnId": "turn-1",
+                "item": { "id": "item-1", "type": "agentMessage", "text": "late" }
+            }
+        });
+        store.record("ws", &late, 11);
+        store.settle("t1").expect("save late item");
+
+        let history = TurnHistoryStore::new(dir.clone())
+            .history("t1")
+            .expect("history");
+        assert_eq!(history.turns.len(), 1);
+        assert_eq!(history.turns[0].status, "completed");
+        assert_eq!(history.turns[0].items[0]["text"], "late");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn fail_running_saves_turns_of_the_stopped_workspace() {
+        let dir = std::env::temp_dir().join(format!("codex-monitor-test-{}", Uuid::new_v4()));
+        let mut store = TurnHistoryStore::new(dir.clone());
+        for (workspace_id, thread_id) in [("ws-1", "t1"), ("ws-2", "t2")] {
+            let started = json!({
+                "method": "turn/started",
+                "params": { "threadId": thread_id, "turn": { "id": "turn-1" } }
+            });
+            store.record(workspace_id, &started, 10);
+        }
+        store.fail_running(Some("ws-1"), 20);
+        assert!(store.threads.contains_key("t2"));
+        assert!(!store.threads.contains_key("t1"));
+
+        let history = TurnHistoryStore::new(dir.clone())
+            .history("t1")
+            .expect("history");
+        assert_eq!(history.turns[0].status, "failed");
+        assert_eq!(history.turns[0].completed_at, Some(20));
+
+        store.fail_running(None, 30);
+        assert!(store.threads.is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
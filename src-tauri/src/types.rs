@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::backend::app_server::DEFAULT_REQUEST_TIMEOUT_SECS;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct GitFileStatus {
@@ -14,6 +17,60 @@ pub(crate) struct GitFileDiff {
     pub(crate) diff: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitChangedFile {
+    pub(crate) path: String,
+    #[serde(rename = "oldPath")]
+    pub(crate) old_path: Option<String>,
+    pub(crate) status: String,
+    pub(crate) staged: bool,
+    pub(crate) unstaged: bool,
+    pub(crate) additions: i64,
+    pub(crate) deletions: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct WorkspaceGitStatus {
+    #[serde(rename = "branchName")]
+    pub(crate) branch_name: String,
+    pub(crate) files: Vec<GitChangedFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitDiffLine {
+    /// `context`, `add`, or `delete`.
+    pub(crate) kind: String,
+    pub(crate) content: String,
+    #[serde(rename = "oldLine")]
+    pub(crate) old_line: Option<u32>,
+    #[serde(rename = "newLine")]
+    pub(crate) new_line: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitDiffHunk {
+    pub(crate) header: String,
+    #[serde(rename = "oldStart")]
+    pub(crate) old_start: u32,
+    #[serde(rename = "oldLines")]
+    pub(crate) old_lines: u32,
+    #[serde(rename = "newStart")]
+    pub(crate) new_start: u32,
+    #[serde(rename = "newLines")]
+    pub(crate) new_lines: u32,
+    pub(crate) lines: Vec<GitDiffLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitFileHunks {
+    pub(crate) path: String,
+    #[serde(rename = "oldPath")]
+    pub(crate) old_path: Option<String>,
+    pub(crate) status: String,
+    pub(crate) binary: bool,
+    pub(crate) hunks: Vec<GitDiffHunk>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct GitLogEntry {
     pub(crate) sha: String,
@@ -128,6 +185,16 @@ pub(crate) struct WorkspaceInfo {
     pub(crate) settings: WorkspaceSettings,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct DiscoveredWorkspace {
+    pub(crate) name: String,
+    pub(crate) path: String,
+    #[serde(default)]
+    pub(crate) languages: Vec<String>,
+    #[serde(default, rename = "alreadyAdded")]
+    pub(crate) already_added: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "lowercase")]
 pub(crate) enum WorkspaceKind {
@@ -150,6 +217,9 @@ impl WorkspaceKind {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct WorktreeInfo {
     pub(crate) branch: String,
+    /// Set when the worktree was created to isolate a single thread's run.
+    #[serde(default, rename = "threadId")]
+    pub(crate) thread_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -158,6 +228,98 @@ pub(crate) struct WorkspaceGroup {
     pub(crate) name: String,
     #[serde(default, rename = "sortOrder")]
     pub(crate) sort_order: Option<u32>,
+    /// Sidebar label color, stored as `#rrggbb`.
+    #[serde(default)]
+    pub(crate) color: Option<String>,
+}
+
+impl WorkspaceGroup {
+    /// Lowercases `color` and expands `#rgb` shorthand; a blank color clears it.
+    pub(crate) fn normalize_color(&mut self) -> Result<(), String> {
+        let Some(color) = self.color.as_deref().map(str::trim) else {
+            return Ok(());
+        };
+        if color.is_empty() {
+            self.color = None;
+            return Ok(());
+        }
+        let invalid = || format!("Invalid color for group \"{}\": {color}", self.name);
+        let hex = color
+            .strip_prefix('#')
+            .filter(|hex| hex.chars().all(|ch| ch.is_ascii_hexdigit()))
+            .ok_or_else(invalid)?;
+        let hex = match hex.len() {
+            6 => hex.to_ascii_lowercase(),
+            3 => hex
+                .chars()
+                .flat_map(|ch| [ch, ch])
+                .collect::<String>()
+                .to_ascii_lowercase(),
+            _ => return Err(invalid()),
+        };
+        self.color = Some(format!("#{hex}"));
+        Ok(())
+    }
+}
+
+/// Ids of the workspaces in a group. Worktrees follow their parent's group.
+pub(crate) fn workspace_ids_in_group(
+    workspaces: &HashMap<String, WorkspaceEntry>,
+    group_id: &str,
+) -> Vec<String> {
+    let group_of = |entry: &WorkspaceEntry| {
+        let owner = entry
+            .parent_id
+            .as_ref()
+            .and_then(|parent_id| workspaces.get(parent_id))
+            .unwrap_or(entry);
+        owner.settings.group_id.clone()
+    };
+    let mut ids: Vec<String> = workspaces
+        .values()
+        .filter(|entry| group_of(entry).as_deref() == Some(group_id))
+        .map(|entry| entry.id.clone())
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ApprovalDecision {
+    Accept,
+    Decline,
+}
+
+/// Answers matching approval requests without asking. Every matcher that is
+/// set must match; rules are tried in order and the first match wins.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct ApprovalRule {
+    pub(crate) id: String,
+    #[serde(default)]
+    pub(crate) name: String,
+    pub(crate) decision: ApprovalDecision,
+    #[serde(default = "default_approval_rule_enabled")]
+    pub(crate) enabled: bool,
+    /// Request kind from the method name, e.g. `commandExecution` or `fileChange`.
+    #[serde(default)]
+    pub(crate) tool: Option<String>,
+    /// Matches the command itself or the command followed by arguments.
+    #[serde(default, rename = "commandPrefix")]
+    pub(crate) command_prefix: Option<String>,
+    /// Glob (`*`, `**`, `?`) over the paths the request touches.
+    #[serde(default, rename = "pathGlob")]
+    pub(crate) path_glob: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct PromptTemplate {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    /// Prompt text with `{name}` placeholders; `{{` and `}}` are literal braces.
+    pub(crate) body: String,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -170,12 +332,92 @@ pub(crate) struct WorkspaceSettings {
     pub(crate) group_id: Option<String>,
     #[serde(default, rename = "gitRoot")]
     pub(crate) git_root: Option<String>,
+    #[serde(default, rename = "notificationsMuted")]
+    pub(crate) notifications_muted: bool,
+    /// Runs the app-server on another machine over SSH instead of locally.
+    #[serde(default)]
+    pub(crate) remote: Option<RemoteWorkspace>,
+    /// MCP servers whose resources can be attached in the composer.
+    #[serde(default, rename = "mcpServers")]
+    pub(crate) mcp_servers: Vec<McpServerConfig>,
+}
+
+/// A stdio MCP server CodexMonitor starts itself, independent of the
+/// backend's own MCP configuration.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct McpServerConfig {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) command: String,
+    #[serde(default)]
+    pub(crate) args: Vec<String>,
+    #[serde(default)]
+    pub(crate) env: HashMap<String, String>,
+}
+
+/// An app-server started with `ssh <host> codex app-server`. `path` is the
+/// workspace directory on the remote host; paths in messages are mapped
+/// between it and the workspace's local path.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct RemoteWorkspace {
+    /// SSH destination, e.g. `dev-box` or `me@10.0.0.5`.
+    pub(crate) host: String,
+    pub(crate) path: String,
+    /// Extra `ssh` flags placed before the destination, e.g. `-p 2222`.
+    #[serde(default, rename = "sshArgs")]
+    pub(crate) ssh_args: Vec<String>,
+}
+
+impl RemoteWorkspace {
+    /// Trims fields and rejects values `ssh` would misread as options or
+    /// that could not name a remote directory.
+    pub(crate) fn normalize(&mut self) -> Result<(), String> {
+        self.host = self.host.trim().to_string();
+        self.path = self.path.trim().to_string();
+        if self.host.is_empty()
+            || self.host.starts_with('-')
+            || self
+                .host
+                .chars()
+                .any(|ch| ch.is_whitespace() || ch.is_control())
+        {
+            return Err(format!("Invalid SSH host \"{}\".", self.host));
+        }
+        if !self.path.starts_with('/') || self.path.chars().any(char::is_control) {
+            return Err(format!(
+                "Remote path \"{}\" must be an absolute path.",
+                self.path
+            ));
+        }
+        self.ssh_args = self
+            .ssh_args
+            .iter()
+            .map(|arg| arg.trim().to_string())
+            .filter(|arg| !arg.is_empty())
+            .collect();
+        if let Some(arg) = self
+            .ssh_args
+            .iter()
+            .find(|arg| arg.chars().any(char::is_control))
+        {
+            return Err(format!("SSH argument {arg:?} contains control characters."));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct AppSettings {
     #[serde(default, rename = "codexBin")]
     pub(crate) codex_bin: Option<String>,
+    /// Extra flags appended to `codex app-server`, e.g. `--profile` or
+    /// `-c key=value` overrides.
+    #[serde(default, rename = "codexArgs")]
+    pub(crate) codex_args: Vec<String>,
+    /// Preferred response verbosity (`low`, `medium`, `high`) sent to the
+    /// app-server in `initialize`.
+    #[serde(default)]
+    pub(crate) verbosity: Option<String>,
     #[serde(default, rename = "backendMode")]
     pub(crate) backend_mode: BackendMode,
     #[serde(default = "default_remote_backend_host", rename = "remoteBackendHost")]
@@ -191,6 +433,11 @@ pub(crate) struct AppSettings {
         rename = "notificationSoundsEnabled"
     )]
     pub(crate) notification_sounds_enabled: bool,
+    #[serde(
+        default = "default_system_notifications_enabled",
+        rename = "systemNotificationsEnabled"
+    )]
+    pub(crate) system_notifications_enabled: bool,
     #[serde(
         default = "default_experimental_collab_enabled",
         rename = "experimentalCollabEnabled"
@@ -222,6 +469,20 @@ pub(crate) struct AppSettings {
     pub(crate) dictation_hold_key: String,
     #[serde(default = "default_workspace_groups", rename = "workspaceGroups")]
     pub(crate) workspace_groups: Vec<WorkspaceGroup>,
+    #[serde(
+        default = "default_request_timeout_secs",
+        rename = "requestTimeoutSecs"
+    )]
+    pub(crate) request_timeout_secs: u64,
+    #[serde(
+        default = "default_max_concurrent_requests",
+        rename = "maxConcurrentRequests"
+    )]
+    pub(crate) max_concurrent_requests: u32,
+    #[serde(default, rename = "promptTemplates")]
+    pub(crate) prompt_templates: Vec<PromptTemplate>,
+    #[serde(default, rename = "approvalRules")]
+    pub(crate) approval_rules: Vec<ApprovalRule>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -249,10 +510,18 @@ fn default_ui_scale() -> f64 {
     1.0
 }
 
+fn default_approval_rule_enabled() -> bool {
+    true
+}
+
 fn default_notification_sounds_enabled() -> bool {
     true
 }
 
+fn default_system_notifications_enabled() -> bool {
+    true
+}
+
 fn default_experimental_collab_enabled() -> bool {
     false
 }
@@ -281,16 +550,27 @@ fn default_workspace_groups() -> Vec<WorkspaceGroup> {
     Vec::new()
 }
 
+fn default_request_timeout_secs() -> u64 {
+    DEFAULT_REQUEST_TIMEOUT_SECS
+}
+
+fn default_max_concurrent_requests() -> u32 {
+    8
+}
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             codex_bin: None,
+            codex_args: Vec::new(),
+            verbosity: None,
             backend_mode: BackendMode::Local,
             remote_backend_host: default_remote_backend_host(),
             remote_backend_token: None,
             default_access_mode: "current".to_string(),
             ui_scale: 1.0,
             notification_sounds_enabled: true,
+            system_notifications_enabled: true,
             experimental_collab_enabled: false,
             experimental_steer_enabled: false,
             experimental_unified_exec_enabled: false,
@@ -299,13 +579,21 @@ impl Default for AppSettings {
             dictation_preferred_language: None,
             dictation_hold_key: default_dictation_hold_key(),
             workspace_groups: default_workspace_groups(),
+            request_timeout_secs: default_request_timeout_secs(),
+            max_concurrent_requests: default_max_concurrent_requests(),
+            prompt_templates: Vec::new(),
+            approval_rules: Vec::new(),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{AppSettings, BackendMode, WorkspaceEntry, WorkspaceKind, WorkspaceSettings};
+    use super::{
+        workspace_ids_in_group, AppSettings, BackendMode, RemoteWorkspace, WorkspaceEntry,
+        WorkspaceGroup, WorkspaceKind, WorkspaceSettings,
+    };
+    use std::collections::HashMap;
 
     #[test]
     fn app_settings_defaults_from_empty_json() {
@@ -317,12 +605,15 @@ mod tests {
         assert_eq!(settings.default_access_mode, "current");
         assert!((settings.ui_scale - 1.0).abs() < f64::EPSILON);
         assert!(settings.notification_sounds_enabled);
+        assert!(settings.system_notifications_enabled);
         assert!(!settings.experimental_steer_enabled);
         assert!(!settings.dictation_enabled);
         assert_eq!(settings.dictation_model_id, "base");
         assert!(settings.dictation_preferred_language.is_none());
         assert_eq!(settings.dictation_hold_key, "alt");
         assert!(settings.workspace_groups.is_empty());
+        assert_eq!(settings.request_timeout_secs, 120);
+        assert_eq!(settings.max_concurrent_requests, 8);
     }
 
     #[test]
@@ -345,5 +636,69 @@ mod tests {
         assert!(settings.sort_order.is_none());
         assert!(settings.group_id.is_none());
         assert!(settings.git_root.is_none());
+        assert!(!settings.notifications_muted);
+    }
+
+    #[test]
+    fn workspace_group_color_is_normalized() {
+        let mut group: WorkspaceGroup =
+            serde_json::from_str(r##"{"id":"g","name":"Infra","color":" #A1b "}"##)
+                .expect("group deserialize");
+        group.normalize_color().expect("valid color");
+        assert_eq!(group.color.as_deref(), Some("#aa11bb"));
+
+        group.color = Some("".to_string());
+        group.normalize_color().expect("blank color");
+        assert!(group.color.is_none());
+
+        group.color = Some("red".to_string());
+        assert!(group.normalize_color().is_err());
+    }
+
+    #[test]
+    fn remote_workspace_is_normalized() {
+        let mut remote = RemoteWorkspace {
+            host: " me@dev-box ".to_string(),
+            path: "/home/me/app ".to_string(),
+            ssh_args: vec![" -p".to_string(), "".to_string(), "2222".to_string()],
+        };
+        remote.normalize().expect("valid remote");
+        assert_eq!(remote.host, "me@dev-box");
+        assert_eq!(remote.path, "/home/me/app");
+        assert_eq!(remote.ssh_args, vec!["-p", "2222"]);
+
+        remote.host = "-oProxyCommand=evil".to_string();
+        assert!(remote.normalize().is_err());
+        remote.host = "dev-box".to_string();
+        remote.path = "app".to_string();
+        assert!(remote.normalize().is_err());
+    }
+
+    #[test]
+    fn workspace_ids_in_group_includes_worktrees_of_members() {
+        let entry = |id: &str, parent: Option<&str>, group: Option<&str>| {
+            let mut entry: WorkspaceEntry = serde_json::from_value(serde_json::json!({
+                "id": id, "name": id, "path": "/tmp", "codex_bin": null, "parentId": parent
+            }))
+            .expect("workspace deserialize");
+            entry.settings.group_id = group.map(str::to_string);
+            entry
+        };
+        let workspaces: HashMap<String, WorkspaceEntry> = [
+            entry("api", None, Some("g1")),
+            entry("api-wt", Some("api"), None),
+            entry("web", None, Some("g2")),
+            entry("docs", None, None),
+        ]
+        .into_iter()
+        .map(|entry| (entry.id.clone(), entry))
+        .collect();
+
+        assert_eq!(
+            workspace_ids_in_group(&workspaces, "g1"),
+            vec!["api", "api-wt"]
+        );
+        assert_eq!(workspace_ids_in_group(&workspaces, "g2"), vec!["web"]);
+        assert!(workspace_ids_in_group(&workspaces, "missing").is_empty());
     }
 }
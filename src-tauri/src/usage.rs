@@ -0,0 +1,411 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::backend::events::AppServerEvent;
+use crate::state::AppState;
+use crate::storage::write_json_file;
+use crate::utils::civil_from_days;
+
+const UNKNOWN_MODEL: &str = "unknown";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub(crate) struct UsageTotals {
+    #[serde(default, rename = "inputTokens")]
+    pub(crate) input_tokens: u64,
+    #[serde(default, rename = "cachedInputTokens")]
+    pub(crate) cached_input_tokens: u64,
+    #[serde(default, rename = "outputTokens")]
+    pub(crate) output_tokens: u64,
+    #[serde(default, rename = "reasoningOutputTokens")]
+    pub(crate) reasoning_output_tokens: u64,
+    #[serde(default, rename = "totalTokens")]
+    pub(crate) total_tokens: u64,
+    #[serde(default)]
+    pub(crate) requests: u64,
+}
+
+impl UsageTotals {
+    fn from_breakdown(value: &Value) -> Self {
+        let read = |camel: &str, snake: &str| {
+            value
+                .get(camel)
+                .or_else(|| value.get(snake))
+                .and_then(Value::as_u64)
+                .unwrap_or(0)
+        };
+        Self {
+            input_tokens: read("inputTokens", "input_tokens"),
+            cached_input_tokens: read("cachedInputTokens", "cached_input_tokens"),
+            output_tokens: read("outputTokens", "output_tokens"),
+            reasoning_output_tokens: read("reasoningOutputTokens", "reasoning_output_tokens"),
+            total_tokens: read("totalTokens", "total_tokens"),
+            requests: 1,
+        }
+    }
+
+    fn add(&mut self, other: &UsageTotals) {
+        self.input_tokens += other.input_tokens;
+        self.cached_input_tokens += other.cached_input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.reasoning_output_tokens += other.reasoning_output_tokens;
+        self.total_tokens += other.total_tokens;
+        self.requests += other.requests;
+    }
+}
+
+/// Token usage rolled up per UTC day, workspace, and model.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct UsageRecord {
+    pub(crate) day: String,
+    #[serde(rename = "workspaceId")]
+    pub(crate) workspace_id: String,
+    pub(crate) model: String,
+    #[serde(flatten)]
+    pub(crate) totals: UsageTotals,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct UsageBucket {
+    pub(crate) key: String,
+    #[serde(flatten)]
+    pub(crate) totals: UsageTotals,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct UsageSummary {
+    pub(crate) range: String,
+    pub(crate) since: Option<String>,
+    pub(crate) totals: UsageTotals,
+    #[serde(rename = "byDay")]
+    pub(crate) by_day: Vec<UsageBucket>,
+    #[serde(rename = "byWorkspace")]
+    pub(crate) by_workspace: Vec<UsageBucket>,
+    #[serde(rename = "byModel")]
+    pub(crate) by_model: Vec<UsageBucket>,
+}
+
+#[derive(Default)]
+pub(crate) struct UsageStore {
+    path: PathBuf,
+    records: Vec<UsageRecord>,
+    thread_models: HashMap<String, String>,
+    /// Last cumulative total seen per thread, so replays and resumed
+    /// threads don't count the same request twice.
+    thread_totals: HashMap<String, u64>,
+    /// Records changed since the last write to `usage.json`.
+    dirty: bool,
+}
+
+fn civil_date(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn today_index() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64 / 86_400)
+        .unwrap_or(0)
+}
+
+/// First day included by `range` (`today`, `7d`, `30d`, `90d`, or `all`).
+fn range_start(range: &str, today: i64) -> Result<Option<String>, String> {
+    let days_back = match range {
+        "all" => return Ok(None),
+        "today" => 0,
+        "7d" => 6,
+        "30d" => 29,
+        "90d" => 89,
+        other => return Err(format!("unsupported usage range: {other}")),
+    };
+    Ok(Some(civil_date(today - days_back)))
+}
+
+/// The `(thread id, model)` named by a `thread/started` or `turn/started`
+/// notification, when it carries one.
+fn event_model(message: &Value) -> Option<(String, String)> {
+    let method = message.get("method").and_then(Value::as_str)?;
+    let params = message.get("params")?;
+    let read = |value: Option<&Value>, camel: &str, snake: &str| {
+        value
+            .and_then(|value| value.get(camel).or_else(|| value.get(snake)))
+            .and_then(Value::as_str)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string)
+    };
+    let (inner, thread_id) = match method {
+        "thread/started" => {
+            let thread = params.get("thread");
+            (thread, read(thread, "id", "id"))
+        }
+        "turn/started" => {
+            let turn = params.get("turn");
+            (
+                turn,
+                read(Some(params), "threadId", "thread_id")
+                    .or_else(|| read(turn, "threadId", "thread_id")),
+            )
+        }
+        _ => return None,
+    };
+    let model = read(inner, "model", "model").or_else(|| read(Some(params), "model", "model"))?;
+    Some((thread_id?, model))
+}
+
+fn sorted_buckets(map: BTreeMap<String, UsageTotals>) -> Vec<UsageBucket> {
+    map.into_iter()
+        .map(|(key, totals)| UsageBucket { key, totals })
+        .collect()
+}
+
+impl UsageStore {
+    pub(crate) fn load(path: PathBuf) -> Self {
+        let records = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str::<Value>(&data).ok())
+            .and_then(|value| value.get("records").cloned())
+            .and_then(|records| serde_json::from_value(records).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            records,
+            ..Self::default()
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        write_json_file(
+            &self.path,
+            &json!({ "version": 1, "records": self.records }),
+        )
+    }
+
+    /// Writes `usage.json` if anything was recorded since the last write.
+    pub(crate) fn flush(&mut self) -> Result<(), String> {
+        if !self.dirty {
+            return Ok(());
+        }
+        self.save()?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    pub(crate) fn note_thread_model(&mut self, thread_id: &str, model: &str) {
+        self.thread_models
+            .insert(thread_id.to_string(), model.to_string());
+    }
+
+    /// Folds a `thread/tokenUsage/updated` notification into today's bucket.
+    /// Returns whether anything was recorded.
+    fn record(&mut self, workspace_id: &str, message: &Value, day: String) -> bool {
+        if message.get("method").and_then(Value::as_str) != Some("thread/tokenUsage/updated") {
+            return false;
+        }
+        let Some(params) = message.get("params") else {
+            return false;
+        };
+        let thread_id = params
+            .get("threadId")
+            .or_else(|| params.get("thread_id"))
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        let Some(usage) = params
+            .get("tokenUsage")
+            .or_else(|| params.get("token_usage"))
+        else {
+            return false;
+        };
+        let Some(last) = usage.get("last") else {
+            return false;
+        };
+        let cumulative = usage
+            .get("total")
+            .map(UsageTotals::from_breakdown)
+            .map(|total| total.total_tokens);
+        if let Some(cumulative) = cumulative.filter(|_| !thread_id.is_empty()) {
+            let seen = self.thread_totals.entry(thread_id.to_string()).or_insert(0);
+            if cumulative <= *seen {
+                return false;
+            }
+            *seen = cumulative;
+        }
+
+        let delta = UsageTotals::from_breakdown(last);
+        let model = self
+            .thread_models
+            .get(thread_id)
+            .cloned()
+            .unwrap_or_else(|| UNKNOWN_MODEL.to_string());
+        match self.records.iter_mut().find(|record| {
+            record.day == day && record.workspace_id == workspace_id && record.model == model
+        }) {
+            Some(record) => record.totals.add(&delta),
+            None => self.records.push(UsageRecord {
+                day,
+                workspace_id: workspace_id.to_string(),
+                model,
+                totals: delta,
+            }),
+        }
+        self.dirty = true;
+        true
+    }
+
+    fn summary(&self, range: &str, since: Option<String>) -> UsageSummary {
+        let mut totals = UsageTotals::default();
+        let mut by_day = BTreeMap::new();
+        let mut by_workspace = BTreeMap::new();
+        let mut by_model = BTreeMap::new();
+        for record in &self.records {
+            if since
+                .as_deref()
+                .is_some_and(|since| record.day.as_str() < since)
+            {
+                continue;
+            }
+            totals.add(&record.totals);
+            by_day
+                .entry(record.day.clone())
+                .or_insert_with(UsageTotals::default)
+                .add(&record.totals);
+            by_workspace
+                .entry(record.workspace_id.clone())
+                .or_insert_with(UsageTotals::default)
+                .add(&record.totals);
+            by_model
+                .entry(record.model.clone())
+                .or_insert_with(UsageTotals::default)
+                .add(&record.totals);
+        }
+        UsageSummary {
+            range: range.to_string(),
+            since,
+            totals,
+            by_day: sorted_buckets(by_day),
+            by_workspace: sorted_buckets(by_workspace),
+            by_model: sorted_buckets(by_model),
+        }
+    }
+}
+
+/// Token usage is folded in as it arrives but only written out when a turn
+/// ends, since `thread/tokenUsage/updated` fires several times per turn.
+pub(crate) fn record_event(app: &AppHandle, event: &AppServerEvent) {
+    let method = event.message.get("method").and_then(Value::as_str);
+    if !matches!(
+        method,
+        Some("thread/tokenUsage/updated" | "thread/started" | "turn/started" | "turn/completed")
+    ) {
+        return;
+    }
+    let app = app.clone();
+    let workspace_id = event.workspace_id.clone();
+    let message = event.message.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let mut usage = state.usage.lock().await;
+        if let Some((thread_id, model)) = event_model(&message) {
+            usage.note_thread_model(&thread_id, &model);
+        }
+        usage.record(&workspace_id, &message, civil_date(today_index()));
+        if message.get("method").and_then(Value::as_str) == Some("turn/completed") {
+            let _ = usage.flush();
+        }
+    });
+}
+
+#[tauri::command]
+pub(crate) async fn usage_summary(
+    range: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<UsageSummary, String> {
+    let range = range.unwrap_or_else(|| "30d".to_string());
+    let since = range_start(&range, today_index())?;
+    Ok(state.usage.lock().await.summary(&range, since))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{civil_date, event_model, range_start, UsageStore};
+    use serde_json::json;
+
+    fn usage_event(thread_id: &str, total: u64, last: u64) -> serde_json::Value {
+        json!({
+            "method": "thread/tokenUsage/updated",
+            "params": {
+                "threadId": thread_id,
+                "tokenUsage": {
+                    "total": { "totalTokens": total },
+                    "last": { "inputTokens": last - 10, "outputTokens": 10, "totalTokens": last }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn civil_date_matches_known_days() {
+        assert_eq!(civil_date(0), "1970-01-01");
+        assert_eq!(civil_date(19_782), "2024-02-29");
+        assert_eq!(
+            range_start("7d", 19_782).unwrap().as_deref(),
+            Some("2024-02-23")
+        );
+        assert!(range_start("forever", 0).is_err());
+    }
+
+    #[test]
+    fn record_aggregates_and_skips_repeated_totals() {
+        let mut store = UsageStore::default();
+        store.note_thread_model("t1", "gpt-5");
+        assert!(store.record("ws", &usage_event("t1", 100, 100), "2024-02-28".into()));
+        assert!(store.record("ws", &usage_event("t1", 150, 50), "2024-02-29".into()));
+        assert!(store.record("ws", &usage_event("t1", 200, 50), "2024-02-29".into()));
+        assert!(!store.record("ws", &usage_event("t1", 200, 50), "2024-02-29".into()));
+        assert!(store.record("ws", &usage_event("t2", 30, 30), "2024-02-29".into()));
+
+        let summary = store.summary("today", Some("2024-02-29".into()));
+        assert_eq!(summary.totals.total_tokens, 130);
+        assert_eq!(summary.totals.requests, 3);
+        assert_eq!(summary.by_day.len(), 1);
+        let models: Vec<_> = summary
+            .by_model
+            .iter()
+            .map(|bucket| bucket.key.as_str())
+            .collect();
+        assert_eq!(models, vec!["gpt-5", "unknown"]);
+
+        let all = store.summary("all", None);
+        assert_eq!(all.totals.total_tokens, 230);
+        assert_eq!(all.by_day.len(), 2);
+    }
+
+    #[test]
+    fn event_model_reads_thread_and_turn_notifications() {
+        let started = json!({
+            "method": "thread/started",
+            "params": { "thread": { "id": "t1", "model": "gpt-5" } }
+        });
+        assert_eq!(
+            event_model(&started),
+            Some(("t1".to_string(), "gpt-5".to_string()))
+        );
+        let turn = json!({
+            "method": "turn/started",
+            "params": { "threadId": "t2", "turn": { "id": "turn-1", "model": "o3" } }
+        });
+        assert_eq!(
+            event_model(&turn),
+            Some(("t2".to_string(), "o3".to_string()))
+        );
+        let no_model = json!({
+            "method": "turn/started",
+            "params": { "threadId": "t2", "turn": { "id": "turn-2" } }
+        });
+        assert_eq!(event_model(&no_model), None);
+    }
+}
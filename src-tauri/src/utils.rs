@@ -1,7 +1,75 @@
+use serde_json::Value;
+
 pub(crate) fn normalize_git_path(path: &str) -> String {
     path.replace('\\', "/")
 }
 
+/// Converts days since the Unix epoch into a UTC `(year, month, day)`.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// Replaces anything but ASCII alphanumerics, `-` and `_` so an id can be
+/// used as a file name.
+pub(crate) fn file_safe_name(id: &str) -> String {
+    id.chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Thread id and error (if any) for a message that ends a turn: either
+/// `turn/completed` or a terminal `error` the server will not retry.
+pub(crate) fn turn_outcome(message: &Value) -> Option<(String, Option<String>)> {
+    let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+    let params = message.get("params");
+    match method {
+        "turn/completed" => {
+            let thread_id = params
+                .and_then(|params| params.get("turn"))
+                .and_then(|turn| turn.get("threadId").or_else(|| turn.get("thread_id")))
+                .or_else(|| params.and_then(|params| params.get("threadId")))
+                .and_then(Value::as_str);
+            thread_id.map(|thread_id| (thread_id.to_string(), None))
+        }
+        "error" => {
+            let params = params.cloned().unwrap_or(Value::Null);
+            let will_retry = params
+                .get("willRetry")
+                .or_else(|| params.get("will_retry"))
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            let thread_id = params
+                .get("threadId")
+                .or_else(|| params.get("thread_id"))
+                .and_then(Value::as_str);
+            let message = params
+                .get("error")
+                .and_then(|error| error.get("message"))
+                .and_then(Value::as_str)
+                .unwrap_or("turn failed")
+                .to_string();
+            thread_id
+                .filter(|_| !will_retry)
+                .map(|thread_id| (thread_id.to_string(), Some(message)))
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::normalize_git_path;
@@ -1,5 +1,5 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 use ignore::WalkBuilder;
 use tauri::{AppHandle, Manager, State};
@@ -7,10 +7,14 @@ use tokio::process::Command;
 use uuid::Uuid;
 
 use crate::codex::spawn_workspace_session;
+use crate::file_index::search_files;
+use crate::git_utils::list_git_roots;
 use crate::state::AppState;
 use crate::storage::write_workspaces;
+use crate::turn_history::fail_running_turns;
 use crate::types::{
-    WorkspaceEntry, WorkspaceInfo, WorkspaceKind, WorkspaceSettings, WorktreeInfo,
+    DiscoveredWorkspace, RemoteWorkspace, WorkspaceEntry, WorkspaceInfo, WorkspaceKind,
+    WorkspaceSettings, WorktreeInfo,
 };
 use crate::utils::normalize_git_path;
 
@@ -37,6 +41,34 @@ fn should_skip_dir(name: &str) -> bool {
     )
 }
 
+const TOOLCHAIN_MARKERS: &[(&str, &str)] = &[
+    ("Cargo.toml", "rust"),
+    ("package.json", "node"),
+    ("deno.json", "deno"),
+    ("go.mod", "go"),
+    ("pyproject.toml", "python"),
+    ("requirements.txt", "python"),
+    ("setup.py", "python"),
+    ("Gemfile", "ruby"),
+    ("pom.xml", "java"),
+    ("build.gradle", "java"),
+    ("build.gradle.kts", "kotlin"),
+    ("Package.swift", "swift"),
+    ("composer.json", "php"),
+    ("mix.exs", "elixir"),
+    ("CMakeLists.txt", "cpp"),
+];
+
+fn detect_workspace_languages(path: &Path) -> Vec<String> {
+    let mut languages: Vec<String> = Vec::new();
+    for (marker, language) in TOOLCHAIN_MARKERS {
+        if path.join(marker).is_file() && !languages.iter().any(|value| value == language) {
+            languages.push(language.to_string());
+        }
+    }
+    languages
+}
+
 fn sanitize_worktree_name(branch: &str) -> String {
     let mut result = String::new();
     for ch in branch.chars() {
@@ -54,6 +86,15 @@ fn sanitize_worktree_name(branch: &str) -> String {
     }
 }
 
+fn thread_branch_name(thread_id: &str) -> String {
+    let short: String = sanitize_worktree_name(thread_id)
+        .trim_start_matches("thread-")
+        .chars()
+        .take(12)
+        .collect();
+    format!("codex/thread-{short}")
+}
+
 fn list_workspace_files_inner(root: &PathBuf, max_files: usize) -> Vec<String> {
     let mut results = Vec::new();
     let walker = WalkBuilder::new(root)
@@ -112,8 +153,11 @@ fn sort_workspaces(list: &mut Vec<WorkspaceInfo>) {
 fn apply_workspace_settings_update(
     workspaces: &mut HashMap<String, WorkspaceEntry>,
     id: &str,
-    settings: WorkspaceSettings,
+    mut settings: WorkspaceSettings,
 ) -> Result<WorkspaceEntry, String> {
+    if let Some(remote) = settings.remote.as_mut() {
+        remote.normalize()?;
+    }
     match workspaces.get_mut(id) {
         Some(entry) => {
             entry.settings = settings.clone();
@@ -201,9 +245,15 @@ pub(crate) async fn list_workspaces(
 pub(crate) async fn add_workspace(
     path: String,
     codex_bin: Option<String>,
+    remote: Option<RemoteWorkspace>,
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<WorkspaceInfo, String> {
+    let mut settings = WorkspaceSettings::default();
+    if let Some(mut remote) = remote {
+        remote.normalize()?;
+        settings.remote = Some(remote);
+    }
     let name = PathBuf::from(&path)
         .file_name()
         .and_then(|s| s.to_str())
@@ -217,7 +267,7 @@ pub(crate) async fn add_workspace(
         kind: WorkspaceKind::Main,
         parent_id: None,
         worktree: None,
-        settings: WorkspaceSettings::default(),
+        settings,
     };
 
     let default_bin = {
@@ -251,12 +301,66 @@ pub(crate) async fn add_workspace(
     })
 }
 
+#[tauri::command]
+pub(crate) async fn discover_workspaces(
+    root: String,
+    depth: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<DiscoveredWorkspace>, String> {
+    let root_path = PathBuf::from(&root);
+    if !root_path.is_dir() {
+        return Err(format!("Folder not found: {root}"));
+    }
+    let depth = depth.unwrap_or(3).clamp(1, 6);
+    let known: HashSet<String> = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces.values().map(|entry| entry.path.clone()).collect()
+    };
+
+    let mut candidates = Vec::new();
+    if root_path.join(".git").exists() {
+        candidates.push(root_path.clone());
+    }
+    candidates.extend(
+        list_git_roots(&root_path, depth, 500)
+            .into_iter()
+            .map(|relative| root_path.join(relative)),
+    );
+
+    Ok(candidates
+        .into_iter()
+        .map(|path| {
+            let path_string = path.to_string_lossy().to_string();
+            DiscoveredWorkspace {
+                name: path
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("Workspace")
+                    .to_string(),
+                languages: detect_workspace_languages(&path),
+                already_added: known.contains(&path_string),
+                path: path_string,
+            }
+        })
+        .collect())
+}
+
 #[tauri::command]
 pub(crate) async fn add_worktree(
     parent_id: String,
     branch: String,
     state: State<'_, AppState>,
     app: AppHandle,
+) -> Result<WorkspaceInfo, String> {
+    create_worktree_workspace(&parent_id, &branch, None, &state, app).await
+}
+
+async fn create_worktree_workspace(
+    parent_id: &str,
+    branch: &str,
+    thread_id: Option<String>,
+    state: &AppState,
+    app: AppHandle,
 ) -> Result<WorkspaceInfo, String> {
     let branch = branch.trim();
     if branch.is_empty() {
@@ -266,7 +370,7 @@ pub(crate) async fn add_worktree(
     let parent_entry = {
         let workspaces = state.workspaces.lock().await;
         workspaces
-            .get(&parent_id)
+            .get(parent_id)
             .cloned()
             .ok_or("parent workspace not found")?
     };
@@ -312,6 +416,7 @@ pub(crate) async fn add_worktree(
         parent_id: Some(parent_entry.id.clone()),
         worktree: Some(WorktreeInfo {
             branch: branch.to_string(),
+            thread_id,
         }),
         settings: WorkspaceSettings::default(),
     };
@@ -347,6 +452,27 @@ pub(crate) async fn add_worktree(
     })
 }
 
+/// Creates a worktree workspace on its own branch for one thread, so the
+/// agent's session runs there instead of in the user's checkout.
+#[tauri::command]
+pub(crate) async fn create_thread_worktree(
+    parent_id: String,
+    thread_id: String,
+    branch: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorkspaceInfo, String> {
+    let thread_id = thread_id.trim().to_string();
+    if thread_id.is_empty() {
+        return Err("Thread id is required.".to_string());
+    }
+    let branch = branch
+        .map(|branch| branch.trim().to_string())
+        .filter(|branch| !branch.is_empty())
+        .unwrap_or_else(|| thread_branch_name(&thread_id));
+    create_worktree_workspace(&parent_id, &branch, Some(thread_id), &state, app).await
+}
+
 #[tauri::command]
 pub(crate) async fn remove_workspace(
     id: String,
@@ -372,9 +498,9 @@ pub(crate) async fn remove_workspace(
     let parent_path = PathBuf::from(&entry.path);
     for child in &child_worktrees {
         if let Some(session) = state.sessions.lock().await.remove(&child.id) {
-            let mut child_process = session.child.lock().await;
-            let _ = child_process.kill().await;
+            session.terminate().await;
         }
+        fail_running_turns(&state, Some(&child.id)).await;
         let child_path = PathBuf::from(&child.path);
         if child_path.exists() {
             run_git_command(
@@ -387,9 +513,9 @@ pub(crate) async fn remove_workspace(
     let _ = run_git_command(&parent_path, &["worktree", "prune", "--expire", "now"]).await;
 
     if let Some(session) = state.sessions.lock().await.remove(&id) {
-        let mut child = session.child.lock().await;
-        let _ = child.kill().await;
+        session.terminate().await;
     }
+    fail_running_turns(&state, Some(&id)).await;
 
     {
         let mut workspaces = state.workspaces.lock().await;
@@ -404,35 +530,38 @@ pub(crate) async fn remove_workspace(
     Ok(())
 }
 
-#[tauri::command]
-pub(crate) async fn remove_worktree(
-    id: String,
-    state: State<'_, AppState>,
-) -> Result<(), String> {
-    let (entry, parent) = {
-        let workspaces = state.workspaces.lock().await;
-        let entry = workspaces
-            .get(&id)
-            .cloned()
-            .ok_or("workspace not found")?;
-        if !entry.kind.is_worktree() {
-            return Err("Not a worktree workspace.".to_string());
-        }
-        let parent_id = entry
-            .parent_id
-            .clone()
-            .ok_or("worktree parent not found")?;
-        let parent = workspaces
-            .get(&parent_id)
-            .cloned()
-            .ok_or("worktree parent not found")?;
-        (entry, parent)
-    };
+async fn worktree_with_parent(
+    state: &AppState,
+    id: &str,
+) -> Result<(WorkspaceEntry, WorkspaceEntry), String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces.get(id).cloned().ok_or("workspace not found")?;
+    if !entry.kind.is_worktree() {
+        return Err("Not a worktree workspace.".to_string());
+    }
+    let parent_id = entry
+        .parent_id
+        .clone()
+        .ok_or("worktree parent not found")?;
+    let parent = workspaces
+        .get(&parent_id)
+        .cloned()
+        .ok_or("worktree parent not found")?;
+    Ok((entry, parent))
+}
 
+/// Stops the worktree's session, removes its checkout, and forgets the
+/// workspace. `delete_branch` takes `-d` or `-D` to also drop the branch.
+async fn teardown_worktree(
+    state: &AppState,
+    entry: &WorkspaceEntry,
+    parent: &WorkspaceEntry,
+    delete_branch: Option<&str>,
+) -> Result<(), String> {
     if let Some(session) = state.sessions.lock().await.remove(&entry.id) {
-        let mut child = session.child.lock().await;
-        let _ = child.kill().await;
+        session.terminate().await;
     }
+    fail_running_turns(state, Some(&entry.id)).await;
 
     let parent_path = PathBuf::from(&parent.path);
     let entry_path = PathBuf::from(&entry.path);
@@ -445,6 +574,10 @@ pub(crate) async fn remove_worktree(
     }
     let _ = run_git_command(&parent_path, &["worktree", "prune", "--expire", "now"]).await;
 
+    if let (Some(flag), Some(worktree)) = (delete_branch, entry.worktree.as_ref()) {
+        run_git_command(&parent_path, &["branch", flag, &worktree.branch]).await?;
+    }
+
     {
         let mut workspaces = state.workspaces.lock().await;
         workspaces.remove(&entry.id);
@@ -455,6 +588,64 @@ pub(crate) async fn remove_worktree(
     Ok(())
 }
 
+#[tauri::command]
+pub(crate) async fn remove_worktree(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let (entry, parent) = worktree_with_parent(&state, &id).await?;
+    teardown_worktree(&state, &entry, &parent, None).await
+}
+
+/// Commits anything left in a thread worktree, merges its branch into the
+/// parent checkout, then removes the worktree and branch.
+#[tauri::command]
+pub(crate) async fn merge_thread_worktree(
+    id: String,
+    message: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let (entry, parent) = worktree_with_parent(&state, &id).await?;
+    let worktree = entry.worktree.clone().ok_or("worktree branch not found")?;
+
+    let entry_path = PathBuf::from(&entry.path);
+    let pending = run_git_command(&entry_path, &["status", "--porcelain"]).await?;
+    if !pending.is_empty() {
+        let message = message
+            .map(|message| message.trim().to_string())
+            .filter(|message| !message.is_empty())
+            .unwrap_or_else(|| match worktree.thread_id.as_deref() {
+                Some(thread_id) => format!("Codex thread {thread_id}"),
+                None => format!("Codex changes on {}", worktree.branch),
+            });
+        run_git_command(&entry_path, &["add", "-A"]).await?;
+        run_git_command(&entry_path, &["commit", "-m", &message]).await?;
+    }
+
+    let parent_path = PathBuf::from(&parent.path);
+    if let Err(err) = run_git_command(
+        &parent_path,
+        &["merge", "--no-ff", "--no-edit", &worktree.branch],
+    )
+    .await
+    {
+        let _ = run_git_command(&parent_path, &["merge", "--abort"]).await;
+        return Err(format!("Merge failed: {err}"));
+    }
+
+    teardown_worktree(&state, &entry, &parent, Some("-d")).await
+}
+
+/// Throws away a thread worktree along with its branch and any changes.
+#[tauri::command]
+pub(crate) async fn discard_thread_worktree(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let (entry, parent) = worktree_with_parent(&state, &id).await?;
+    teardown_worktree(&state, &entry, &parent, Some("-D")).await
+}
+
 #[tauri::command]
 pub(crate) async fn update_workspace_settings(
     id: String,
@@ -545,21 +736,38 @@ pub(crate) async fn connect_workspace(
     };
     let codex_home = resolve_codex_home(&entry, parent_path.as_deref());
     let session = spawn_workspace_session(entry.clone(), default_bin, app, codex_home).await?;
-    state.sessions.lock().await.insert(entry.id, session);
+    let previous = state.sessions.lock().await.insert(entry.id, session);
+    if let Some(previous) = previous {
+        previous.terminate().await;
+    }
     Ok(())
 }
 
 #[tauri::command]
 pub(crate) async fn list_workspace_files(
     workspace_id: String,
+    query: Option<String>,
+    limit: Option<usize>,
     state: State<'_, AppState>,
 ) -> Result<Vec<String>, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?;
-    let root = PathBuf::from(&entry.path);
-    Ok(list_workspace_files_inner(&root, usize::MAX))
+    let root = {
+        let workspaces = state.workspaces.lock().await;
+        let entry = workspaces
+            .get(&workspace_id)
+            .ok_or("workspace not found")?;
+        PathBuf::from(&entry.path)
+    };
+    let files = state
+        .file_index
+        .get_or_build(&workspace_id, move || {
+            list_workspace_files_inner(&root, usize::MAX)
+        })
+        .await?;
+    Ok(search_files(
+        &files,
+        query.as_deref().unwrap_or(""),
+        limit.unwrap_or(usize::MAX),
+    ))
 }
 
 #[tauri::command]
@@ -585,7 +793,10 @@ mod tests {
     use std::collections::HashMap;
     use std::path::PathBuf;
 
-    use super::{apply_workspace_settings_update, sanitize_worktree_name, sort_workspaces};
+    use super::{
+        apply_workspace_settings_update, detect_workspace_languages, sanitize_worktree_name,
+        sort_workspaces, thread_branch_name,
+    };
     use crate::storage::{read_workspaces, write_workspaces};
     use crate::types::{WorktreeInfo, WorkspaceEntry, WorkspaceInfo, WorkspaceKind, WorkspaceSettings};
     use uuid::Uuid;
@@ -605,6 +816,7 @@ mod tests {
                 Some("parent".to_string()),
                 Some(WorktreeInfo {
                     branch: name.to_string(),
+                    thread_id: None,
                 }),
             )
         } else {
@@ -624,6 +836,9 @@ mod tests {
                 sort_order,
                 group_id: None,
                 git_root: None,
+                notifications_muted: false,
+                remote: None,
+                mcp_servers: Vec::new(),
             },
         }
     }
@@ -641,6 +856,15 @@ mod tests {
         assert_eq!(sanitize_worktree_name("feature--x"), "feature--x");
     }
 
+    #[test]
+    fn thread_branch_name_uses_short_sanitized_thread_id() {
+        assert_eq!(
+            thread_branch_name("thread-0199a3f2-7c1e-4d"),
+            "codex/thread-0199a3f2-7c1"
+        );
+        assert_eq!(thread_branch_name("abc/def"), "codex/thread-abc-def");
+    }
+
     #[test]
     fn sort_workspaces_orders_by_sort_then_name() {
         let mut items = vec![
@@ -758,4 +982,20 @@ mod tests {
         assert!(stored.settings.sidebar_collapsed);
         assert_eq!(stored.settings.git_root.as_deref(), Some("/tmp"));
     }
+
+    #[test]
+    fn detect_workspace_languages_reads_toolchain_markers() {
+        let temp_dir = std::env::temp_dir()
+            .join(format!("codex-monitor-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&temp_dir).expect("create temp dir");
+        assert!(detect_workspace_languages(&temp_dir).is_empty());
+
+        for marker in ["Cargo.toml", "package.json", "pyproject.toml", "requirements.txt"] {
+            std::fs::write(temp_dir.join(marker), "").expect("write marker");
+        }
+        assert_eq!(
+            detect_workspace_languages(&temp_dir),
+            vec!["rust".to_string(), "node".to_string(), "python".to_string()]
+        );
+    }
 }
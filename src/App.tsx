@@ -275,6 +275,7 @@ function MainApp() {
     createWorkspaceGroup,
     renameWorkspaceGroup,
     moveWorkspaceGroup,
+    setWorkspaceGroupColor,
     deleteWorkspaceGroup,
     assignWorkspaceGroup,
     removeWorkspace,
@@ -1257,6 +1258,7 @@ function MainApp() {
           onCreateWorkspaceGroup={createWorkspaceGroup}
           onRenameWorkspaceGroup={renameWorkspaceGroup}
           onMoveWorkspaceGroup={moveWorkspaceGroup}
+          onSetWorkspaceGroupColor={setWorkspaceGroupColor}
           onDeleteWorkspaceGroup={deleteWorkspaceGroup}
           onAssignWorkspaceGroup={assignWorkspaceGroup}
           reduceTransparency={reduceTransparency}
@@ -12,6 +12,7 @@ const COLLAPSED_GROUPS_STORAGE_KEY = "codexmonitor.collapsedGroups";
 type WorkspaceGroupSection = {
   id: string | null;
   name: string;
+  color?: string | null;
   workspaces: WorkspaceInfo[];
 };
 
@@ -398,7 +399,12 @@ export function Sidebar({
               >
                 {showGroupHeader && (
                   <div className="workspace-group-header">
-                    <div className="workspace-group-label">{group.name}</div>
+                    <div
+                      className="workspace-group-label"
+                      style={group.color ? { color: group.color } : undefined}
+                    >
+                      {group.name}
+                    </div>
                     {groupId && (
                       <button
                         className={`group-toggle ${
@@ -1,5 +1,9 @@
 import { useEffect } from "react";
-import type { AppServerEvent, ApprovalRequest } from "../../../types";
+import type {
+  AppServerEvent,
+  ApprovalRequest,
+  WorkspaceConnectionStatus,
+} from "../../../types";
 import { subscribeAppServerEvents } from "../../../services/events";
 
 type AgentDelta = {
@@ -18,6 +22,10 @@ type AgentCompleted = {
 
 type AppServerEventHandlers = {
   onWorkspaceConnected?: (workspaceId: string) => void;
+  onWorkspaceStatusChanged?: (
+    workspaceId: string,
+    status: WorkspaceConnectionStatus,
+  ) => void;
   onApprovalRequest?: (request: ApprovalRequest) => void;
   onAgentMessageDelta?: (event: AgentDelta) => void;
   onAgentMessageCompleted?: (event: AgentCompleted) => void;
@@ -69,6 +77,15 @@ export function useAppServerEvents(handlers: AppServerEventHandlers) {
         return;
       }
 
+      if (method === "workspace/statusChanged") {
+        const params = (message.params as Record<string, unknown>) ?? {};
+        handlers.onWorkspaceStatusChanged?.(
+          workspace_id,
+          String(params.status ?? "") as WorkspaceConnectionStatus,
+        );
+        return;
+      }
+
       if (method.includes("requestApproval") && typeof message.id === "number") {
         handlers.onApprovalRequest?.({
           workspace_id,
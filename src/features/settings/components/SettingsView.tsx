@@ -44,6 +44,7 @@ type SettingsViewProps = {
   onCreateWorkspaceGroup: (name: string) => Promise<WorkspaceGroup | null>;
   onRenameWorkspaceGroup: (id: string, name: string) => Promise<boolean | null>;
   onMoveWorkspaceGroup: (id: string, direction: "up" | "down") => Promise<boolean | null>;
+  onSetWorkspaceGroupColor: (id: string, color: string | null) => Promise<boolean | null>;
   onDeleteWorkspaceGroup: (id: string) => Promise<boolean | null>;
   onAssignWorkspaceGroup: (
     workspaceId: string,
@@ -78,6 +79,7 @@ export function SettingsView({
   onCreateWorkspaceGroup,
   onRenameWorkspaceGroup,
   onMoveWorkspaceGroup,
+  onSetWorkspaceGroupColor,
   onDeleteWorkspaceGroup,
   onAssignWorkspaceGroup,
   reduceTransparency,
@@ -458,6 +460,15 @@ export function SettingsView({
                             }}
                           />
                           <div className="settings-group-actions">
+                            <input
+                              type="color"
+                              className="settings-group-color"
+                              value={group.color ?? "#8a8f98"}
+                              onChange={(event) => {
+                                void onSetWorkspaceGroupColor(group.id, event.target.value);
+                              }}
+                              aria-label="Group color"
+                            />
                             <button
                               type="button"
                               className="ghost icon-button"
@@ -667,6 +678,27 @@ export function SettingsView({
                     <span className="settings-toggle-knob" />
                   </button>
                 </div>
+                <div className="settings-toggle-row">
+                  <div>
+                    <div className="settings-toggle-title">Desktop notifications</div>
+                    <div className="settings-toggle-subtitle">
+                      Show a system notification when a turn finishes, fails, or needs approval while the window is unfocused.
+                    </div>
+                  </div>
+                  <button
+                    type="button"
+                    className={`settings-toggle ${appSettings.systemNotificationsEnabled ? "on" : ""}`}
+                    onClick={() =>
+                      void onUpdateAppSettings({
+                        ...appSettings,
+                        systemNotificationsEnabled: !appSettings.systemNotificationsEnabled,
+                      })
+                    }
+                    aria-pressed={appSettings.systemNotificationsEnabled}
+                  >
+                    <span className="settings-toggle-knob" />
+                  </button>
+                </div>
                 <div className="settings-sound-actions">
                   <button
                     type="button"
@@ -960,6 +992,16 @@ export function SettingsView({
                       {doctorState.result.nodeDetails && (
                         <div>{doctorState.result.nodeDetails}</div>
                       )}
+                      {doctorState.result.checks
+                        .filter((check) => check.status === "fail" || check.status === "warn")
+                        .map((check) => (
+                          <div key={check.id} className={`settings-doctor-check ${check.status}`}>
+                            {check.label}: {check.detail ?? check.status}
+                            {check.hint && (
+                              <div className="settings-doctor-hint">{check.hint}</div>
+                            )}
+                          </div>
+                        ))}
                       {doctorState.result.path && (
                         <div className="settings-doctor-path">
                           PATH: {doctorState.result.path}
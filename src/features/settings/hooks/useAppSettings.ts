@@ -5,12 +5,15 @@ import { clampUiScale, UI_SCALE_DEFAULT } from "../../../utils/uiScale";
 
 const defaultSettings: AppSettings = {
   codexBin: null,
+  codexArgs: [],
+  verbosity: null,
   backendMode: "local",
   remoteBackendHost: "127.0.0.1:4732",
   remoteBackendToken: null,
   defaultAccessMode: "current",
   uiScale: UI_SCALE_DEFAULT,
   notificationSoundsEnabled: true,
+  systemNotificationsEnabled: true,
   experimentalCollabEnabled: false,
   experimentalSteerEnabled: false,
   experimentalUnifiedExecEnabled: false,
@@ -19,6 +22,10 @@ const defaultSettings: AppSettings = {
   dictationPreferredLanguage: null,
   dictationHoldKey: "alt",
   workspaceGroups: [],
+  requestTimeoutSecs: 120,
+  maxConcurrentRequests: 8,
+  promptTemplates: [],
+  approvalRules: [],
 };
 
 function normalizeAppSettings(settings: AppSettings): AppSettings {
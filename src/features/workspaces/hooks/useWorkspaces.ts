@@ -34,6 +34,7 @@ type UseWorkspacesOptions = {
 type WorkspaceGroupSection = {
   id: string | null;
   name: string;
+  color?: string | null;
   workspaces: WorkspaceInfo[];
 };
 
@@ -173,6 +174,7 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}) {
     const sections: WorkspaceGroupSection[] = workspaceGroups.map((group) => ({
       id: group.id,
       name: group.name,
+      color: group.color ?? null,
       workspaces: sortWorkspaces(buckets.get(group.id) ?? []),
     }));
 
@@ -480,6 +482,21 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}) {
     [appSettings, onUpdateAppSettings, updateWorkspaceGroups, workspaceGroups],
   );
 
+  const setWorkspaceGroupColor = useCallback(
+    async (groupId: string, color: string | null) => {
+      if (!appSettings || !onUpdateAppSettings) {
+        return null;
+      }
+      const currentGroups = appSettings.workspaceGroups ?? [];
+      const nextGroups = currentGroups.map((group) =>
+        group.id === groupId ? { ...group, color } : group,
+      );
+      await updateWorkspaceGroups(nextGroups);
+      return true;
+    },
+    [appSettings, onUpdateAppSettings, updateWorkspaceGroups],
+  );
+
   const deleteWorkspaceGroup = useCallback(
     async (groupId: string) => {
       if (!appSettings || !onUpdateAppSettings) {
@@ -648,6 +665,7 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}) {
     createWorkspaceGroup,
     renameWorkspaceGroup,
     moveWorkspaceGroup,
+    setWorkspaceGroupColor,
     deleteWorkspaceGroup,
     assignWorkspaceGroup,
     removeWorkspace,
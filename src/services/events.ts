@@ -1,5 +1,10 @@
 import { listen } from "@tauri-apps/api/event";
-import type { AppServerEvent, DictationEvent, DictationModelStatus } from "../types";
+import type {
+  AppServerEvent,
+  AutoApproval,
+  DictationEvent,
+  DictationModelStatus,
+} from "../types";
 
 export type Unsubscribe = () => void;
 
@@ -17,6 +22,14 @@ export async function subscribeAppServerEvents(
   });
 }
 
+export async function subscribeAutoApprovals(
+  onEvent: (event: AutoApproval) => void,
+): Promise<Unsubscribe> {
+  return listen<AutoApproval>("approval-auto-resolved", (event) => {
+    onEvent(event.payload);
+  });
+}
+
 export async function subscribeDictationDownload(
   onEvent: (event: DictationModelStatus) => void,
 ): Promise<Unsubscribe> {
@@ -1,21 +1,43 @@
 import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
 import type {
+  AppServerEvent,
   AppSettings,
+  ArchivedThreadSummary,
+  AutoApproval,
+  BackendCapabilities,
+  BackendLogEntry,
+  Broadcast,
   CodexDoctorResult,
   DictationModelStatus,
   DictationSessionState,
+  DiscoveredWorkspace,
+  LastThread,
+  McpResourceList,
+  McpResourceRef,
+  RemoteWorkspace,
+  RequestQueueMetrics,
+  ScheduledRun,
+  ServerRequest,
+  SessionStats,
+  ThreadExportFormat,
+  ThreadHistory,
+  ThreadSearchResult,
+  UsageRange,
+  UsageSummary,
   WorkspaceInfo,
   WorkspaceSettings,
 } from "../types";
 import type {
   GitFileDiff,
+  GitFileHunks,
   GitFileStatus,
   GitHubIssuesResponse,
   GitHubPullRequestDiff,
   GitHubPullRequestsResponse,
   GitLogResponse,
   ReviewTarget,
+  WorkspaceGitStatus,
 } from "../types";
 
 export async function pickWorkspacePath(): Promise<string | null> {
@@ -49,8 +71,26 @@ export async function listWorkspaces(): Promise<WorkspaceInfo[]> {
 export async function addWorkspace(
   path: string,
   codex_bin: string | null,
+  remote?: RemoteWorkspace | null,
 ): Promise<WorkspaceInfo> {
-  return invoke<WorkspaceInfo>("add_workspace", { path, codex_bin });
+  return invoke<WorkspaceInfo>("add_workspace", {
+    path,
+    codex_bin,
+    remote: remote ?? null,
+  });
+}
+
+export async function listMcpResources(
+  workspaceId: string,
+): Promise<McpResourceList> {
+  return invoke<McpResourceList>("list_mcp_resources", { workspaceId });
+}
+
+export async function discoverWorkspaces(
+  root: string,
+  depth?: number,
+): Promise<DiscoveredWorkspace[]> {
+  return invoke<DiscoveredWorkspace[]>("discover_workspaces", { root, depth });
 }
 
 export async function addWorktree(
@@ -82,6 +122,29 @@ export async function removeWorktree(id: string): Promise<void> {
   return invoke("remove_worktree", { id });
 }
 
+export async function createThreadWorktree(
+  parentId: string,
+  threadId: string,
+  branch?: string,
+): Promise<WorkspaceInfo> {
+  return invoke<WorkspaceInfo>("create_thread_worktree", {
+    parentId,
+    threadId,
+    branch,
+  });
+}
+
+export async function mergeThreadWorktree(
+  id: string,
+  message?: string,
+): Promise<void> {
+  return invoke("merge_thread_worktree", { id, message });
+}
+
+export async function discardThreadWorktree(id: string): Promise<void> {
+  return invoke("discard_thread_worktree", { id });
+}
+
 export async function openWorkspaceIn(path: string, app: string): Promise<void> {
   return invoke("open_workspace_in", { path, app });
 }
@@ -103,6 +166,8 @@ export async function sendUserMessage(
     effort?: string | null;
     accessMode?: "read-only" | "current" | "full-access";
     images?: string[];
+    attachments?: string[];
+    mcpResources?: McpResourceRef[];
     collaborationMode?: Record<string, unknown> | null;
   },
 ) {
@@ -114,10 +179,36 @@ export async function sendUserMessage(
     effort: options?.effort ?? null,
     accessMode: options?.accessMode ?? null,
     images: options?.images ?? null,
+    attachments: options?.attachments ?? null,
+    mcpResources: options?.mcpResources ?? null,
     collaborationMode: options?.collaborationMode ?? null,
   });
 }
 
+export async function interruptAll(workspaceId: string) {
+  return invoke<{
+    interrupted: { threadId: string; turnId: string }[];
+    errors: { threadId: string; turnId: string; error: string }[];
+  }>("interrupt_all", { workspaceId });
+}
+
+export async function interruptWorkspaceGroup(groupId: string) {
+  return invoke<{
+    results: Record<
+      string,
+      {
+        interrupted: { threadId: string; turnId: string }[];
+        errors: { threadId: string; turnId: string; error: string }[];
+      }
+    >;
+    notConnected: string[];
+  }>("interrupt_workspace_group", { groupId });
+}
+
+export async function killBackend(workspaceId: string) {
+  return invoke("kill_backend", { workspaceId });
+}
+
 export async function interruptTurn(
   workspaceId: string,
   threadId: string,
@@ -151,6 +242,12 @@ export async function respondToServerRequest(
   });
 }
 
+export async function listServerRequests(
+  workspaceId: string,
+): Promise<ServerRequest[]> {
+  return invoke<ServerRequest[]>("list_server_requests", { workspaceId });
+}
+
 export async function getGitStatus(workspace_id: string): Promise<{
   branchName: string;
   files: GitFileStatus[];
@@ -173,6 +270,19 @@ export async function getGitDiffs(
   return invoke("get_git_diffs", { workspaceId: workspace_id });
 }
 
+export async function getWorkspaceGitStatus(
+  workspace_id: string,
+): Promise<WorkspaceGitStatus> {
+  return invoke("workspace_git_status", { workspaceId: workspace_id });
+}
+
+export async function getWorkspaceGitDiff(
+  workspace_id: string,
+  path?: string,
+): Promise<GitFileHunks[]> {
+  return invoke("workspace_git_diff", { workspaceId: workspace_id, path });
+}
+
 export async function getGitLog(
   workspace_id: string,
   limit = 40,
@@ -222,10 +332,148 @@ export async function getSkillsList(workspaceId: string) {
   return invoke<any>("skills_list", { workspaceId });
 }
 
+export async function searchThreads(
+  query: string,
+  limit?: number,
+): Promise<ThreadSearchResult[]> {
+  return invoke<ThreadSearchResult[]>("search_threads", { query, limit });
+}
+
+export async function exportThread(
+  workspaceId: string,
+  threadId: string,
+  format: ThreadExportFormat,
+  path?: string,
+): Promise<string> {
+  return invoke<string>("export_thread", { workspaceId, threadId, format, path });
+}
+
+export async function listArchivedThreads(
+  query?: string | null,
+): Promise<ArchivedThreadSummary[]> {
+  return invoke<ArchivedThreadSummary[]>("list_archived_threads", {
+    query: query ?? null,
+  });
+}
+
+export async function exportArchivedThread(
+  threadId: string,
+  format: ThreadExportFormat,
+  path?: string,
+): Promise<string> {
+  return invoke<string>("export_archived_thread", { threadId, format, path });
+}
+
+export async function deleteArchivedThread(threadId: string) {
+  return invoke("delete_archived_thread", { threadId });
+}
+
+export async function listAutoApprovals(): Promise<AutoApproval[]> {
+  return invoke<AutoApproval[]>("list_auto_approvals");
+}
+
+export async function getTurnHistory(
+  threadId: string,
+): Promise<ThreadHistory | null> {
+  return invoke<ThreadHistory | null>("get_turn_history", { threadId });
+}
+
+export async function broadcastTurn(
+  workspaceIds: string[],
+  text: string,
+  model?: string | null,
+): Promise<Broadcast> {
+  return invoke<Broadcast>("broadcast_turn", {
+    workspaceIds,
+    text,
+    model: model ?? null,
+  });
+}
+
+export async function listBroadcasts(): Promise<Broadcast[]> {
+  return invoke<Broadcast[]>("list_broadcasts");
+}
+
+export async function listSchedules(): Promise<ScheduledRun[]> {
+  return invoke<ScheduledRun[]>("list_schedules");
+}
+
+export async function saveSchedule(schedule: ScheduledRun): Promise<ScheduledRun> {
+  return invoke<ScheduledRun>("save_schedule", { schedule });
+}
+
+export async function deleteSchedule(id: string): Promise<void> {
+  return invoke("delete_schedule", { id });
+}
+
+export async function runScheduleNow(id: string): Promise<void> {
+  return invoke("run_schedule_now", { id });
+}
+
+export async function getUsageSummary(
+  range: UsageRange = "30d",
+): Promise<UsageSummary> {
+  return invoke<UsageSummary>("usage_summary", { range });
+}
+
+export async function getBackendLogs(
+  workspaceId: string,
+  lines?: number,
+): Promise<BackendLogEntry[]> {
+  return invoke<BackendLogEntry[]>("get_backend_logs", { workspaceId, lines });
+}
+
+export async function getRequestQueueMetrics(
+  workspaceId: string,
+): Promise<RequestQueueMetrics> {
+  return invoke<RequestQueueMetrics>("get_request_queue_metrics", { workspaceId });
+}
+
+export async function getSessionStats(workspaceId: string): Promise<SessionStats> {
+  return invoke<SessionStats>("get_session_stats", { workspaceId });
+}
+
+export async function getBackendCapabilities(
+  workspaceId: string,
+): Promise<BackendCapabilities> {
+  return invoke<BackendCapabilities>("get_backend_capabilities", { workspaceId });
+}
+
+export async function replayEvents(
+  workspaceId: string,
+  sinceSeq: number,
+): Promise<AppServerEvent[]> {
+  return invoke<AppServerEvent[]>("replay_events", { workspaceId, sinceSeq });
+}
+
+export async function exportBackendLogs(workspaceId: string, path: string) {
+  return invoke("export_backend_logs", { workspaceId, path });
+}
+
 export async function getPromptsList(workspaceId: string) {
   return invoke<any>("prompts_list", { workspaceId });
 }
 
+export async function sendTemplateMessage(
+  workspaceId: string,
+  templateId: string,
+  vars: Record<string, string>,
+  options?: {
+    threadId?: string | null;
+    model?: string | null;
+    effort?: string | null;
+  },
+) {
+  return invoke<{ threadId: string; response: any }>("send_template_message", {
+    workspaceId,
+    templateId,
+    vars,
+    threadId: options?.threadId ?? null,
+    model: options?.model ?? null,
+    effort: options?.effort ?? null,
+  });
+}
+
 export async function getAppSettings(): Promise<AppSettings> {
   return invoke<AppSettings>("get_app_settings");
 }
@@ -236,12 +484,20 @@ export async function updateAppSettings(settings: AppSettings): Promise<AppSetti
 
 export async function runCodexDoctor(
   codexBin: string | null,
+  codexArgs?: string[] | null,
 ): Promise<CodexDoctorResult> {
-  return invoke<CodexDoctorResult>("codex_doctor", { codexBin });
+  return invoke<CodexDoctorResult>("codex_doctor", {
+    codexBin,
+    codexArgs: codexArgs ?? null,
+  });
 }
 
-export async function getWorkspaceFiles(workspaceId: string) {
-  return invoke<string[]>("list_workspace_files", { workspaceId });
+export async function getWorkspaceFiles(
+  workspaceId: string,
+  query?: string,
+  limit?: number,
+) {
+  return invoke<string[]>("list_workspace_files", { workspaceId, query, limit });
 }
 
 export async function listGitBranches(workspaceId: string) {
@@ -355,6 +611,18 @@ export async function resumeThread(workspaceId: string, threadId: string) {
   return invoke<any>("resume_thread", { workspaceId, threadId });
 }
 
+export async function getLastThread(
+  workspaceId: string,
+): Promise<LastThread | null> {
+  return invoke<LastThread | null>("get_last_thread", { workspaceId });
+}
+
+export async function resumeLastThread(workspaceId: string) {
+  return invoke<{ threadId: string; response: any }>("resume_last_thread", {
+    workspaceId,
+  });
+}
+
 export async function archiveThread(workspaceId: string, threadId: string) {
   return invoke<any>("archive_thread", { workspaceId, threadId });
 }
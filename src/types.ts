@@ -3,18 +3,63 @@ export type WorkspaceSettings = {
   sortOrder?: number | null;
   groupId?: string | null;
   gitRoot?: string | null;
+  notificationsMuted?: boolean;
+  remote?: RemoteWorkspace | null;
+  mcpServers?: McpServerConfig[];
+};
+
+export type McpServerConfig = {
+  id: string;
+  name: string;
+  command: string;
+  args?: string[];
+  env?: Record<string, string>;
+};
+
+export type McpResource = {
+  serverId: string;
+  serverName: string;
+  uri: string;
+  name: string;
+  description: string | null;
+  mimeType: string | null;
+};
+
+export type McpResourceRef = {
+  serverId: string;
+  uri: string;
+};
+
+export type McpResourceList = {
+  resources: McpResource[];
+  errors: { serverId: string; error: string }[];
+};
+
+export type RemoteWorkspace = {
+  host: string;
+  path: string;
+  sshArgs?: string[];
 };
 
 export type WorkspaceGroup = {
   id: string;
   name: string;
   sortOrder?: number | null;
+  color?: string | null;
 };
 
 export type WorkspaceKind = "main" | "worktree";
 
 export type WorktreeInfo = {
   branch: string;
+  threadId?: string | null;
+};
+
+export type DiscoveredWorkspace = {
+  name: string;
+  path: string;
+  languages: string[];
+  alreadyAdded: boolean;
 };
 
 export type WorkspaceInfo = {
@@ -29,8 +74,15 @@ export type WorkspaceInfo = {
   settings: WorkspaceSettings;
 };
 
+export type WorkspaceConnectionStatus =
+  | "starting"
+  | "connected"
+  | "unresponsive"
+  | "dead";
+
 export type AppServerEvent = {
   workspace_id: string;
+  seq: number;
   message: Record<string, unknown>;
 };
 
@@ -62,6 +114,98 @@ export type ThreadSummary = {
   updatedAt: number;
 };
 
+export type ScheduledRunStatus = "running" | "completed" | "failed";
+
+export type ScheduledRun = {
+  id: string;
+  workspaceId: string;
+  name: string;
+  prompt: string;
+  schedule: string;
+  enabled: boolean;
+  model?: string | null;
+  lastRunAt?: number | null;
+  lastThreadId?: string | null;
+  lastStatus?: ScheduledRunStatus | null;
+  lastError?: string | null;
+};
+
+export type LastThread = {
+  threadId: string;
+  updatedAt: number;
+};
+
+export type BroadcastTarget = {
+  workspaceId: string;
+  threadId: string | null;
+  status: ScheduledRunStatus;
+  error: string | null;
+};
+
+export type Broadcast = {
+  id: string;
+  text: string;
+  model: string | null;
+  startedAt: number;
+  completedAt: number | null;
+  targets: BroadcastTarget[];
+};
+
+export type UsageTotals = {
+  inputTokens: number;
+  cachedInputTokens: number;
+  outputTokens: number;
+  reasoningOutputTokens: number;
+  totalTokens: number;
+  requests: number;
+};
+
+export type UsageBucket = UsageTotals & { key: string };
+
+export type UsageRange = "today" | "7d" | "30d" | "90d" | "all";
+
+export type UsageSummary = {
+  range: UsageRange;
+  since: string | null;
+  totals: UsageTotals;
+  byDay: UsageBucket[];
+  byWorkspace: UsageBucket[];
+  byModel: UsageBucket[];
+};
+
+export type ThreadExportFormat = "markdown" | "html" | "json";
+
+export type ArchivedThreadSummary = {
+  workspaceId: string;
+  threadId: string;
+  preview: string;
+  archivedAt: number;
+};
+
+export type TurnRecord = {
+  turnId: string;
+  status: string;
+  startedAt: number;
+  completedAt: number | null;
+  error: string | null;
+  items: Record<string, unknown>[];
+};
+
+export type ThreadHistory = {
+  workspaceId: string;
+  threadId: string;
+  turns: TurnRecord[];
+};
+
+export type ThreadSearchResult = {
+  workspaceId: string;
+  threadId: string;
+  preview: string;
+  snippet: string;
+  score: number;
+  updatedAt: number;
+};
+
 export type ReviewTarget =
   | { type: "uncommittedChanges" }
   | { type: "baseBranch"; branch: string }
@@ -73,12 +217,15 @@ export type BackendMode = "local" | "remote";
 
 export type AppSettings = {
   codexBin: string | null;
+  codexArgs: string[];
+  verbosity: "low" | "medium" | "high" | null;
   backendMode: BackendMode;
   remoteBackendHost: string;
   remoteBackendToken: string | null;
   defaultAccessMode: AccessMode;
   uiScale: number;
   notificationSoundsEnabled: boolean;
+  systemNotificationsEnabled: boolean;
   experimentalCollabEnabled: boolean;
   experimentalSteerEnabled: boolean;
   experimentalUnifiedExecEnabled: boolean;
@@ -87,6 +234,49 @@ export type AppSettings = {
   dictationPreferredLanguage: string | null;
   dictationHoldKey: string | null;
   workspaceGroups: WorkspaceGroup[];
+  requestTimeoutSecs: number;
+  maxConcurrentRequests: number;
+  promptTemplates: PromptTemplate[];
+  approvalRules: ApprovalRule[];
+};
+
+export type PromptTemplate = {
+  id: string;
+  name: string;
+  body: string;
+  description?: string | null;
+};
+
+export type ApprovalDecision = "accept" | "decline";
+
+export type ApprovalRule = {
+  id: string;
+  name?: string;
+  decision: ApprovalDecision;
+  enabled?: boolean;
+  tool?: string | null;
+  commandPrefix?: string | null;
+  pathGlob?: string | null;
+};
+
+export type AutoApproval = {
+  workspaceId: string;
+  requestId: number;
+  method: string;
+  ruleId: string;
+  decision: ApprovalDecision;
+  command: string | null;
+  at: number;
+};
+
+export type DoctorCheckStatus = "pass" | "warn" | "fail" | "skip";
+
+export type DoctorCheck = {
+  id: string;
+  label: string;
+  status: DoctorCheckStatus;
+  detail: string | null;
+  hint: string | null;
 };
 
 export type CodexDoctorResult = {
@@ -99,6 +289,39 @@ export type CodexDoctorResult = {
   nodeOk: boolean;
   nodeVersion: string | null;
   nodeDetails: string | null;
+  argsOk: boolean;
+  argsDetails: string | null;
+  checks: DoctorCheck[];
+};
+
+export type RequestQueueMetrics = {
+  limit: number;
+  inFlight: number;
+  queued: number;
+  peakQueued: number;
+  dispatched: number;
+};
+
+export type SessionStats = {
+  pid: number | null;
+  cpuPercent: number | null;
+  rssBytes: number | null;
+  uptimeSecs: number;
+  restartCount: number;
+  status: WorkspaceConnectionStatus;
+};
+
+export type BackendCapabilities = {
+  userAgent: string | null;
+  methods: string[] | null;
+  unsupported: string[];
+  initialize: Record<string, unknown> | null;
+};
+
+export type BackendLogEntry = {
+  timestamp: number;
+  stream: "stdout" | "stderr";
+  line: string;
 };
 
 export type ApprovalRequest = {
@@ -108,6 +331,27 @@ export type ApprovalRequest = {
   params: Record<string, unknown>;
 };
 
+export type ServerRequest = {
+  id: number;
+  method: string;
+  threadId: string | null;
+  receivedAt: number;
+} & (
+  | {
+      kind: "commandApproval";
+      command: string | null;
+      cwd: string | null;
+      reason: string | null;
+    }
+  | {
+      kind: "fileChangeApproval";
+      paths: string[];
+      reason: string | null;
+      grantRoot: string | null;
+    }
+  | { kind: "other" }
+);
+
 export type GitFileStatus = {
   path: string;
   status: string;
@@ -120,6 +364,45 @@ export type GitFileDiff = {
   diff: string;
 };
 
+export type GitChangedFile = {
+  path: string;
+  oldPath: string | null;
+  status: string;
+  staged: boolean;
+  unstaged: boolean;
+  additions: number;
+  deletions: number;
+};
+
+export type WorkspaceGitStatus = {
+  branchName: string;
+  files: GitChangedFile[];
+};
+
+export type GitDiffLine = {
+  kind: "context" | "add" | "delete";
+  content: string;
+  oldLine: number | null;
+  newLine: number | null;
+};
+
+export type GitDiffHunk = {
+  header: string;
+  oldStart: number;
+  oldLines: number;
+  newStart: number;
+  newLines: number;
+  lines: GitDiffLine[];
+};
+
+export type GitFileHunks = {
+  path: string;
+  oldPath: string | null;
+  status: string;
+  binary: boolean;
+  hunks: GitDiffHunk[];
+};
+
 export type GitLogEntry = {
   sha: string;
   summary: string;